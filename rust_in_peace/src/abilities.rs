@@ -0,0 +1,168 @@
+//! Special moves enemies pull out during a fight. Each move has a chance of
+//! being used on the enemy's turn, and the world file decides which enemy
+//! knows which moves.
+use super::{Outcome, StatusEffect, World, LOC_PLAYER};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// What a special move does when it is used
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Ability {
+    /// A strike that leaves the target bleeding for some turns
+    Maul { bleed: u64, turns: u64 },
+    /// A strike that puts an effect on the target, stacking with any dose of
+    /// it they already have
+    Afflict(StatusEffect),
+    /// The enemy heals at the start of its turn and still gets to attack
+    Regenerate(u64),
+    /// Damage split evenly between every member of the party, each of whom
+    /// can dodge their share
+    Volley(u64),
+}
+
+/// A special move an enemy knows, with the chance in percent of it being used
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpecialMove {
+    pub text: String,
+    pub chance: u64,
+    pub ability: Ability,
+}
+
+impl World {
+    /// Rolls whether an enemy heals itself this round. Returns what the player
+    /// sees and a line for the round summary.
+    pub(crate) fn regenerate(&mut self, enemy: usize) -> Option<(String, String)> {
+        let (text, amount) =
+            self.objects[enemy]
+                .specials
+                .iter()
+                .find_map(|special| match special.ability {
+                    Ability::Regenerate(amount) if self.special_used(special) => {
+                        Some((special.text.clone(), amount))
+                    }
+                    _ => None,
+                })?;
+
+        let healed = self.restore_health(enemy, amount);
+        if healed == 0 {
+            return None;
+        }
+        let name = &self.objects[enemy].label[0];
+        Some((
+            format!(
+                "\n\n{}\n{} health: {}\n",
+                text,
+                name,
+                self.objects[enemy].health.unwrap_or(0)
+            ),
+            format!("- The {} healed {}.\n", name, healed),
+        ))
+    }
+
+    /// Rolls whether an enemy attacks with a special move instead of a plain
+    /// strike. Returns what the player sees and a line for the round summary.
+    pub(crate) fn special_attack(&mut self, enemy: usize) -> Option<(String, String)> {
+        let special = self.objects[enemy]
+            .specials
+            .iter()
+            .filter(|special| !matches!(special.ability, Ability::Regenerate(_)))
+            .find(|special| self.special_used(special))?
+            .clone();
+        let name = self.objects[enemy].label[0].clone();
+
+        match special.ability {
+            Ability::Maul { bleed, turns } => {
+                let target = self.select_target(enemy);
+                let result = self.resolve_damage(enemy, None, target);
+                let mut output =
+                    format!("\n\n{}", special.text) + &self.describe_blow(target, &result);
+                let mut summary = self.summarize_strike(enemy, target, &result);
+                if result.damage > 0 && result.health > 0 {
+                    self.add_effect(target, StatusEffect::bleeding(bleed, turns));
+                    output += &self.describe_affliction(target, "bleeding");
+                    output += &self.infect(enemy, target);
+                    summary = summary.replacen(" hit ", " mauled ", 1);
+                }
+                Some((output, summary))
+            }
+            Ability::Afflict(effect) => {
+                let target = self.select_target(enemy);
+                let result = self.resolve_damage(enemy, None, target);
+                let mut output =
+                    format!("\n\n{}", special.text) + &self.describe_blow(target, &result);
+                let summary = self.summarize_strike(enemy, target, &result);
+                if result.damage > 0 && result.health > 0 {
+                    output += &self.describe_affliction(target, &effect.name.to_lowercase());
+                    self.stack_effect(target, effect);
+                }
+                Some((output, summary))
+            }
+            Ability::Volley(damage) => {
+                let targets: Vec<usize> = self
+                    .party()
+                    .into_iter()
+                    .filter(|member| self.objects[*member].health.unwrap_or(0) > 0)
+                    .collect();
+                let share = (damage / targets.len().max(1) as u64).max(1);
+                let mut output = format!("\n\n{}\n", special.text);
+                let mut total = 0;
+                for target in targets.iter().copied() {
+                    let outcome = self.roll_attack(enemy, None, target);
+                    if outcome == Outcome::Miss {
+                        self.log_blow(name.clone(), outcome.verb(), target, 0);
+                        output += &match target {
+                            LOC_PLAYER => "You dodged the attack\n".to_string(),
+                            _ => {
+                                format!("The {} dodged the attack\n", self.objects[target].label[0])
+                            }
+                        };
+                        continue;
+                    }
+                    let mut dealt = World::adjust_damage(
+                        outcome.damage(share),
+                        self.attack_bonus(enemy) - self.defense_bonus(target),
+                    );
+                    if self.guarding(target) {
+                        dealt /= 2;
+                    }
+                    total += dealt;
+                    let health = self.objects[target]
+                        .health
+                        .unwrap_or(0)
+                        .saturating_sub(dealt);
+                    self.objects[target].health = Some(health);
+                    self.log_blow(name.clone(), "volleyed", target, dealt);
+                    output += &if target == LOC_PLAYER {
+                        format!("You got hit for {}\nYour health: {}\n", dealt, health)
+                    } else {
+                        let member = &self.objects[target].label[0];
+                        format!(
+                            "The {} got hit for {}\n{} health: {}\n",
+                            member, dealt, member, health
+                        )
+                    };
+                }
+                let summary = match total {
+                    0 => format!("- The {} missed the whole party.\n", name),
+                    _ => format!("- The {} hit the party for {} in all.\n", name, total),
+                };
+                Some((output, summary))
+            }
+            Ability::Regenerate(_) => None,
+        }
+    }
+
+    /// Tells the player what state a special move left a member of the party in
+    fn describe_affliction(&self, target: usize, state: &str) -> String {
+        if target == LOC_PLAYER {
+            format!("You are {}.\n", state)
+        } else {
+            format!("The {} is {}.\n", self.objects[target].label[0], state)
+        }
+    }
+
+    /// Rolls whether an enemy uses a special move this round
+    fn special_used(&self, special: &SpecialMove) -> bool {
+        self.rng().gen_range(1..=100) <= special.chance
+    }
+}
@@ -0,0 +1,54 @@
+//! Shorthand players make up for themselves, such as "atk" for "attack bandits".
+//! Aliases are kept with the rest of the world, so they carry over in the save.
+use super::{parse, Command, World};
+
+impl World {
+    /// Makes, shows or removes an alias. "alias atk = attack bandits" makes
+    /// one, "alias atk =" removes it and "alias" on its own lists them all.
+    pub fn do_alias(&mut self, noun: &str) -> String {
+        if noun.is_empty() {
+            if self.aliases.is_empty() {
+                return "You haven't made any aliases yet.\n".to_string();
+            }
+            return self
+                .aliases
+                .iter()
+                .map(|(name, expansion)| format!("{} = {}\n", name, expansion))
+                .collect();
+        }
+
+        let Some((name, expansion)) = noun.split_once('=') else {
+            return match self.aliases.get(noun) {
+                Some(expansion) => format!("{} = {}\n", noun, expansion),
+                None => "Usage: alias <name> = <command>\n".to_string(),
+            };
+        };
+        let (name, expansion) = (name.trim(), expansion.trim());
+        if name.is_empty() || name.contains(' ') {
+            return "An alias has to be a single word.\n".to_string();
+        }
+        if expansion.is_empty() {
+            return match self.aliases.remove(name) {
+                Some(_) => format!("Removed the alias '{}'.\n", name),
+                None => format!("There is no alias '{}'.\n", name),
+            };
+        }
+        if !matches!(parse(name.to_string()), Command::Unknown(_)) {
+            return format!("'{}' is already a command.\n", name);
+        }
+
+        self.aliases.insert(name.to_string(), expansion.to_string());
+        format!("'{}' now means '{}'.\n", name, expansion)
+    }
+
+    /// Rewrites a command starting with one of the player's aliases. Returns
+    /// None if it doesn't start with one.
+    pub(crate) fn expand_alias(&self, command: &Command) -> Option<Command> {
+        let Command::Unknown(input) = command else {
+            return None;
+        };
+        let (name, rest) = input.split_once(' ').unwrap_or((input, ""));
+        let expansion = self.aliases.get(name)?;
+        Some(parse(format!("{} {}", expansion, rest)))
+    }
+}
@@ -0,0 +1,62 @@
+//! Armor the player can wear to take the edge off the blows they receive.
+use super::{World, LOC_PLAYER};
+
+impl World {
+    /// Returns the armor the player is wearing, as long as they still carry it
+    pub fn worn_armor(&self) -> Option<usize> {
+        self.wearing
+            .filter(|index| self.is_containing(Some(LOC_PLAYER), Some(*index)))
+    }
+
+    /// Returns how much damage the player's armor takes off each blow, half
+    /// as much once it has worn out
+    pub fn armor_defense(&self) -> u64 {
+        match self.worn_armor() {
+            Some(index) if self.objects[index].is_worn_out() => {
+                self.objects[index].armor.unwrap_or(0) / 2
+            }
+            Some(index) => self.objects[index].armor.unwrap_or(0),
+            None => 0,
+        }
+    }
+
+    /// Player puts on armor they carry, taking off whatever they wore before
+    pub fn do_wear(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+
+        if self.objects[index].armor.is_none() {
+            format!("You can't wear the {}.\n", name)
+        } else if !self.is_containing(Some(LOC_PLAYER), Some(index)) {
+            format!("You are not holding any {}.\n", name)
+        } else if self.worn_armor() == Some(index) {
+            format!("You are already wearing the {}.\n", name)
+        } else {
+            let output = match self.worn_armor() {
+                Some(old) => format!("You take off the {}. ", self.objects[old].label[0]),
+                None => String::new(),
+            };
+            self.wearing = Some(index);
+            output
+                + &format!(
+                    "You put on the {}. It will take {} off every blow you receive.\n",
+                    name,
+                    self.armor_defense()
+                )
+        }
+    }
+
+    /// Player takes off the armor they are wearing
+    pub fn do_take_off(&mut self) -> String {
+        match self.worn_armor() {
+            Some(index) => {
+                self.wearing = None;
+                format!("You take off the {}.\n", self.objects[index].label[0])
+            }
+            None => "You are not wearing any armor.\n".to_string(),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! The player's core attributes. Strength adds to the damage they deal and the
+//! weight they can carry, agility to their chance of dodging, and intellect to
+//! checks like persuading or interrogating someone. Points to raise them are
+//! handed out at the start of the game and every time the player levels up,
+//! along with a perk to pick.
+use super::{World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// An attribute at this score neither helps nor hinders
+const BASE_ATTRIBUTE: u64 = 10;
+
+// Experience it takes to reach each next level
+const LEVEL_EXPERIENCE: u64 = 100;
+
+// Attribute points gained with every level
+const POINTS_PER_LEVEL: u64 = 2;
+
+// Chance in percent of dodging an attack for every point of agility bonus
+const DODGE_PER_AGILITY: i64 = 3;
+
+/// One of the player's core attributes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Attribute {
+    Strength,
+    Agility,
+    Intellect,
+}
+
+impl Attribute {
+    const ALL: [Attribute; 3] = [
+        Attribute::Strength,
+        Attribute::Agility,
+        Attribute::Intellect,
+    ];
+
+    /// Finds an attribute by name
+    pub fn named(name: &str) -> Option<Attribute> {
+        Attribute::ALL
+            .into_iter()
+            .find(|attribute| attribute.to_string() == name.to_lowercase())
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl World {
+    /// Returns the player's score in an attribute
+    pub fn attribute(&self, attribute: Attribute) -> u64 {
+        let player = &self.objects[LOC_PLAYER];
+        match attribute {
+            Attribute::Strength => player.strength,
+            Attribute::Agility => player.agility,
+            Attribute::Intellect => player.intellect,
+        }
+        .unwrap_or(BASE_ATTRIBUTE)
+    }
+
+    /// Returns what an attribute adds to the rolls it feeds into, which is
+    /// negative for a score below the base
+    pub fn attribute_bonus(&self, attribute: Attribute) -> i64 {
+        (self.attribute(attribute) as i64 - BASE_ATTRIBUTE as i64) / 2
+            - self.survival_penalty(attribute)
+    }
+
+    /// Returns how much the player's agility adds to their chance of dodging
+    pub(crate) fn agility_dodge(&self) -> i64 {
+        self.attribute_bonus(Attribute::Agility) * DODGE_PER_AGILITY
+    }
+
+    /// Returns the level the player's experience has brought them to
+    pub fn level(&self) -> u64 {
+        1 + self.experience / LEVEL_EXPERIENCE
+    }
+
+    /// Gives the player experience, with attribute points for every level it
+    /// takes them up
+    pub fn gain_experience(&mut self, amount: u64) -> String {
+        let before = self.level();
+        self.experience += amount;
+        let levels = self.level() - before;
+        if levels == 0 {
+            return String::new();
+        }
+        self.attribute_points += levels * POINTS_PER_LEVEL;
+        self.perk_points += levels;
+        self.record(format!("player reached level {}", self.level()));
+        format!(
+            "You reached level {}! You have {} attribute points to spend. Try <raise strength>.\n\
+             You can pick a perk too. Enter <perks> to see them.\n",
+            self.level(),
+            self.attribute_points
+        )
+    }
+
+    /// Player spends a point to raise an attribute
+    pub fn do_raise(&mut self, noun: &str) -> String {
+        let Some(attribute) = Attribute::named(noun) else {
+            return "Raise which attribute? Try strength, agility or intellect.\n".to_string();
+        };
+        if self.attribute_points == 0 {
+            return "You have no attribute points to spend.\n".to_string();
+        }
+        self.attribute_points -= 1;
+        let score = self.attribute(attribute) + 1;
+        let player = &mut self.objects[LOC_PLAYER];
+        match attribute {
+            Attribute::Strength => player.strength = Some(score),
+            Attribute::Agility => player.agility = Some(score),
+            Attribute::Intellect => player.intellect = Some(score),
+        }
+        format!(
+            "Your {} rises to {}. You have {} attribute points left.\n",
+            attribute, score, self.attribute_points
+        )
+    }
+
+    /// Lists the player's attributes and what each adds to their rolls
+    pub(crate) fn describe_attributes(&self) -> String {
+        Attribute::ALL
+            .into_iter()
+            .map(|attribute| {
+                format!(
+                    "{}: {} ({:+})\n",
+                    attribute,
+                    self.attribute(attribute),
+                    self.attribute_bonus(attribute)
+                )
+            })
+            .collect()
+    }
+
+    /// Describes the player's level and how far they are from the next
+    pub(crate) fn describe_level(&self) -> String {
+        format!(
+            "Level {}, {}/{} experience to the next.\n",
+            self.level(),
+            self.experience % LEVEL_EXPERIENCE,
+            LEVEL_EXPERIENCE
+        )
+    }
+
+    /// Reminds the player of the attribute points they have left to spend
+    pub(crate) fn describe_attribute_points(&self) -> String {
+        match self.attribute_points {
+            0 => String::new(),
+            points => format!(
+                "You have {} attribute points to spend. Try <raise strength>.\n",
+                points
+            ),
+        }
+    }
+
+    /// Shows the player's attributes, level and points left to spend
+    pub fn do_attributes(&self) -> String {
+        self.describe_attributes() + &self.describe_level() + &self.describe_attribute_points()
+    }
+}
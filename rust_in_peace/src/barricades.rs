@@ -0,0 +1,104 @@
+//! Barricades the player builds across an exit to hold off enemies following
+//! them, until the enemies batter their way through or the barricade rots.
+use super::{World, LOC_PLAYER};
+
+impl World {
+    /// Finds the passage leading back the way a passage came
+    pub fn reverse_passage(&self, passage: usize) -> Option<usize> {
+        let (from, to) = (
+            self.objects[passage].location?,
+            self.objects[passage].destination?,
+        );
+        self.exits(to)
+            .into_iter()
+            .find(|back| self.objects[*back].destination == Some(from))
+    }
+
+    /// Sets how long a passage and its way back stay blocked
+    fn set_barricade(&mut self, passage: usize, turns: u64) {
+        self.objects[passage].barricaded = turns;
+        if let Some(back) = self.reverse_passage(passage) {
+            self.objects[back].barricaded = turns;
+        }
+    }
+
+    /// Player blocks an exit from their location with a material they carry
+    pub fn do_barricade(&mut self, noun: &String) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let passage = self
+            .exits(player_loc.unwrap_or_default())
+            .into_iter()
+            .find(|passage| {
+                self.objects[*passage]
+                    .label
+                    .iter()
+                    .any(|label| label.to_lowercase() == noun.to_lowercase())
+            });
+        let Some(passage) = passage else {
+            return format!("There is no way {} to barricade.\n", noun);
+        };
+        let name = self.objects[passage].label[0].to_lowercase();
+        let material = (0..self.objects.len()).find(|index| {
+            self.objects[*index].barricade.is_some()
+                && !self.objects[*index].key_item
+                && self.is_containing(Some(LOC_PLAYER), Some(*index))
+        });
+
+        match material {
+            _ if self.objects[passage].barricaded > 0 => {
+                format!("The way {} is already barricaded.\n", name)
+            }
+            None => format!("You have nothing to barricade the way {} with.\n", name),
+            Some(material) => {
+                let turns = self.objects[material].barricade.unwrap_or(0);
+                self.objects[material].location = None;
+                self.set_barricade(passage, turns);
+                format!(
+                    "You barricade the way {} with the {}. It should hold for a while.\n",
+                    name, self.objects[material].label[0]
+                )
+            }
+        }
+    }
+
+    /// Player pulls down a barricade standing in their way
+    pub(crate) fn tear_down(&mut self, passage: usize) -> String {
+        self.set_barricade(passage, 0);
+        format!(
+            "You tear down the barricade blocking the way {}.\n",
+            self.objects[passage].label[0].to_lowercase()
+        )
+    }
+
+    /// An enemy wears down a barricade between it and the player
+    pub(crate) fn batter(&mut self, enemy: usize, passage: usize) -> String {
+        let turns = self.objects[passage].barricaded.saturating_sub(1);
+        self.set_barricade(passage, turns);
+        let side = self.reverse_passage(passage).unwrap_or(passage);
+        format!(
+            "\nYou hear the {} battering at the barricade to the {}.\n",
+            self.objects[enemy].label[0],
+            self.objects[side].label[0].to_lowercase()
+        )
+    }
+
+    /// Barricades slowly fall apart on their own
+    pub(crate) fn run_barricades(&mut self) -> String {
+        let mut output = String::new();
+        let player_loc = self.objects[LOC_PLAYER].location;
+
+        for index in 0..self.objects.len() {
+            if self.objects[index].barricaded == 0 {
+                continue;
+            }
+            self.objects[index].barricaded -= 1;
+            if self.objects[index].barricaded == 0 && self.objects[index].location == player_loc {
+                output += &format!(
+                    "\nThe barricade to the {} gives way.\n",
+                    self.objects[index].label[0].to_lowercase()
+                );
+            }
+        }
+        output
+    }
+}
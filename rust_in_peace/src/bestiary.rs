@@ -0,0 +1,109 @@
+//! A codex of the enemies the player has come across. Seeing an enemy adds it,
+//! fighting it reveals its stats, resistances and weaknesses, and defeating
+//! it reveals what is known about it.
+use super::{DamageType, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// What the player has learned about an enemy
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct BestiaryEntry {
+    /// The enemy's health when the player first saw it
+    pub health: u64,
+    #[serde(default)]
+    pub fought: bool,
+    #[serde(default)]
+    pub defeated: u64,
+}
+
+/// Joins damage types into a list, such as "slash, pierce"
+fn list_types(types: &[DamageType]) -> String {
+    match types.is_empty() {
+        true => "nothing".to_string(),
+        false => types
+            .iter()
+            .map(|kind| kind.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+impl World {
+    /// Adds the enemies where the player can see them to the bestiary
+    pub(crate) fn note_sightings(&mut self) {
+        let Some(player_loc) = self.objects[LOC_PLAYER].location else {
+            return;
+        };
+        if self.is_dark(player_loc) {
+            return;
+        }
+        for index in 0..self.objects.len() {
+            let object = &self.objects[index];
+            if object.enemy && !object.hidden && object.location == Some(player_loc) {
+                let health = object.health.unwrap_or(0);
+                self.bestiary.entry(index).or_insert(BestiaryEntry {
+                    health,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    /// Notes that the player has fought an enemy, which reveals its stats
+    pub(crate) fn note_fought(&mut self, enemy: usize) {
+        let health = self.objects[enemy].health.unwrap_or(0);
+        self.bestiary
+            .entry(enemy)
+            .or_insert(BestiaryEntry {
+                health,
+                ..Default::default()
+            })
+            .fought = true;
+    }
+
+    /// Notes that the player has defeated an enemy, which reveals its lore
+    pub(crate) fn note_defeated(&mut self, enemy: usize) {
+        self.note_fought(enemy);
+        if let Some(entry) = self.bestiary.get_mut(&enemy) {
+            entry.defeated += 1;
+        }
+    }
+
+    /// Describes an enemy in the bestiary, as far as the player knows it
+    fn describe_entry(&self, enemy: usize, entry: &BestiaryEntry) -> String {
+        let object = &self.objects[enemy];
+        let mut output = format!("- {}: {}\n", object.label[0], object.description);
+        if !entry.fought {
+            return output + "  Fight it to learn more.\n";
+        }
+        output += &format!(
+            "  Health: {}. Attack: {} ({}).\n  Resists: {}. Weak to: {}.\n",
+            entry.health,
+            object.attack.unwrap_or(0),
+            object.damage_type(),
+            list_types(&object.resists),
+            list_types(&object.weak_to)
+        );
+        if entry.defeated == 0 {
+            return output + "  Defeat it to learn more.\n";
+        }
+        output += &format!("  Defeated: {}.\n", entry.defeated);
+        match &object.lore {
+            Some(lore) => output + &format!("  {}\n", lore),
+            None => output,
+        }
+    }
+
+    /// Lists every enemy the player has come across and what they know of it
+    pub fn do_bestiary(&self) -> String {
+        if self.bestiary.is_empty() {
+            return "Your bestiary is empty. Enemies you come across are written in it.\n"
+                .to_string();
+        }
+        "Bestiary:\n".to_string()
+            + &self
+                .bestiary
+                .iter()
+                .map(|(enemy, entry)| self.describe_entry(*enemy, entry))
+                .collect::<String>()
+    }
+}
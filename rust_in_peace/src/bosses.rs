@@ -0,0 +1,60 @@
+//! Boss fights that change as the boss is worn down. Each phase begins once
+//! the boss's health drops below a threshold and can teach it new moves, call
+//! in reinforcements or send it running to another location.
+use super::{SpecialMove, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// A stage of a boss fight, entered once when the boss's health drops below a threshold
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub below: u64,
+    pub text: String,
+    /// Special moves the boss learns on top of the ones it already knows
+    #[serde(default)]
+    pub specials: Vec<SpecialMove>,
+    /// Enemies brought in to join the fight
+    #[serde(default)]
+    pub summon: Vec<usize>,
+    /// Location the boss runs off to, leaving the fight
+    #[serde(default)]
+    pub retreat: Option<usize>,
+}
+
+impl World {
+    /// Moves every boss in the fight on to the phases its health calls for
+    pub(crate) fn change_phases(&mut self) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let mut output = String::new();
+
+        for boss in self.fighting_enemies() {
+            let health = self.objects[boss].health.unwrap_or(0);
+            while let Some(phase) = self.objects[boss]
+                .phases
+                .get(self.objects[boss].phase)
+                .filter(|phase| health < phase.below)
+                .cloned()
+            {
+                self.objects[boss].phase += 1;
+                output += &format!("\n{}\n", phase.text);
+                self.objects[boss].specials.extend(phase.specials);
+
+                for ally in phase.summon {
+                    self.objects[ally].location = player_loc;
+                    self.objects[ally].asleep = false;
+                    if let Some(combat) = self.combat.as_mut() {
+                        if !combat.enemies.contains(&ally) {
+                            combat.enemies.push(ally);
+                        }
+                    }
+                    output += &format!("The {} joins the fight!\n", self.objects[ally].label[0]);
+                }
+                if let Some(to) = phase.retreat {
+                    self.objects[boss].location = Some(to);
+                    self.objects[boss].threat.clear();
+                    break;
+                }
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,167 @@
+//! Brewing potions over a fire, like the one in the Tavern, out of herbs,
+//! mushrooms and whatever else the player gathers. The world file says what
+//! can be brewed from what, and how hard it is. Every brew rolls a check of
+//! the player's intellect: a poor roll makes a weak potion that does half as
+//! much, and a roll well past what was needed a potent one that does half as
+//! much again.
+use super::{Attribute, Distance, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+// How far past a brew's difficulty the check has to go for a potent potion
+const POTENT_MARGIN: i64 = 5;
+
+/// How to brew a potion out of ingredients the player carries
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Brew {
+    pub makes: usize,
+    pub needs: Vec<usize>,
+    /// What the intellect check has to reach for the potion to come out right
+    pub difficulty: i64,
+}
+
+/// How well a potion came out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Potency {
+    Weak,
+    Standard,
+    Potent,
+}
+
+impl Potency {
+    /// Returns the word put in front of the potion's name, if any
+    pub fn adjective(&self) -> Option<&'static str> {
+        match self {
+            Potency::Weak => Some("Weak"),
+            Potency::Standard => None,
+            Potency::Potent => Some("Potent"),
+        }
+    }
+
+    /// Scales an amount the potion restores or adds by how well it came out
+    fn scale(&self, amount: u64) -> u64 {
+        match self {
+            Potency::Weak => amount / 2,
+            Potency::Standard => amount,
+            Potency::Potent => amount * 3 / 2,
+        }
+    }
+}
+
+impl World {
+    /// Returns true if there is something to brew over where the player is
+    fn can_brew_here(&self) -> bool {
+        (0..self.objects.len()).any(|index| {
+            self.objects[index].brewing
+                && self.get_distance(Some(LOC_PLAYER), Some(index)) == Distance::Here
+        })
+    }
+
+    /// Names the ingredients of a brew
+    fn ingredients(&self, brew: &Brew) -> String {
+        brew.needs
+            .iter()
+            .map(|need| self.objects[*need].label[0].clone())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// Gives the player a potion brewed to a potency. A standard one is just
+    /// like the potion it is brewed after, and weak and potent ones are named
+    /// for how they came out and do less or more.
+    fn pour_potion(&mut self, makes: usize, potency: Potency) -> String {
+        let mut potion = self.objects[makes].clone();
+        potion.location = Some(LOC_PLAYER);
+        potion.price = None;
+        potion.quantity = potion.quantity.map(|_| 1);
+        if let Some(adjective) = potency.adjective() {
+            potion
+                .label
+                .insert(0, format!("{} {}", adjective, potion.label[0]));
+            potion.value = potion.value.map(|value| potency.scale(value));
+            potion.health = potion.health.map(|health| potency.scale(health));
+            if let Some(buff) = potion.buff.as_mut() {
+                let scale =
+                    |amount: i64| potency.scale(amount.unsigned_abs()) as i64 * amount.signum();
+                buff.attack = scale(buff.attack);
+                buff.defense = scale(buff.defense);
+                buff.dodge = scale(buff.dodge);
+                buff.heal = potency.scale(buff.heal);
+            }
+        }
+        let name = potion.label[0].clone();
+        let potion = self.place_copy(potion);
+        self.merge_stack(potion);
+        name
+    }
+
+    /// Player brews a potion over a fire where they are, or lists what they
+    /// know how to brew if they don't name one
+    pub fn do_brew(&mut self, noun: &String) -> String {
+        if noun.is_empty() {
+            let known: String = self
+                .brews
+                .iter()
+                .map(|brew| {
+                    format!(
+                        "    {} ({})\n",
+                        self.objects[brew.makes].label[0],
+                        self.ingredients(brew)
+                    )
+                })
+                .collect();
+            return match known.is_empty() {
+                true => "You don't know how to brew anything.\n".to_string(),
+                false => "You know how to brew:\n".to_string() + &known,
+            };
+        }
+        let brew = self.brews.iter().find(|brew| {
+            self.objects[brew.makes]
+                .label
+                .iter()
+                .any(|label| label.to_lowercase() == noun.to_lowercase())
+        });
+        let Some(brew) = brew.cloned() else {
+            return format!("You don't know how to brew a '{}'.\n", noun);
+        };
+        let name = self.objects[brew.makes].label[0].clone();
+        let ingredients = self.ingredients(&brew);
+
+        if !self.can_brew_here() {
+            return format!(
+                "There is no fire here to brew the {} over. Try the fireplace in the Tavern.\n",
+                name
+            );
+        }
+        let Some(carried) = brew
+            .needs
+            .iter()
+            .map(|need| self.carried_like(*need).first().copied())
+            .collect::<Option<Vec<usize>>>()
+        else {
+            return format!("To brew the {} you need: {}.\n", name, ingredients);
+        };
+
+        let check = self.skill_check(Attribute::Intellect, 0, brew.difficulty);
+        let potency = match check.total() - brew.difficulty {
+            _ if !check.passed() => Potency::Weak,
+            margin if margin >= POTENT_MARGIN => Potency::Potent,
+            _ => Potency::Standard,
+        };
+        for ingredient in carried {
+            self.use_up(ingredient);
+        }
+        let poured = self.pour_potion(brew.makes, potency);
+        let verdict = match potency {
+            Potency::Weak => " It came out weak.",
+            Potency::Standard => "",
+            Potency::Potent => " It came out potent.",
+        };
+        format!(
+            "You brew a {} from the {}{}.{}\n",
+            poured,
+            ingredients,
+            self.roll_note(&check),
+            verdict
+        )
+    }
+}
@@ -0,0 +1,155 @@
+//! Non-lethal ends to a fight: tying up knocked out enemies, looting them,
+//! questioning them and handing captives over for their bounty.
+use super::{Attribute, Distance, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// Something a captive knows and may give up under questioning
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Intel {
+    pub text: String,
+    pub flag: String,
+    pub difficulty: i64,
+}
+
+impl World {
+    /// Finds a defeated enemy next to the player, or explains why there is none
+    fn defeated_enemy(&self, noun: &String) -> Result<usize, String> {
+        let (output, obj_opt) = self.object_visible(noun);
+
+        match obj_opt {
+            Some(index) if !self.objects[index].enemy => Err(format!(
+                "The {} is not an enemy.\n",
+                self.objects[index].label[0]
+            )),
+            Some(index) if self.objects[index].health.unwrap_or(0) > 0 => Err(format!(
+                "The {} is still standing. Defeat it first.\n",
+                self.objects[index].label[0]
+            )),
+            Some(index) => match self.get_distance(Some(LOC_PLAYER), Some(index)) {
+                Distance::Held | Distance::Here => Ok(index),
+                _ => Err(format!("You don't see any '{}' here.\n", noun)),
+            },
+            None => Err(output),
+        }
+    }
+
+    /// Player ties up a knocked out enemy and leads it along
+    pub fn do_capture(&mut self, noun: &String) -> String {
+        match self.defeated_enemy(noun) {
+            Ok(index) if self.objects[index].captured => {
+                format!(
+                    "The {} is already your captive.\n",
+                    self.objects[index].label[0]
+                )
+            }
+            Ok(index) if !self.objects[index].knocked_out => {
+                format!("The {} is dead.\n", self.objects[index].label[0])
+            }
+            Ok(index) => {
+                self.objects[index].captured = true;
+                self.karma += 1;
+                format!(
+                    "You tie up the {}. It will follow you wherever you go.\n",
+                    self.objects[index].label[0]
+                )
+            }
+            Err(output) => output,
+        }
+    }
+
+    /// Player takes everything a defeated enemy was carrying
+    pub fn do_loot(&mut self, noun: &String) -> String {
+        match self.defeated_enemy(noun) {
+            Ok(index) => {
+                let carried: Vec<usize> = (0..self.objects.len())
+                    .filter(|pos| self.is_containing(Some(index), Some(*pos)))
+                    .collect();
+                if carried.is_empty() {
+                    return format!("The {} has nothing on it.\n", self.objects[index].label[0]);
+                }
+                carried
+                    .into_iter()
+                    .map(|pos| self.carry(pos, self.count(pos)))
+                    .collect()
+            }
+            Err(output) => output,
+        }
+    }
+
+    /// Player hands a captive over at a bounty board
+    pub fn do_turn_in(&mut self, noun: &String) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let board_here = self
+            .objects
+            .iter()
+            .any(|object| object.bounty_board && object.location == player_loc);
+
+        match self.defeated_enemy(noun) {
+            Ok(index) if !self.objects[index].captured => {
+                format!(
+                    "The {} is not your captive.\n",
+                    self.objects[index].label[0]
+                )
+            }
+            Ok(_) if !board_here => "There is nobody here to take your captive.\n".to_string(),
+            Ok(index) => {
+                let bounty = self.objects[index].bounty.unwrap_or(0);
+                self.objects[index].location = None;
+                self.reputation += bounty as i64;
+                format!(
+                    "You claim the bounty on the {}. Your reputation is now {}.\n",
+                    self.objects[index].label[0], self.reputation
+                )
+            }
+            Err(output) => output,
+        }
+    }
+
+    /// Player questions a captive, with an intellect check helped by karma against
+    /// what it knows
+    pub fn do_interrogate(&mut self, noun: &String) -> String {
+        let index = match self.defeated_enemy(noun) {
+            Ok(index) if self.objects[index].captured => index,
+            Ok(index) => {
+                return format!(
+                    "The {} is in no state to answer questions.\n",
+                    self.objects[index].label[0]
+                )
+            }
+            Err(output) => return output,
+        };
+
+        let secret = self.objects[index]
+            .intel
+            .iter()
+            .find(|intel| !self.flags.contains(&intel.flag))
+            .cloned();
+        match secret {
+            None => format!(
+                "The {} has nothing more to tell you.\n",
+                self.objects[index].label[0]
+            ),
+            Some(intel) => {
+                let check = self.skill_check(
+                    Attribute::Intellect,
+                    self.karma + self.eloquence(),
+                    intel.difficulty,
+                );
+                let note = self.roll_note(&check);
+                if check.passed() {
+                    self.set_flag(intel.flag);
+                    self.journal.push(intel.text.clone());
+                    format!(
+                        "The {} talks: \"{}\"{}\n(Written in your journal)\n",
+                        self.objects[index].label[0], intel.text, note
+                    )
+                } else {
+                    format!(
+                        "The {} glares at you and says nothing.{}\n",
+                        self.objects[index].label[0], note
+                    )
+                }
+            }
+        }
+    }
+}
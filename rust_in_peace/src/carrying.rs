@@ -0,0 +1,92 @@
+//! How much the player can carry. Every item weighs something, and the
+//! player's strength decides how much of it they can haul around before they
+//! have to leave something behind. A mount they ride carries some more.
+use super::{World, LOC_PLAYER};
+
+// Weight the player can carry for every point of strength
+const CAPACITY_PER_STRENGTH: u64 = 3;
+// Share of their capacity in percent above which the player is warned about the load
+const HEAVY_LOAD: u64 = 80;
+
+impl World {
+    /// Returns the weight of everything the player carries, including what is
+    /// in the containers they carry
+    pub fn load(&self) -> u64 {
+        self.objects
+            .iter()
+            .filter(|object| self.weighs_on_player(object.location))
+            .map(|object| object.weight * object.quantity.unwrap_or(1))
+            .sum()
+    }
+
+    /// Returns true if what is at a location counts towards the player's load:
+    /// whatever they hold, and whatever is in a container they hold
+    fn weighs_on_player(&self, location: Option<usize>) -> bool {
+        location == Some(LOC_PLAYER)
+            || location.is_some_and(|container| {
+                self.objects[container].container.is_some()
+                    && self.objects[container].location == Some(LOC_PLAYER)
+            })
+    }
+
+    /// Returns the most weight the player can carry, with what the mount they
+    /// ride carries, or None if their strength isn't set and there is no limit
+    pub fn capacity(&self) -> Option<u64> {
+        let mount = self.riding.map_or(0, |mount| self.objects[mount].carries);
+        self.objects[LOC_PLAYER]
+            .strength
+            .map(|strength| strength * CAPACITY_PER_STRENGTH + mount)
+    }
+
+    /// Describes the player's load, if there is a limit to it
+    pub fn describe_load(&self) -> String {
+        match self.capacity() {
+            Some(capacity) => format!("You are carrying {}/{} weight.\n", self.load(), capacity),
+            None => String::new(),
+        }
+    }
+
+    /// Returns why the player can't pick up some of an object, if it would
+    /// take them over what they can carry
+    pub(crate) fn too_heavy(&self, index: usize, count: u64) -> Option<String> {
+        // Taking something out of a bag the player carries adds nothing to the load
+        if self.weighs_on_player(self.objects[index].location) {
+            return None;
+        }
+        let capacity = self.capacity()?;
+        let load = self.load();
+        let weight = self.objects[index].weight * count;
+        (load + weight > capacity).then(|| {
+            format!(
+                "The {} is too heavy to carry as well. You are carrying {}/{} weight and it weighs {}.\n",
+                self.objects[index].label[0], load, capacity, weight
+            )
+        })
+    }
+
+    /// Warns the player when what they carry is close to all they can manage
+    pub(crate) fn load_warning(&self) -> String {
+        match self.capacity() {
+            Some(capacity) if self.load() * 100 > capacity * HEAVY_LOAD => {
+                format!(
+                    "Your pack is getting heavy: {}/{} weight.\n",
+                    self.load(),
+                    capacity
+                )
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Picks up some of an object, unless it is more than the player can carry
+    pub(crate) fn carry(&mut self, index: usize, count: u64) -> String {
+        match self.too_heavy(index, count) {
+            Some(refusal) => refusal,
+            None => {
+                self.move_some(index, count, Some(LOC_PLAYER))
+                    + &self.pocket_gold(index)
+                    + &self.load_warning()
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Skill checks for what the player does outside of fights, like sneaking,
+//! picking locks, searching, talking someone round or climbing. A check rolls
+//! a d20, adds the bonus of the attribute it tests and whatever else helps,
+//! and passes if the total reaches its difficulty. The player can ask to see
+//! the numbers behind every check.
+use super::{Attribute, World};
+use rand::Rng;
+use std::fmt;
+
+/// A roll against a difficulty, with everything that went into it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkillCheck {
+    pub attribute: Attribute,
+    /// The d20
+    pub roll: i64,
+    /// The attribute's bonus and everything else added to the roll
+    pub bonus: i64,
+    pub difficulty: i64,
+}
+
+impl SkillCheck {
+    /// Returns the roll with its bonus
+    pub fn total(&self) -> i64 {
+        self.roll + self.bonus
+    }
+
+    /// Returns true if the total reached the difficulty
+    pub fn passed(&self) -> bool {
+        self.total() >= self.difficulty
+    }
+}
+
+impl fmt::Display for SkillCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} check: rolled {} {:+} = {} against {}, {}",
+            self.attribute,
+            self.roll,
+            self.bonus,
+            self.total(),
+            self.difficulty,
+            match self.passed() {
+                true => "passed",
+                false => "failed",
+            }
+        )
+    }
+}
+
+impl World {
+    /// Rolls a check of an attribute against a difficulty, with a bonus on
+    /// top of the attribute's own
+    pub fn skill_check(&self, attribute: Attribute, bonus: i64, difficulty: i64) -> SkillCheck {
+        SkillCheck {
+            attribute,
+            roll: self.rng().gen_range(1..=20),
+            bonus: self.attribute_bonus(attribute) + bonus,
+            difficulty,
+        }
+    }
+
+    /// Returns the numbers behind a check for the player to see, if they asked
+    /// for them, to go at the end of a sentence
+    pub fn roll_note(&self, check: &SkillCheck) -> String {
+        match self.show_rolls {
+            true => format!(" ({})", check),
+            false => String::new(),
+        }
+    }
+
+    /// Player turns showing the numbers behind checks on or off
+    pub fn do_rolls(&mut self, noun: &str) -> String {
+        self.show_rolls = match noun {
+            "on" => true,
+            "off" => false,
+            "" => !self.show_rolls,
+            _ => return "Try <rolls on> or <rolls off>.\n".to_string(),
+        };
+        match self.show_rolls {
+            true => "The rolls behind checks will be shown.\n".to_string(),
+            false => "The rolls behind checks will be hidden.\n".to_string(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Asking the player which object they meant when a name fits more than one.
+//! The matches are listed with numbers, and typing a number carries on with
+//! the command as if that object had been named on its own.
+use super::{Command, Distance, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// A command waiting on the player to pick which of several objects it is for
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Choice {
+    pub command: Command,
+    pub options: Vec<usize>,
+}
+
+impl World {
+    /// Lists every object a name could mean, numbered, and holds on to them
+    /// so the player can answer with a number
+    pub(crate) fn offer_choice(
+        &self,
+        noun: &str,
+        from: Option<usize>,
+        max_distance: Distance,
+    ) -> String {
+        let options = self.matching_objects(noun, from, max_distance);
+        let mut output = format!("Which {} do you mean?\n", noun);
+        for (number, option) in options.iter().enumerate() {
+            output += &format!("{}. {}\n", number + 1, self.describe_option(*option));
+        }
+        self.offered.replace(options);
+        output + "Type a number to choose.\n"
+    }
+
+    /// Turns a number typed in answer to a choice back into the command that
+    /// asked it, with the picked object standing in for the name. Returns None
+    /// if the input isn't the number of one of the options.
+    pub(crate) fn make_choice(&self, command: &Command, choice: Option<Choice>) -> Option<Command> {
+        let Command::Unknown(input) = command else {
+            return None;
+        };
+        let choice = choice?;
+        let number = input.parse::<usize>().ok()?.checked_sub(1)?;
+        self.chosen.set(Some(*choice.options.get(number)?));
+        Some(choice.command)
+    }
+
+    /// Names an option along with where it is, so options with the same name
+    /// can be told apart
+    fn describe_option(&self, option: usize) -> String {
+        let name = &self.objects[option].label[0];
+        match self.objects[option].location {
+            Some(LOC_PLAYER) => format!("{} (held)", name),
+            Some(location) => format!("{} ({})", name, self.objects[location].label[0]),
+            None => name.clone(),
+        }
+    }
+}
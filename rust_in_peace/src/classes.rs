@@ -0,0 +1,86 @@
+//! Character classes the player picks from at the start of the game. A class
+//! sets the player's starting attributes, hands them a starting item and gives
+//! them a small perk of its own that combat and checks honour for the rest of
+//! the game.
+use super::{Perk, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// A class the player can start the game as
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Class {
+    pub name: String,
+    pub description: String,
+    pub strength: u64,
+    pub agility: u64,
+    pub intellect: u64,
+    /// Given to the player when they pick the class
+    #[serde(default)]
+    pub item: Option<usize>,
+    pub perk: Perk,
+}
+
+impl World {
+    /// Lists the classes the player can pick from, or nothing once it's too late
+    pub fn describe_classes(&self) -> String {
+        if self.classes.is_empty() || !self.can_choose_class() {
+            return String::new();
+        }
+        let mut output = "Choose your class with <class name>:\n".to_string();
+        for class in &self.classes {
+            output += &format!(
+                "- {}: {} (strength {}, agility {}, intellect {})\n  {}.",
+                class.name,
+                class.description,
+                class.strength,
+                class.agility,
+                class.intellect,
+                class.perk
+            );
+            if let Some(item) = class.item {
+                output += &format!(" Starts with the {}.", self.objects[item].label[0]);
+            }
+            output += "\n";
+        }
+        output
+    }
+
+    /// Returns true until the player has picked a class or set out on their journey
+    fn can_choose_class(&self) -> bool {
+        self.objects[LOC_PLAYER].class.is_none() && self.turn == 0
+    }
+
+    /// Player picks the class they start the game as, or with no name lists them
+    pub fn do_class(&mut self, noun: &str) -> String {
+        if let Some(class) = self.objects[LOC_PLAYER].class {
+            return format!("You are a {}.\n", self.classes[class].name);
+        }
+        if self.classes.is_empty() {
+            return "There are no classes to choose from.\n".to_string();
+        }
+        if !self.can_choose_class() {
+            return "It's too late to choose a class now that your journey has begun.\n"
+                .to_string();
+        }
+        let Some(index) = self
+            .classes
+            .iter()
+            .position(|class| class.name.to_lowercase() == noun.to_lowercase())
+        else {
+            return self.describe_classes();
+        };
+
+        let class = self.classes[index].clone();
+        let player = &mut self.objects[LOC_PLAYER];
+        player.class = Some(index);
+        player.strength = Some(class.strength);
+        player.agility = Some(class.agility);
+        player.intellect = Some(class.intellect);
+        self.record(format!("player became a {}", class.name));
+        let mut output = format!("You are a {}. {}.\n", class.name, class.perk);
+        if let Some(item) = class.item {
+            self.objects[item].location = Some(LOC_PLAYER);
+            output += &format!("You start with the {}.\n", self.objects[item].label[0]);
+        }
+        output
+    }
+}
@@ -0,0 +1,746 @@
+//! Fights between the player and an enemy. A fight lasts several commands, so
+//! its state is kept on the world and each round is an ordinary command.
+use super::{
+    Command, Object, Slot, StatusEffect, World, ATTACK_COST, DEFEND_RECOVERY, FLEE_COST, LOC_PLAYER,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Chance in percent that an attack lands before accuracy and dodging are counted
+const BASE_HIT: i64 = 85;
+// Chance in percent that a landed attack is a critical hit before any bonus
+const BASE_CRITICAL: i64 = 5;
+// A critical hit does this many times the damage
+const CRITICAL_MULTIPLIER: u64 = 2;
+
+// Fleeing is a d20 roll for the player against a d20 roll for each enemy, each
+// side adding a fifth of its dodge or accuracy
+const FLEE_DIE: i64 = 20;
+
+// Initiative is a d20 roll plus the fighter's initiative stat
+const INITIATIVE_DIE: i64 = 20;
+
+// The effect a player builds up by defending, the attack it adds each time they
+// defend and the most it can add up to
+const COUNTERATTACK: &str = "Counterattack";
+const COUNTER_BONUS: i64 = 5;
+const MAX_COUNTER: i64 = 15;
+
+// Shown whenever the player tries something that doesn't work in a fight
+const COMBAT_HINT: &str = "\nHint: Use the following commands when attacking: 'use <weapon name>' (or 'use fists'), 'attack', 'throw <item>', 'taunt', 'sneak', 'defend', 'inventory', 'log' or 'run'\n";
+
+/// The fight the player is in the middle of
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CombatState {
+    /// The enemy the player's attacks go to
+    pub enemy: usize,
+    /// Every enemy taking part in the fight
+    #[serde(default)]
+    pub enemies: Vec<usize>,
+    /// Set while the enemies are caught by surprise and can't strike back yet
+    #[serde(default)]
+    pub surprise: bool,
+    /// Set for the round the player spends defending
+    #[serde(default)]
+    pub defending: bool,
+}
+
+/// The kind of damage a weapon or enemy deals
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DamageType {
+    #[default]
+    Slash,
+    Pierce,
+    Blunt,
+}
+
+impl fmt::Display for DamageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DamageType::Slash => write!(f, "slash"),
+            DamageType::Pierce => write!(f, "pierce"),
+            DamageType::Blunt => write!(f, "blunt"),
+        }
+    }
+}
+
+/// Everything that happened when an attack was resolved, for building the messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageResult {
+    pub outcome: Outcome,
+    pub kind: DamageType,
+    pub resisted: bool,
+    pub vulnerable: bool,
+    pub damage: u64,
+    pub health: u64,
+}
+
+/// How an attack turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Miss,
+    Hit,
+    Critical,
+}
+
+impl Outcome {
+    /// Scales the damage of an attack by how well it landed
+    pub fn damage(self, damage: u64) -> u64 {
+        match self {
+            Outcome::Miss => 0,
+            Outcome::Hit => damage,
+            Outcome::Critical => damage * CRITICAL_MULTIPLIER,
+        }
+    }
+
+    /// How the attack is written in the combat log
+    pub fn verb(self) -> &'static str {
+        match self {
+            Outcome::Miss => "missed",
+            Outcome::Hit => "hit",
+            Outcome::Critical => "critically hit",
+        }
+    }
+}
+
+impl DamageResult {
+    /// Announces a critical hit
+    pub(crate) fn critical_text(&self) -> &'static str {
+        if self.outcome == Outcome::Critical {
+            "A critical hit! "
+        } else {
+            ""
+        }
+    }
+
+    /// Tells how well the kind of damage worked on the defender
+    pub(crate) fn effect_text(&self) -> &'static str {
+        match (self.resisted, self.vulnerable) {
+            (true, false) => " It shrugs off some of the blow.",
+            (false, true) => " It hits a weak spot!",
+            _ => "",
+        }
+    }
+}
+
+impl Object {
+    /// Returns the lowest and highest damage this weapon or fighter deals. Weapons
+    /// hit for their attack unless a minimum is given, fighters for anywhere up to it.
+    pub fn damage_range(&self) -> (u64, u64) {
+        // Worn out weapons only hit half as hard
+        let high = match self.is_worn_out() {
+            true => self.attack.unwrap_or(0) / 2,
+            false => self.attack.unwrap_or(0),
+        };
+        let low = match self.min_attack {
+            Some(low) if self.is_worn_out() => low / 2,
+            Some(low) => low,
+            None if self.enemy => 1,
+            None => high,
+        };
+        (low.min(high), high)
+    }
+
+    /// Returns the kind of damage this weapon or fighter deals
+    pub fn damage_type(&self) -> DamageType {
+        if self.blunt {
+            DamageType::Blunt
+        } else {
+            self.damage_type
+        }
+    }
+}
+
+impl World {
+    /// Rolls whether an attack lands and whether it is a critical hit. The
+    /// attacker's accuracy and critical chance add to those of their weapon.
+    pub fn roll_attack(&self, attacker: usize, weapon: Option<usize>, defender: usize) -> Outcome {
+        let stat = |pick: fn(&Object) -> i64| {
+            pick(&self.objects[attacker])
+                + weapon.map_or(0, |w| pick(&self.objects[w]))
+                + self.trinket_bonus(attacker, pick)
+        };
+        let dodge = self.objects[defender].dodge
+            + self.trinket_bonus(defender, |o| o.dodge)
+            + self.dodge_bonus(defender);
+        let hit_chance = BASE_HIT + stat(|o| o.accuracy) - dodge;
+        let critical_chance = BASE_CRITICAL + stat(|o| o.critical);
+
+        let mut rng = self.rng();
+        if rng.gen_range(1..=100) > hit_chance && !self.sure_shot(attacker, weapon) {
+            Outcome::Miss
+        } else if rng.gen_range(1..=100) <= critical_chance {
+            Outcome::Critical
+        } else {
+            Outcome::Hit
+        }
+    }
+
+    /// Rolls an attack and deals its damage to the defender. The damage is rolled
+    /// within the range of the weapon, or the attacker when it has none, then
+    /// scaled by critical hits, resistances and weaknesses, and bonuses.
+    pub fn resolve_damage(
+        &mut self,
+        attacker: usize,
+        weapon: Option<usize>,
+        defender: usize,
+    ) -> DamageResult {
+        let source = &self.objects[weapon.unwrap_or(attacker)];
+        let (low, high) = source.damage_range();
+        let kind = source.damage_type();
+        let target = &self.objects[defender];
+        let resisted = target.resists.contains(&kind);
+        let vulnerable = target.weak_to.contains(&kind);
+
+        let outcome = self.roll_attack(attacker, weapon, defender);
+        let mut damage = outcome.damage(self.rng().gen_range(low..=high));
+        if resisted {
+            damage /= 2;
+        }
+        if vulnerable {
+            damage = damage.saturating_mul(3) / 2;
+        }
+        if outcome != Outcome::Miss {
+            damage = World::adjust_damage(
+                damage,
+                self.attack_bonus(attacker) - self.defense_bonus(defender),
+            );
+        }
+        if self.guarding(defender) {
+            damage /= 2;
+        }
+        if self.winded(attacker) {
+            damage /= 2;
+        }
+
+        let health = self.objects[defender]
+            .health
+            .unwrap_or(0)
+            .saturating_sub(damage);
+        self.objects[defender].health = Some(health);
+        self.log_blow(
+            self.objects[attacker].label[0].clone(),
+            outcome.verb(),
+            defender,
+            damage,
+        );
+        DamageResult {
+            outcome,
+            kind,
+            resisted,
+            vulnerable,
+            damage,
+            health,
+        }
+    }
+
+    /// Returns the enemies in the fight that are still standing and still here
+    pub fn fighting_enemies(&self) -> Vec<usize> {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        self.combat
+            .as_ref()
+            .map(|combat| combat.enemies.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|enemy| {
+                self.objects[*enemy].location == player_loc
+                    && self.objects[*enemy].health.unwrap_or(0) > 0
+            })
+            .collect()
+    }
+
+    /// Starts a fight with an enemy next to the player. Every other enemy standing
+    /// in the same place joins in.
+    pub(crate) fn start_combat(&mut self, enemy: usize) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let mut enemies = vec![enemy];
+        enemies.extend((0..self.objects.len()).filter(|index| {
+            let object = &self.objects[*index];
+            *index != enemy
+                && object.enemy
+                && !object.hidden
+                && !object.calmed
+                && object.location == player_loc
+                && object.health.unwrap_or(0) > 0
+        }));
+        // Attacking an enemy that was talked down sets it against the player again
+        self.objects[enemy].calmed = false;
+
+        // Sleeping enemies and ones caught in a trap don't see the first blows coming
+        let surprise = self.objects[enemy].asleep || self.objects[enemy].snared_by.is_some();
+        let mut output = format!(
+            "\nYou are attacking the {}.\n",
+            self.objects[enemy].label[0]
+        );
+        if surprise {
+            output += &format!(
+                "You catch the {} by surprise and get to strike twice!\n",
+                self.objects[enemy].label[0]
+            );
+        }
+        for index in enemies.iter().copied() {
+            self.note_fought(index);
+            self.objects[index].pursuing = false;
+            self.objects[index].asleep = false;
+            if index != enemy {
+                output += &format!("The {} joins the fight!\n", self.objects[index].label[0]);
+            }
+            output += &self.trap_damage(index);
+        }
+        self.combat = Some(CombatState {
+            enemy,
+            enemies,
+            surprise,
+            defending: false,
+        });
+        if !surprise {
+            output += &self.strike_first();
+        }
+        if self.objects[LOC_PLAYER].health.unwrap_or(0) == 0 {
+            self.combat = None;
+            return output + "\nYou died";
+        }
+        output + COMBAT_HINT
+    }
+
+    /// Rolls initiative for the player against every enemy in the fight. Enemies
+    /// quicker than the player attack before the player can act.
+    fn strike_first(&mut self) -> String {
+        let mut rng = self.rng();
+        let player = rng.gen_range(1..=INITIATIVE_DIE) + self.objects[LOC_PLAYER].initiative;
+        let mut output = String::new();
+
+        for enemy in self.fighting_enemies() {
+            if self.objects[enemy].attack.unwrap_or(0) == 0
+                || rng.gen_range(1..=INITIATIVE_DIE) + self.objects[enemy].initiative <= player
+            {
+                continue;
+            }
+            output += &format!(
+                "The {} is quicker and strikes first!",
+                self.objects[enemy].label[0]
+            );
+            let target = self.select_target(enemy);
+            let result = self.resolve_damage(enemy, None, target);
+            output += &self.describe_strike(enemy, target, &result);
+            if result.damage > 0 && result.health > 0 {
+                output += &self.infect(enemy, target);
+            }
+            output += &self.wear_armor(target, result.damage);
+        }
+        output
+    }
+
+    /// Carries out a command while a fight is on. Returns None if there is no
+    /// fight, or the command doesn't belong to it, so it is handled as usual.
+    pub(crate) fn fight(&mut self, command: &Command) -> Option<String> {
+        let mut enemy = self.combat.as_ref()?.enemy;
+        let fighting = self.fighting_enemies();
+        if fighting.is_empty() {
+            // Something took the enemies away in the middle of the fight
+            self.end_combat();
+            return None;
+        }
+        if !fighting.contains(&enemy) {
+            enemy = fighting[0];
+            self.set_target(enemy);
+        }
+
+        let output = match command {
+            // A stunned player loses whatever they were about to do
+            Command::Use { .. }
+            | Command::Attack { .. }
+            | Command::Taunt
+            | Command::Sneak
+            | Command::Defend
+            | Command::Run
+            | Command::Eat(_)
+            | Command::Throw { .. }
+                if self.is_stunned(LOC_PLAYER) =>
+            {
+                "You are stunned and can't act!".to_string() + &self.enemies_turn()
+            }
+            Command::Use { item, target } => self.do_use(item, target.as_deref(), enemy),
+            // Attacking again carries on with the best weapon to hand, or fists
+            Command::Attack { target, weapon } => self.do_use(
+                weapon.as_deref().unwrap_or_default(),
+                Some(target.as_str()).filter(|target| !target.is_empty()),
+                enemy,
+            ),
+            Command::Taunt => {
+                self.add_threat(enemy, LOC_PLAYER, 25);
+                format!(
+                    "You taunt the {}. It only has eyes for you now.",
+                    self.objects[enemy].label[0]
+                ) + &self.enemies_turn()
+            }
+            Command::Sneak => self.try_sneak(enemy) + &self.enemies_turn(),
+            Command::Defend => self.do_defend(),
+            Command::Run => self.try_flee(),
+            // Eating or drinking in the middle of a fight costs the player their turn
+            Command::Eat(noun) => self.do_eat(noun).trim_end().to_string() + &self.enemies_turn(),
+            Command::Throw { item, target } => self.do_throw(item, target.as_ref()),
+            Command::Inventory
+            | Command::Compare { .. }
+            | Command::Help(_)
+            | Command::Quit
+            | Command::Log(_)
+            | Command::Attributes
+            | Command::Stats
+            | Command::Rolls(_)
+            | Command::Survival(_)
+            | Command::Bestiary
+            | Command::Time
+            | Command::Malformed(_) => return None,
+            _ => COMBAT_HINT.to_string(),
+        };
+
+        if self.combat.is_some() && self.objects[LOC_PLAYER].health.unwrap_or(0) == 0 {
+            self.combat = None;
+            return Some(output + "\nYou died");
+        }
+        Some(output)
+    }
+
+    /// Player braces for the enemies' blows, taking half damage this round and
+    /// building up a bonus to their next attack
+    fn do_defend(&mut self) -> String {
+        let counter = self.objects[LOC_PLAYER]
+            .effects
+            .iter()
+            .find(|effect| effect.name == COUNTERATTACK)
+            .map_or(0, |effect| effect.attack);
+        let counter = (counter + COUNTER_BONUS).min(MAX_COUNTER);
+        // Two turns, so the bonus outlasts this round and is there for the next
+        self.add_effect(
+            LOC_PLAYER,
+            StatusEffect {
+                name: COUNTERATTACK.to_string(),
+                attack: counter,
+                turns: 2,
+                ..Default::default()
+            },
+        );
+
+        if let Some(combat) = self.combat.as_mut() {
+            combat.defending = true;
+        }
+        self.recover_stamina(DEFEND_RECOVERY);
+        let output = format!(
+            "You raise your guard and wait for an opening. Your next attack gets {:+}.",
+            counter
+        ) + &self.enemies_turn();
+        if let Some(combat) = self.combat.as_mut() {
+            combat.defending = false;
+        }
+        output
+    }
+
+    /// Returns true if a fighter is defending and takes only half damage
+    pub(crate) fn guarding(&self, fighter: usize) -> bool {
+        fighter == LOC_PLAYER && self.combat.as_ref().is_some_and(|combat| combat.defending)
+    }
+
+    /// Player tries to get away. Every enemy still standing rolls to cut them off,
+    /// and the ones who do get a free attack. Some bosses never let anyone go.
+    fn try_flee(&mut self) -> String {
+        let fighting = self.fighting_enemies();
+        if let Some(boss) = fighting
+            .iter()
+            .find(|enemy| self.objects[**enemy].blocks_flee)
+        {
+            return format!(
+                "The {} won't let you get away!",
+                self.objects[*boss].label[0]
+            ) + &self.enemies_turn();
+        }
+        if !self.has_stamina(FLEE_COST) {
+            return "You are too winded to run!".to_string() + &self.enemies_turn();
+        }
+        self.spend_stamina(FLEE_COST);
+
+        let mut rng = self.rng();
+        let dodge = self.objects[LOC_PLAYER].dodge
+            + self.trinket_bonus(LOC_PLAYER, |o| o.dodge)
+            + self.dodge_bonus(LOC_PLAYER);
+        let escape = rng.gen_range(1..=FLEE_DIE) + dodge / 5;
+        let catcher = fighting
+            .iter()
+            .copied()
+            .find(|enemy| rng.gen_range(1..=FLEE_DIE) + self.objects[*enemy].accuracy / 5 > escape);
+        match catcher {
+            Some(enemy) => {
+                format!(
+                    "You try to run, but the {} cuts you off!",
+                    self.objects[enemy].label[0]
+                ) + &self.enemies_turn()
+            }
+            None => self.end_combat().unwrap_or_default(),
+        }
+    }
+
+    /// Makes an enemy the one the player's attacks go to
+    pub(crate) fn set_target(&mut self, enemy: usize) {
+        if let Some(combat) = self.combat.as_mut() {
+            combat.enemy = enemy;
+        }
+    }
+
+    /// Function to perform the attack while attacking an enemy. A target picks
+    /// which enemy of the fight to attack.
+    pub fn do_use(&mut self, weapon: &str, target: Option<&str>, mut enemy: usize) -> String {
+        if let Some(target) = target {
+            let (output, obj_opt) = self.object_visible(&target.to_string());
+            match obj_opt {
+                Some(index) if self.fighting_enemies().contains(&index) => {
+                    enemy = index;
+                    self.set_target(enemy);
+                }
+                Some(index) => {
+                    return format!(
+                        "You are not fighting the {}.\n",
+                        self.objects[index].label[0]
+                    )
+                }
+                None => return output,
+            }
+        }
+
+        let weapon_index = match self.choose_weapon(weapon) {
+            Ok(weapon_index) => weapon_index,
+            Err(output) => return output,
+        };
+        let name = self.objects[enemy].label[0].clone();
+        let result = self.resolve_damage(LOC_PLAYER, weapon_index, enemy);
+        let winded = self.spend_stamina(ATTACK_COST);
+        if result.outcome == Outcome::Miss {
+            return format!("You missed the {}.{}", name, winded) + &self.enemies_turn();
+        }
+        self.add_threat(enemy, LOC_PLAYER, result.damage);
+        let worn = weapon_index.map_or(String::new(), |weapon| self.wear(weapon));
+        let mut output = format!(
+            "{}You attacked the {}{}.{}\nEnemy health: {}",
+            result.critical_text(),
+            name,
+            if weapon_index.is_none() {
+                " with your bare fists"
+            } else {
+                ""
+            },
+            result.effect_text(),
+            result.health
+        ) + &worn
+            + &winded;
+        if result.health > 0 {
+            if let Some(effect) = weapon_index.and_then(|w| self.objects[w].on_hit.clone()) {
+                output += &format!("\nThe {} suffers {}.", name, effect);
+                self.stack_effect(enemy, effect);
+            }
+            return output + &self.enemies_turn();
+        }
+
+        self.objects[enemy].knocked_out =
+            result.kind == DamageType::Blunt && self.objects[enemy].capturable;
+        self.record_defeat(enemy);
+        output += &self.defeat(enemy);
+        output + &self.next_enemy()
+    }
+
+    /// Turns the player to the next enemy of the fight once one is down, or
+    /// ends the fight if that was the last of them
+    pub(crate) fn next_enemy(&mut self) -> String {
+        match self.fighting_enemies().first() {
+            Some(next) => {
+                let next = *next;
+                self.set_target(next);
+                format!("You turn to the {}.", self.objects[next].label[0]) + &self.enemies_turn()
+            }
+            None => {
+                self.combat = None;
+                String::new()
+            }
+        }
+    }
+
+    /// Finds the weapon the player names. No name means the weapon they have
+    /// equipped, or else the best one they carry, and None stands for fists,
+    /// which the player's own attack stands for.
+    pub(crate) fn choose_weapon(&self, weapon: &str) -> Result<Option<usize>, String> {
+        match weapon.to_lowercase().as_str() {
+            "" => Ok(self.equipped(Slot::Weapon).or_else(|| self.best_weapon())),
+            "fists" | "fist" | "hands" => Ok(None),
+            _ => match self.object_visible(&weapon.to_string()) {
+                (_, Some(index))
+                    if !self.objects[index].enemy && self.objects[index].attack.is_some() =>
+                {
+                    Ok(Some(index))
+                }
+                (_, Some(_)) => Err("That is not a weapon!!".to_string() + COMBAT_HINT),
+                (output, None) => Err(output),
+            },
+        }
+    }
+
+    /// Returns the hardest hitting weapon the player carries, if they have any
+    fn best_weapon(&self) -> Option<usize> {
+        (0..self.objects.len())
+            .filter(|index| {
+                let object = &self.objects[*index];
+                !object.enemy
+                    && object.attack.is_some()
+                    && self.is_containing(Some(LOC_PLAYER), Some(*index))
+            })
+            .max_by_key(|index| self.objects[*index].attack)
+    }
+
+    /// Companions strike and bosses worn down far enough move on to their next
+    /// phase, then every enemy still standing strikes back at a member of the
+    /// party, or uses one of its special moves. With more than one enemy the
+    /// round ends with a summary of what each of them did.
+    pub(crate) fn enemies_turn(&mut self) -> String {
+        let mut output = self.companions_turn() + &self.change_phases();
+        let fighting = self.fighting_enemies();
+        if fighting.is_empty() {
+            // The last enemy standing ran off, or a companion finished it
+            self.combat = None;
+            return output;
+        }
+        if let Some(combat) = self.combat.as_mut().filter(|combat| combat.surprise) {
+            combat.surprise = false;
+            return output + "\nYour foes are still reeling from the surprise.\n";
+        }
+        let mut summary = String::new();
+        for enemy in fighting.iter().copied() {
+            if let Some((text, line)) = self.regenerate(enemy) {
+                output += &text;
+                summary += &line;
+            }
+            if self.objects[enemy].attack.unwrap_or(0) == 0 {
+                continue;
+            }
+            if self.is_stunned(enemy) {
+                let name = &self.objects[enemy].label[0];
+                output += &format!("\n\nThe {} is stunned and can't act.", name);
+                summary += &format!("- The {} was stunned.\n", name);
+                continue;
+            }
+            if let Some((text, line)) = self.special_attack(enemy) {
+                output += &text;
+                summary += &line;
+                continue;
+            }
+            let target = self.select_target(enemy);
+            let result = self.resolve_damage(enemy, None, target);
+            output += &self.describe_strike(enemy, target, &result);
+            if result.damage > 0 && result.health > 0 {
+                output += &self.infect(enemy, target);
+            }
+            output += &self.wear_armor(target, result.damage);
+            summary += &self.summarize_strike(enemy, target, &result);
+        }
+        if fighting.len() > 1 && !summary.is_empty() {
+            output += &format!("\nThis round:\n{}", summary);
+        }
+        output
+    }
+
+    /// Describes an enemy's attack on a member of the party
+    fn describe_strike(&self, enemy: usize, target: usize, result: &DamageResult) -> String {
+        format!("\n\nThe {} attacks", self.objects[enemy].label[0])
+            + &self.describe_blow(target, result)
+    }
+
+    /// Describes how a blow landed on a member of the party
+    pub(crate) fn describe_blow(&self, target: usize, result: &DamageResult) -> String {
+        let mut output = String::new();
+        let name = &self.objects[target].label[0];
+
+        if result.damage == 0 {
+            if target == LOC_PLAYER {
+                output += "\nYou dodged the attack";
+            } else {
+                output += &format!("\nThe {} dodged the attack", name);
+            }
+        } else if target == LOC_PLAYER {
+            output += &format!(
+                "\n{}You got hit{}\nYour health: {}",
+                result.critical_text(),
+                result.effect_text(),
+                result.health
+            );
+        } else {
+            output += &format!(
+                "\n{}The {} got hit{}\n{} health: {}",
+                result.critical_text(),
+                name,
+                result.effect_text(),
+                name,
+                result.health
+            );
+        }
+        output + "\n"
+    }
+
+    /// One line on what an enemy did this round
+    pub(crate) fn summarize_strike(
+        &self,
+        enemy: usize,
+        target: usize,
+        result: &DamageResult,
+    ) -> String {
+        let target = if target == LOC_PLAYER {
+            "you".to_string()
+        } else {
+            format!("the {}", self.objects[target].label[0])
+        };
+        if result.damage == 0 {
+            format!(
+                "- The {} missed {}.\n",
+                self.objects[enemy].label[0], target
+            )
+        } else {
+            format!(
+                "- The {} hit {} for {}.\n",
+                self.objects[enemy].label[0], target, result.damage
+            )
+        }
+    }
+
+    /// Tells the player an enemy is down and lets it drop what it carried
+    pub(crate) fn defeat(&mut self, enemy: usize) -> String {
+        self.objects[enemy].threat.clear();
+        let name = self.objects[enemy].label[0].clone();
+        let loot = self.roll_loot(enemy);
+        if self.objects[enemy].knocked_out {
+            format!(
+                "\nYou knocked out the {}. You can capture or loot it now.\n",
+                name
+            ) + &loot
+        } else {
+            format!("\nYou killed the {}.\n", name) + &loot + &self.drop_carried(enemy)
+        }
+    }
+
+    /// Ends the fight with the player running from every enemy still standing.
+    /// Returns None if there was no fight to run from.
+    fn end_combat(&mut self) -> Option<String> {
+        let fighting = self.fighting_enemies();
+        let combat = self.combat.take()?;
+        for enemy in combat.enemies {
+            self.objects[enemy].threat.clear();
+        }
+        if fighting.is_empty() {
+            return None;
+        }
+
+        let names: Vec<String> = fighting
+            .iter()
+            .map(|enemy| {
+                self.objects[*enemy].pursuing = !self.objects[*enemy].roams.is_empty();
+                format!("the {}", self.objects[*enemy].label[0])
+            })
+            .collect();
+        Some(format!("You ran away from {}.\n", names.join(" and ")))
+    }
+}
@@ -0,0 +1,59 @@
+//! A record of every blow struck in a fight, so the player can look back on
+//! what happened when several enemies were going at them at once.
+use super::{timeline::last_entries, World};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// How many blows the log holds before it drops the oldest
+const COMBAT_LOG_LIMIT: usize = 100;
+
+/// One blow in a fight: who did what to whom, for how much, and how much
+/// health the target had left after it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CombatLogEntry {
+    pub turn: u64,
+    pub attacker: String,
+    pub action: String,
+    pub target: String,
+    pub damage: u64,
+    pub health: u64,
+}
+
+impl fmt::Display for CombatLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[turn {}] {} {}", self.turn, self.attacker, self.action)?;
+        if self.attacker == self.target {
+            write!(f, " {}", self.damage)?;
+        } else if self.damage == 0 {
+            write!(f, " {}", self.target)?;
+        } else {
+            write!(f, " {} for {}", self.target, self.damage)?;
+        }
+        write!(f, " ({} health left)", self.health)
+    }
+}
+
+impl World {
+    /// Writes a blow into the combat log, along with the health its target has left
+    pub(crate) fn log_blow(&mut self, attacker: String, action: &str, target: usize, damage: u64) {
+        self.combat_log.push(CombatLogEntry {
+            turn: self.turn,
+            attacker,
+            action: action.to_string(),
+            target: self.objects[target].label[0].clone(),
+            damage,
+            health: self.objects[target].health.unwrap_or(0),
+        });
+        if self.combat_log.len() > COMBAT_LOG_LIMIT {
+            self.combat_log.remove(0);
+        }
+    }
+
+    /// Shows the last few blows struck, ten unless the player asks for more
+    pub fn do_log(&self, noun: &str) -> String {
+        if self.combat_log.is_empty() {
+            return "You haven't been in a fight yet.\n".to_string();
+        }
+        last_entries(&self.combat_log, noun)
+    }
+}
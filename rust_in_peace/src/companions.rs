@@ -0,0 +1,244 @@
+//! Companions travelling with the player: who can be recruited, what they say
+//! along the way and how much they approve of the player's choices. A companion
+//! follows the player from place to place, strikes at the enemy the player is
+//! attacking every round of a fight, and goes back home when dismissed.
+use super::{Outcome, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+// A companion only speaks up every few turns
+const BANTER_INTERVAL: u64 = 5;
+
+/// A line a companion says once, optionally only in one place or after a flag is set
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Banter {
+    pub line: String,
+    #[serde(default)]
+    pub location: Option<usize>,
+    #[serde(default)]
+    pub flag: Option<String>,
+    #[serde(default)]
+    pub said: bool,
+}
+
+/// How a companion feels about something the player did, keyed by its flag
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Opinion {
+    pub flag: String,
+    pub change: i64,
+    #[serde(default)]
+    pub applied: bool,
+}
+
+/// Everything that makes an object a companion
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Companion {
+    #[serde(default)]
+    pub joined: bool,
+    #[serde(default)]
+    pub approval: i64,
+    #[serde(default)]
+    pub banter: Vec<Banter>,
+    #[serde(default)]
+    pub opinions: Vec<Opinion>,
+    /// Approval at which the companion asks the player for a favour
+    #[serde(default)]
+    pub quest_at: Option<i64>,
+    #[serde(default)]
+    pub quest_flag: Option<String>,
+    /// Approval below which the companion walks away
+    #[serde(default)]
+    pub leave_below: Option<i64>,
+    /// Where the companion goes back to when dismissed, or where it was
+    /// recruited if this isn't set
+    #[serde(default)]
+    pub home: Option<usize>,
+}
+
+impl World {
+    /// Returns the companions that have joined the player and are with them
+    pub fn companions(&self) -> Vec<usize> {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        self.objects
+            .iter()
+            .enumerate()
+            .filter(|(_, object)| {
+                object.companion.as_ref().map(|c| c.joined).unwrap_or(false)
+                    && object.location == player_loc
+            })
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// Player asks an npc here to come along with them
+    pub fn do_recruit(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+        if self.objects[index].location != self.objects[LOC_PLAYER].location {
+            return format!("The {} is too far away to ask.\n", name);
+        }
+        let here = self.objects[index].location;
+        let Some(companion) = self.objects[index].companion.as_mut() else {
+            return format!("The {} has no wish to travel with you.\n", name);
+        };
+        if companion.joined {
+            return format!("The {} is already travelling with you.\n", name);
+        }
+        companion.joined = true;
+        if companion.home.is_none() {
+            companion.home = here;
+        }
+        self.record(format!("{} joined the party", name.to_lowercase()));
+        format!(
+            "The {} joins you. They will follow you wherever you go.\n",
+            name
+        )
+    }
+
+    /// Player sends a companion travelling with them back home
+    pub fn do_dismiss(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+        if !self.companions().contains(&index) {
+            return format!("The {} isn't travelling with you.\n", name);
+        }
+        let Some(companion) = self.objects[index].companion.as_mut() else {
+            return format!("The {} isn't travelling with you.\n", name);
+        };
+        companion.joined = false;
+        let home = companion.home;
+        self.objects[index].location = home;
+        self.record(format!("{} left the party", name.to_lowercase()));
+        match home {
+            Some(home) if home != self.objects[LOC_PLAYER].location.unwrap_or(home) => format!(
+                "The {} heads back to the {}.\n",
+                name, self.objects[home].label[0]
+            ),
+            _ => format!("The {} stays behind.\n", name),
+        }
+    }
+
+    /// Every companion fighting alongside the player strikes at the enemy the
+    /// player is attacking, or the next one standing if that one is down
+    pub(crate) fn companions_turn(&mut self) -> String {
+        let mut output = String::new();
+        for companion in self.companions() {
+            if self.objects[companion].attack.unwrap_or(0) == 0
+                || self.objects[companion].health.unwrap_or(0) == 0
+            {
+                continue;
+            }
+            let fighting = self.fighting_enemies();
+            let Some(enemy) = self
+                .combat
+                .as_ref()
+                .map(|combat| combat.enemy)
+                .filter(|enemy| fighting.contains(enemy))
+                .or(fighting.first().copied())
+            else {
+                break;
+            };
+            let name = self.objects[companion].label[0].clone();
+            if self.is_stunned(companion) {
+                output += &format!("\nThe {} is stunned and can't act.", name);
+                continue;
+            }
+            let enemy_name = self.objects[enemy].label[0].clone();
+            let result = self.resolve_damage(companion, None, enemy);
+            if result.outcome == Outcome::Miss {
+                output += &format!("\nThe {} attacks the {} but misses.", name, enemy_name);
+                continue;
+            }
+            self.add_threat(enemy, companion, result.damage);
+            output += &format!(
+                "\n{}The {} attacks the {}.{}\nEnemy health: {}",
+                result.critical_text(),
+                name,
+                enemy_name,
+                result.effect_text(),
+                result.health
+            );
+            if result.health == 0 {
+                self.objects[enemy].knocked_out = false;
+                self.record_defeat(enemy);
+                output += &self.defeat(enemy);
+                if let Some(next) = self.fighting_enemies().first() {
+                    self.set_target(*next);
+                }
+            }
+        }
+        output
+    }
+
+    /// Updates approval from the player's choices and lets companions chat
+    pub(crate) fn run_companions(&mut self) -> String {
+        let mut output = String::new();
+        let mut favours = Vec::new();
+        let player_loc = self.objects[LOC_PLAYER].location;
+
+        for index in self.companions() {
+            let name = self.objects[index].label[0].clone();
+            let Some(companion) = self.objects[index].companion.as_mut() else {
+                continue;
+            };
+
+            for opinion in companion.opinions.iter_mut() {
+                if opinion.applied || !self.flags.contains(&opinion.flag) {
+                    continue;
+                }
+                opinion.applied = true;
+                companion.approval += opinion.change;
+                if opinion.change > 0 {
+                    output += &format!("\n{} approves.\n", name);
+                } else if opinion.change < 0 {
+                    output += &format!("\n{} disapproves.\n", name);
+                }
+            }
+
+            if let (Some(quest_at), Some(quest_flag)) = (companion.quest_at, &companion.quest_flag)
+            {
+                if companion.approval >= quest_at && !self.flags.contains(quest_flag) {
+                    favours.push(quest_flag.clone());
+                    self.journal
+                        .push(format!("{} trusts you enough to ask for a favour.", name));
+                    output += &format!("\n{} wants to talk to you about something.\n", name);
+                }
+            }
+
+            if companion
+                .leave_below
+                .is_some_and(|floor| companion.approval < floor)
+            {
+                companion.joined = false;
+                output += &format!("\n{} has had enough of you and leaves.\n", name);
+                self.objects[index].location = None;
+                continue;
+            }
+
+            if !self.turn.is_multiple_of(BANTER_INTERVAL) {
+                continue;
+            }
+            let line = companion.banter.iter_mut().find(|banter| {
+                !banter.said
+                    && banter.location.is_none_or(|loc| Some(loc) == player_loc)
+                    && banter
+                        .flag
+                        .as_ref()
+                        .is_none_or(|flag| self.flags.contains(flag))
+            });
+            if let Some(banter) = line {
+                banter.said = true;
+                output += &format!("\n{}: \"{}\"\n", name, banter.line);
+            }
+        }
+        for flag in favours {
+            self.set_flag(flag);
+        }
+        output
+    }
+}
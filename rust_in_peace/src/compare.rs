@@ -0,0 +1,124 @@
+//! Weighing weapons against each other. The player sees the stats of two
+//! weapons side by side, so choosing between them doesn't take remembering
+//! every number.
+use super::{AmbiguousOption, Distance, Slot, World, LOC_PLAYER};
+
+// Width of the column the names of the stats are printed in
+const STAT_WIDTH: usize = 12;
+// Width of the column for the first weapon
+const WEAPON_WIDTH: usize = 24;
+
+impl World {
+    /// Finds a weapon the player can see, or explains why it can't be compared
+    fn weapon_to_compare(&self, noun: &String) -> Result<usize, String> {
+        let (output, obj_opt) = self.object_visible(noun);
+        match obj_opt {
+            Some(index) if self.slot_for(index) == Some(Slot::Weapon) => Ok(index),
+            Some(index) => Err(format!(
+                "The {} is not a weapon.\n",
+                self.objects[index].label[0]
+            )),
+            None => Err(output),
+        }
+    }
+
+    /// Splits a name like "bow spear" into two weapons the player carries, as
+    /// long as it isn't the name of a single object, like "rusty sword"
+    fn weapon_pair(&self, noun: &str) -> Option<(usize, usize)> {
+        let (left, right) = noun
+            .split_once(' ')
+            .filter(|(_, right)| !right.contains(' '))?;
+        if !matches!(
+            self.object_index(noun, Some(LOC_PLAYER), Distance::OverThere),
+            AmbiguousOption::None
+        ) {
+            return None;
+        }
+        let carried_weapon =
+            |word: &str| match self.object_index(word, Some(LOC_PLAYER), Distance::Held) {
+                AmbiguousOption::Some(index) if self.slot_for(index) == Some(Slot::Weapon) => {
+                    Some(index)
+                }
+                _ => None,
+            };
+        let (first, second) = (carried_weapon(left)?, carried_weapon(right)?);
+        (first != second).then_some((first, second))
+    }
+
+    /// Lists the stats of a weapon in the order they are compared
+    fn weapon_stats(&self, index: usize) -> Vec<(&'static str, String)> {
+        let weapon = &self.objects[index];
+        let attack = match weapon.damage_range() {
+            (low, high) if low == high => high.to_string(),
+            (low, high) => format!("{}-{}", low, high),
+        };
+        vec![
+            ("Attack", attack),
+            ("Damage", weapon.damage_type().to_string()),
+            ("Accuracy", format!("{:+}", weapon.accuracy)),
+            ("Critical", format!("{:+}", weapon.critical)),
+            (
+                "On hit",
+                weapon
+                    .on_hit
+                    .as_ref()
+                    .map_or("-".to_string(), |effect| effect.to_string()),
+            ),
+            (
+                "Durability",
+                weapon.durability.map_or("-".to_string(), |durability| {
+                    format!("{}/{}", durability.current, durability.max)
+                }),
+            ),
+            ("Weight", weapon.weight.to_string()),
+            ("Rarity", weapon.rarity.to_string()),
+        ]
+    }
+
+    /// Player compares two weapons side by side. With only one named, it is
+    /// compared with the weapon they have ready, or the best one they carry.
+    /// Two carried weapons can be named one word each, as in "compare bow spear".
+    pub fn do_compare(&self, first: &String, second: Option<&String>) -> String {
+        if let (Some((first, second)), None) = (self.weapon_pair(first), second) {
+            return self.side_by_side(first, second);
+        }
+        let first = match self.weapon_to_compare(first) {
+            Ok(index) => index,
+            Err(output) => return output,
+        };
+        let second = match second {
+            Some(second) => match self.weapon_to_compare(second) {
+                Ok(index) => index,
+                Err(output) => return output,
+            },
+            None => match self.choose_weapon("") {
+                Ok(Some(index)) if index != first => index,
+                _ => {
+                    return format!(
+                        "You have no other weapon to compare the {} with. Try <compare <weapon> with <weapon>>.\n",
+                        self.objects[first].label[0]
+                    )
+                }
+            },
+        };
+        self.side_by_side(first, second)
+    }
+
+    /// Lays out the stats of two weapons in columns
+    fn side_by_side(&self, first: usize, second: usize) -> String {
+        let mut output = format!(
+            "{:<STAT_WIDTH$}{:<WEAPON_WIDTH$}{}\n",
+            "",
+            self.gear_name(first),
+            self.gear_name(second)
+        );
+        for ((stat, left), (_, right)) in self
+            .weapon_stats(first)
+            .into_iter()
+            .zip(self.weapon_stats(second))
+        {
+            output += &format!("{:<STAT_WIDTH$}{:<WEAPON_WIDTH$}{}\n", stat, left, right);
+        }
+        output
+    }
+}
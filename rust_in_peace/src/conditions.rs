@@ -0,0 +1,57 @@
+//! Passages that only open once something has happened in the world, answers
+//! in a conversation that are only offered once it has, and what completes a
+//! quest or a chapter of the story.
+use super::{QuestState, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// Something that has to be true before a passage can be used
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// The enemy has been killed or knocked out
+    EnemyDead(usize),
+    /// The player is carrying the item
+    ItemHeld(usize),
+    /// The flag has been set
+    Flag(String),
+    /// The flag hasn't been set yet
+    NotFlag(String),
+    /// The quest has got this far
+    Quest { quest: usize, state: QuestState },
+    /// The story variable has reached the value
+    AtLeast { variable: String, value: i64 },
+}
+
+impl World {
+    /// Returns true if the condition holds right now
+    pub fn condition_met(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::EnemyDead(enemy) => self
+                .objects
+                .get(*enemy)
+                .is_some_and(|enemy| enemy.health == Some(0)),
+            Condition::ItemHeld(item) => self.is_containing(Some(LOC_PLAYER), Some(*item)),
+            Condition::Flag(flag) => self.flags.contains(flag),
+            Condition::NotFlag(flag) => !self.flags.contains(flag),
+            Condition::Quest { quest, state } => self
+                .quests
+                .get(*quest)
+                .is_some_and(|quest| quest.state == *state),
+            Condition::AtLeast { variable, value } => self.variable(variable) >= *value,
+        }
+    }
+
+    /// Explains why the player can't use a passage yet, if they can't
+    pub(crate) fn passage_refusal(&self, passage: usize) -> Option<String> {
+        let condition = self.objects[passage].requires.as_ref()?;
+        if self.condition_met(condition) {
+            return None;
+        }
+        Some(match &self.objects[passage].blocked_text {
+            Some(text) => format!("{}\n", text),
+            None => format!(
+                "You can't go {} yet.\n",
+                self.objects[passage].label[0].to_lowercase()
+            ),
+        })
+    }
+}
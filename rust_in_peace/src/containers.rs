@@ -0,0 +1,146 @@
+//! Chests, bags and anything else other objects can be kept in. A container
+//! has to be opened before anything can be put in it or taken out, and what
+//! is inside an open one is listed along with it.
+use super::{split_count, AmbiguousOption, Command, Distance, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// Something other objects can be put in
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Container {
+    #[serde(default)]
+    pub open: bool,
+}
+
+impl World {
+    /// Returns true if an object is a container that is open
+    pub fn is_open(&self, index: usize) -> bool {
+        matches!(&self.objects[index].container, Some(container) if container.open)
+    }
+
+    /// Lists what is inside an open container, one level deep
+    pub(crate) fn list_contents(&self, container: usize) -> String {
+        if !self.is_open(container) {
+            return String::new();
+        }
+        (0..self.objects.len())
+            .filter(|index| {
+                !self.objects[*index].hidden && self.is_containing(Some(container), Some(*index))
+            })
+            .map(|index| {
+                format!(
+                    "    in the {}: {}\n",
+                    self.objects[container].label[0],
+                    self.stack_description(index)
+                )
+            })
+            .collect()
+    }
+
+    /// Finds a container the player can reach, or explains why they can't use it
+    fn reachable_container(&self, noun: &String) -> Result<usize, String> {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return Err(output);
+        };
+        let name = &self.objects[index].label[0];
+        if self.objects[index].container.is_none() {
+            return Err(format!("The {} can't hold anything.\n", name));
+        }
+        if self.get_distance(Some(LOC_PLAYER), Some(index)) > Distance::Here {
+            return Err(format!("The {} is not here.\n", name));
+        }
+        Ok(index)
+    }
+
+    /// Player opens a container to see what is inside
+    pub fn do_open(&mut self, noun: &String) -> String {
+        let index = match self.reachable_container(noun) {
+            Ok(index) => index,
+            Err(output) => return output,
+        };
+        let name = self.objects[index].label[0].clone();
+        if self.is_open(index) {
+            return format!("The {} is already open.\n", name);
+        }
+
+        if let Some(container) = self.objects[index].container.as_mut() {
+            container.open = true;
+        }
+        match self.list_contents(index) {
+            contents if contents.is_empty() => format!("You open the {}. It is empty.\n", name),
+            contents => format!("You open the {}.\n", name) + &contents,
+        }
+    }
+
+    /// Player puts something they carry into an open container
+    pub fn do_put(&mut self, item: &str, container: Option<&String>) -> String {
+        let Some(container) = container else {
+            return "Put it in what? Try <put <item> in <container>>.\n".to_string();
+        };
+        let container = match self.reachable_container(container) {
+            Ok(index) => index,
+            Err(output) => return output,
+        };
+        let (wanted, item) = split_count(item);
+        let command = Command::Put {
+            item: item.clone(),
+            container: None,
+        };
+        let (output, obj_opt) = self.get_possession(Some(LOC_PLAYER), command, &item);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+
+        let name = self.objects[container].label[0].clone();
+        if index == container {
+            return format!("The {} won't fit inside itself.\n", name);
+        }
+        if let Some(refusal) = self.keep_key_item(index) {
+            return refusal;
+        }
+        if !self.is_open(container) {
+            return format!(
+                "The {} is closed. Try <open {}>.\n",
+                name,
+                name.to_lowercase()
+            );
+        }
+        let count = match self.count_wanted(index, wanted) {
+            Ok(count) => count,
+            Err(refusal) => return refusal,
+        };
+        if count == self.count(index) {
+            self.put_away(index);
+        }
+        self.move_some(index, count, Some(container))
+    }
+
+    /// Player takes something out of an open container
+    pub fn do_get_from(&mut self, item: &str, container: &String) -> String {
+        let container = match self.reachable_container(container) {
+            Ok(index) => index,
+            Err(output) => return output,
+        };
+        let name = self.objects[container].label[0].clone();
+        if !self.is_open(container) {
+            return format!(
+                "The {} is closed. Try <open {}>.\n",
+                name,
+                name.to_lowercase()
+            );
+        }
+
+        let (wanted, item) = split_count(item);
+        let index = match self.object_index(&item, Some(container), Distance::Held) {
+            AmbiguousOption::Some(index) => index,
+            AmbiguousOption::Ambiguous => {
+                return self.offer_choice(&item, Some(container), Distance::Held)
+            }
+            AmbiguousOption::None => return format!("There is no {} in the {}.\n", item, name),
+        };
+        match self.count_wanted(index, wanted) {
+            Ok(count) => self.carry(index, count),
+            Err(refusal) => refusal,
+        }
+    }
+}
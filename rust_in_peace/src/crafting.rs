@@ -0,0 +1,135 @@
+//! Making things out of what the player carries, following the recipes of the
+//! world file. The player can ask for what they want to make by name, or put
+//! things together and see what comes of it. Most things can only be made
+//! once, but anything that stacks, like salves, can be made again and again.
+use super::{Command, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// How to make an object out of others the player carries
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Recipe {
+    pub makes: usize,
+    pub needs: Vec<usize>,
+}
+
+impl World {
+    /// Returns true if what a recipe makes is already out in the world and
+    /// can't be made again
+    fn already_made(&self, recipe: &Recipe) -> bool {
+        let made = &self.objects[recipe.makes];
+        made.quantity.is_none() && made.location.is_some()
+    }
+
+    /// Uses up the ingredients and gives the player what the recipe makes
+    fn make(&mut self, recipe: &Recipe, ingredients: &[usize]) -> String {
+        let name = self.objects[recipe.makes].label[0].clone();
+        let materials = ingredients
+            .iter()
+            .map(|ingredient| self.objects[*ingredient].label[0].clone())
+            .collect::<Vec<String>>()
+            .join(", ");
+        for ingredient in ingredients {
+            self.use_up(*ingredient);
+        }
+        match self.objects[recipe.makes].quantity {
+            Some(_) => self.add_one(recipe.makes, Some(LOC_PLAYER)),
+            None => self.objects[recipe.makes].location = Some(LOC_PLAYER),
+        }
+        format!("You make the {} from the {}.\n", name, materials)
+    }
+
+    /// Player makes something from the materials they carry
+    pub fn do_craft(&mut self, noun: &String) -> String {
+        let recipe = self.recipes.iter().find(|recipe| {
+            self.objects[recipe.makes]
+                .label
+                .iter()
+                .any(|label| label.to_lowercase() == noun.to_lowercase())
+        });
+        let Some(recipe) = recipe.cloned() else {
+            return format!("You don't know how to make a '{}'.\n", noun);
+        };
+        let name = self.objects[recipe.makes].label[0].clone();
+
+        if self.already_made(&recipe) {
+            return format!("You have already made the {}.\n", name);
+        }
+        let carried = recipe
+            .needs
+            .iter()
+            .map(|need| self.carried_like(*need).first().copied())
+            .collect::<Option<Vec<usize>>>();
+        match carried {
+            Some(ingredients) => self.make(&recipe, &ingredients),
+            None => {
+                let materials = recipe
+                    .needs
+                    .iter()
+                    .map(|need| self.objects[*need].label[0].clone())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                format!("To make the {} you need: {}.\n", name, materials)
+            }
+        }
+    }
+
+    /// Player puts one or two things they carry together, making whatever a
+    /// recipe makes out of exactly those
+    pub fn do_combine(&mut self, first: &String, second: Option<&String>) -> String {
+        if first.is_empty() {
+            return "Combine what? Try <combine <item> with <item>>.\n".to_string();
+        }
+        let command = Command::Combine {
+            first: first.clone(),
+            second: second.cloned(),
+        };
+        let mut ingredients = Vec::new();
+        for noun in std::iter::once(first).chain(second) {
+            let (output, obj_opt) = self.get_possession(Some(LOC_PLAYER), command.clone(), noun);
+            let Some(index) = obj_opt else {
+                return output;
+            };
+            ingredients.push(index);
+        }
+        if let [first, second] = ingredients[..] {
+            if first == second {
+                return format!(
+                    "You can't combine the {} with itself.\n",
+                    self.objects[first].label[0]
+                );
+            }
+        }
+
+        let labels = |indices: &[usize]| {
+            let mut labels: Vec<&String> = indices
+                .iter()
+                .map(|index| &self.objects[*index].label[0])
+                .collect();
+            labels.sort();
+            labels
+        };
+        let recipe = self
+            .recipes
+            .iter()
+            .find(|recipe| labels(&recipe.needs) == labels(&ingredients))
+            .cloned();
+        let names: Vec<String> = ingredients
+            .iter()
+            .map(|index| self.objects[*index].label[0].clone())
+            .collect();
+        match recipe {
+            None if names.len() == 1 => {
+                format!("You can't make anything from the {} alone.\n", names[0])
+            }
+            None => format!(
+                "The {} and the {} don't make anything together.\n",
+                names[0], names[1]
+            ),
+            Some(recipe) if self.already_made(&recipe) => format!(
+                "You have already made the {}.\n",
+                self.objects[recipe.makes].label[0]
+            ),
+            Some(recipe) => self.make(&recipe, &ingredients),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Creating the player's character at the start of a new game. The answers to
+//! the creation questions, a name, a class and where to spend the starting
+//! attribute points, are gathered into choices that any frontend can fill in,
+//! whether by asking the player or programmatically.
+use super::{Attribute, World, LOC_PLAYER};
+
+// Longest name a character can be given
+const MAX_NAME_LENGTH: usize = 20;
+
+/// The answers that make up a new character
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CharacterChoices {
+    pub name: String,
+    /// The name of the class to play as, or none to play without one
+    pub class: Option<String>,
+    /// Attributes to spend the starting points on, a point each
+    pub raises: Vec<Attribute>,
+}
+
+impl World {
+    /// Checks that the choices make a character in this world, without
+    /// changing anything
+    pub fn check_choices(&self, choices: &CharacterChoices) -> Result<(), String> {
+        let name = choices.name.trim();
+        if name.is_empty() {
+            return Err("Your character needs a name.".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "Names can be at most {} letters long.",
+                MAX_NAME_LENGTH
+            ));
+        }
+        if let Some(class) = &choices.class {
+            if !self
+                .classes
+                .iter()
+                .any(|known| known.name.to_lowercase() == class.to_lowercase())
+            {
+                return Err(format!("There is no class called {}.", class));
+            }
+        }
+        if choices.raises.len() as u64 > self.attribute_points {
+            return Err(format!(
+                "You only have {} attribute points to spend.",
+                self.attribute_points
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates the player's character from the choices, before the first turn
+    /// of a new game. Returns what the player sees, or why the choices don't
+    /// work, in which case nothing is changed.
+    pub fn create_character(&mut self, choices: &CharacterChoices) -> Result<String, String> {
+        if self.turn > 0 || self.objects[LOC_PLAYER].name.is_some() {
+            return Err("The character has already been created.".to_string());
+        }
+        self.check_choices(choices)?;
+
+        let name = choices.name.trim().to_string();
+        self.objects[LOC_PLAYER].name = Some(name.clone());
+        self.record(format!("{} set out on their journey", name));
+        let mut output = String::new();
+        if let Some(class) = &choices.class {
+            output += &self.do_class(class);
+        }
+        for attribute in &choices.raises {
+            output += &self.do_raise(&attribute.to_string());
+        }
+        Ok(output + &format!("Welcome, {}.\n", name))
+    }
+}
@@ -0,0 +1,122 @@
+//! Conversations that branch. An npc with a dialogue says one of its nodes and
+//! lists numbered answers, and typing a number answers it: the answer may
+//! change the world, like handing over an item or setting a flag, and leads on
+//! to the next node until the conversation ends. Answers can be held back until
+//! a condition holds, such as the player carrying an item.
+use super::{Change, Command, Condition, World};
+use serde::{Deserialize, Serialize};
+
+/// One thing an npc says in a conversation, with the answers the player can give
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DialogueNode {
+    pub text: String,
+    #[serde(default)]
+    pub responses: Vec<Response>,
+}
+
+/// Something the player can say back to an npc
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Response {
+    pub text: String,
+    /// Only offered while this holds
+    #[serde(default)]
+    pub requires: Option<Condition>,
+    /// Made to the world when the player gives this answer
+    #[serde(default)]
+    pub changes: Vec<Change>,
+    /// The node the conversation goes on to, or None to end it
+    #[serde(default)]
+    pub next: Option<usize>,
+}
+
+/// The conversation the player is in the middle of
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conversation {
+    pub npc: usize,
+    pub node: usize,
+}
+
+impl World {
+    /// Returns the answers to a node the player can give right now, by their
+    /// place among all of the node's answers
+    fn open_responses(&self, npc: usize, node: usize) -> Vec<usize> {
+        self.objects[npc]
+            .dialogue
+            .get(node)
+            .map(|node| {
+                (0..node.responses.len())
+                    .filter(|response| {
+                        node.responses[*response]
+                            .requires
+                            .as_ref()
+                            .is_none_or(|condition| self.condition_met(condition))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Has an npc say a node of its dialogue and lists the answers to it,
+    /// holding on to the conversation if there are any
+    pub(crate) fn say_node(&mut self, npc: usize, node: usize) -> String {
+        let Some(said) = self.objects[npc].dialogue.get(node) else {
+            return String::new();
+        };
+        let mut output = format!(
+            "The {} says: \"{}\"\n",
+            self.objects[npc].label[0], said.text
+        );
+        let open = self.open_responses(npc, node);
+        if open.is_empty() {
+            return output;
+        }
+        for (number, response) in open.iter().enumerate() {
+            output += &format!("{}. {}\n", number + 1, said.responses[*response].text);
+        }
+        self.conversation = Some(Conversation { npc, node });
+        output + "Type a number to answer.\n"
+    }
+
+    /// Turns a number typed during a conversation into the answer it stands
+    /// for. Returns None if there is no conversation or the input isn't the
+    /// number of an answer.
+    pub(crate) fn make_response(
+        &self,
+        command: &Command,
+        conversation: Option<Conversation>,
+    ) -> Option<Command> {
+        let Command::Unknown(input) = command else {
+            return None;
+        };
+        let Conversation { npc, node } = conversation?;
+        let number = input.parse::<usize>().ok()?.checked_sub(1)?;
+        let response = *self.open_responses(npc, node).get(number)?;
+        Some(Command::Respond {
+            npc,
+            node,
+            response,
+        })
+    }
+
+    /// Player gives an answer in a conversation, which makes its changes to
+    /// the world and carries the conversation on
+    pub fn do_respond(&mut self, npc: usize, node: usize, response: usize) -> String {
+        let Some(response) = self
+            .objects
+            .get(npc)
+            .and_then(|npc| npc.dialogue.get(node))
+            .and_then(|node| node.responses.get(response))
+            .cloned()
+        else {
+            return "You have nothing to answer.\n".to_string();
+        };
+        let output = format!("You say: \"{}\"\n", response.text);
+        for change in response.changes {
+            self.apply_change(change);
+        }
+        match response.next {
+            Some(next) => output + &self.say_node(npc, next),
+            None => output,
+        }
+    }
+}
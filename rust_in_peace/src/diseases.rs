@@ -0,0 +1,141 @@
+//! Diseases some enemies pass on with their blows. A disease lies low for a
+//! few turns before it shows, then costs the player health every turn, and
+//! more the longer it goes on. It never wears off by itself: only the right
+//! remedy, or a good rest somewhere safe like the Tavern, gets rid of it.
+use super::{World, LOC_PLAYER};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A disease an enemy carries, or the player has caught
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Disease {
+    pub name: String,
+    /// Chance in percent of a blow that draws blood passing it on
+    pub chance: u64,
+    /// Turns it lies low before it shows
+    pub incubation: u64,
+    /// Health it costs every turn once it shows
+    pub damage: u64,
+    /// Turns between it getting worse by a point of health a turn, or 0 if
+    /// it never does
+    #[serde(default)]
+    pub worsens: u64,
+    /// Turns the player has had it
+    #[serde(default)]
+    pub turns: u64,
+}
+
+impl Disease {
+    /// Returns the health the disease costs every turn at this stage of it
+    pub fn severity(&self) -> u64 {
+        if self.turns <= self.incubation {
+            return 0;
+        }
+        let worse = match self.worsens {
+            0 => 0,
+            worsens => (self.turns - self.incubation - 1) / worsens,
+        };
+        self.damage + worse
+    }
+}
+
+impl World {
+    /// Rolls whether an enemy's blow passes its disease on to the player
+    pub(crate) fn infect(&mut self, enemy: usize, target: usize) -> String {
+        let Some(disease) = self.objects[enemy].disease.clone() else {
+            return String::new();
+        };
+        if target != LOC_PLAYER
+            || self.objects[LOC_PLAYER]
+                .diseases
+                .iter()
+                .any(|caught| caught.name == disease.name)
+            || self.rng().gen_range(1..=100) > disease.chance
+        {
+            return String::new();
+        }
+        self.record(format!("player caught {}", disease.name));
+        let text = format!(
+            "The wound from the {} doesn't look right.\n",
+            self.objects[enemy].label[0]
+        );
+        self.objects[LOC_PLAYER].diseases.push(Disease {
+            turns: 0,
+            ..disease
+        });
+        text
+    }
+
+    /// Moves every disease the player has on by a turn, taking the health the
+    /// ones that show cost
+    pub(crate) fn run_diseases(&mut self) -> String {
+        let mut output = String::new();
+        let mut damage = 0;
+        for disease in self.objects[LOC_PLAYER].diseases.iter_mut() {
+            let before = disease.severity();
+            disease.turns += 1;
+            let after = disease.severity();
+            if before == 0 && after > 0 {
+                output += &format!("\nYou come down with {}.\n", disease.name);
+            } else if after > before {
+                output += &format!("\nYour {} gets worse.\n", disease.name);
+            }
+            damage += after;
+        }
+        let Some(health) = self.objects[LOC_PLAYER].health.filter(|h| *h > 0) else {
+            return output;
+        };
+        if damage == 0 {
+            return output;
+        }
+        let health = health.saturating_sub(damage);
+        self.objects[LOC_PLAYER].health = Some(health);
+        output + &format!("\nYou lose {} health. Your health: {}\n", damage, health)
+    }
+
+    /// Returns true if the object cures a disease the player has
+    pub(crate) fn cures_disease(&self, object: usize) -> bool {
+        self.objects[LOC_PLAYER]
+            .diseases
+            .iter()
+            .any(|disease| self.objects[object].cures.contains(&disease.name))
+    }
+
+    /// Cures the player of every disease the object is a remedy for
+    pub(crate) fn cure_with(&mut self, object: usize) -> String {
+        let cures = self.objects[object].cures.clone();
+        self.cure(|disease| cures.contains(&disease.name))
+    }
+
+    /// Cures the player of every disease they have, as a good rest does
+    pub(crate) fn cure_all(&mut self) -> String {
+        self.cure(|_| true)
+    }
+
+    /// Cures the player of the diseases that match
+    fn cure(&mut self, matches: impl Fn(&Disease) -> bool) -> String {
+        let diseases = &mut self.objects[LOC_PLAYER].diseases;
+        let (cured, kept) = diseases.drain(..).partition(|disease| matches(disease));
+        *diseases = kept;
+        cured
+            .iter()
+            .map(|disease: &Disease| format!("You are cured of {}.\n", disease.name))
+            .collect()
+    }
+
+    /// Describes the diseases the player has, for the character sheet
+    pub(crate) fn describe_diseases(&self) -> String {
+        let diseases = &self.objects[LOC_PLAYER].diseases;
+        if diseases.is_empty() {
+            return String::new();
+        }
+        let list: Vec<String> = diseases
+            .iter()
+            .map(|disease| match disease.severity() {
+                0 => format!("{} (not showing yet)", disease.name),
+                severity => format!("{} (-{} health per turn)", disease.name, severity),
+            })
+            .collect();
+        format!("diseases: {}\n", list.join(", "))
+    }
+}
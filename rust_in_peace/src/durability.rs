@@ -0,0 +1,131 @@
+//! Wear and tear on gear. Weapons lose a point of durability with every blow
+//! they land and armor with every blow it takes, and worn out gear only does
+//! half its job until it is repaired by a fireplace or with a whetstone.
+use super::{Distance, Object, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+// Gold it costs to restore each point of durability by a fireplace
+const REPAIR_COST: u64 = 1;
+
+/// How much more use a piece of gear can take before it wears out
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Durability {
+    pub current: u64,
+    pub max: u64,
+}
+
+impl Object {
+    /// Returns true if a piece of gear has worn out
+    pub fn is_worn_out(&self) -> bool {
+        self.durability
+            .is_some_and(|durability| durability.current == 0)
+    }
+}
+
+impl World {
+    /// Takes a point of durability off a piece of gear that was used, telling
+    /// the player when it wears out
+    pub(crate) fn wear(&mut self, index: usize) -> String {
+        let Some(durability) = self.objects[index].durability.as_mut() else {
+            return String::new();
+        };
+        if durability.current == 0 {
+            return String::new();
+        }
+        durability.current -= 1;
+        match durability.current {
+            0 => format!(
+                "\nYour {} has worn out. Try <repair {}>.",
+                self.objects[index].label[0],
+                self.objects[index].label[0].to_lowercase()
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// Describes how worn a piece of gear is, once it has seen some use
+    pub fn describe_durability(&self, index: usize) -> String {
+        match self.objects[index].durability {
+            Some(durability) if durability.current == 0 => {
+                format!("Durability: 0/{} (worn out).\n", durability.max)
+            }
+            Some(durability) if durability.current < durability.max => {
+                format!("Durability: {}/{}.\n", durability.current, durability.max)
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Wears down the player's armor when a blow gets through to them
+    pub(crate) fn wear_armor(&mut self, target: usize, damage: u64) -> String {
+        match self.worn_armor() {
+            Some(armor) if target == LOC_PLAYER && damage > 0 => self.wear(armor),
+            _ => String::new(),
+        }
+    }
+
+    /// Returns something the player can mend gear with where they are: a
+    /// fireplace here, which costs gold, or a whetstone they carry, which is used up
+    fn mender(&self) -> Option<usize> {
+        let menders = (0..self.objects.len()).filter(|index| self.objects[*index].mends);
+        menders
+            .clone()
+            .find(|index| {
+                !self.objects[*index].item
+                    && self.get_distance(Some(LOC_PLAYER), Some(*index)) == Distance::Here
+            })
+            .or_else(|| {
+                menders
+                    .clone()
+                    .find(|index| self.objects[*index].location == Some(LOC_PLAYER))
+            })
+    }
+
+    /// Player repairs a piece of gear they carry
+    pub fn do_repair(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+        let Some(durability) = self.objects[index].durability else {
+            return format!("The {} can't be repaired.\n", name);
+        };
+        if !self.is_containing(Some(LOC_PLAYER), Some(index)) {
+            return format!("You need to carry the {} to repair it.\n", name);
+        }
+        if durability.current >= durability.max {
+            return format!("The {} is already in perfect condition.\n", name);
+        }
+        let Some(mender) = self.mender() else {
+            return format!(
+                "There is nothing here to repair the {} with. Find a fireplace, or carry a whetstone.\n",
+                name
+            );
+        };
+
+        let mender_name = self.objects[mender].label[0].to_lowercase();
+        let output = if self.objects[mender].item {
+            self.use_up(mender);
+            format!("You work the {} over with a {}.", name, mender_name)
+        } else {
+            let cost = (durability.max - durability.current) * REPAIR_COST;
+            if self.objects[LOC_PLAYER].gold < cost {
+                return format!(
+                    "Repairing the {} costs {} gold and you have {}.\n",
+                    name, cost, self.objects[LOC_PLAYER].gold
+                );
+            }
+            self.objects[LOC_PLAYER].gold -= cost;
+            format!(
+                "You spend {} gold on coal and mend the {} by the {}.",
+                cost, name, mender_name
+            )
+        };
+        self.objects[index].durability = Some(Durability {
+            current: durability.max,
+            ..durability
+        });
+        output + " It is as good as new.\n"
+    }
+}
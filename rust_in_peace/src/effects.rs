@@ -0,0 +1,376 @@
+//! Temporary effects on the player and enemies, such as blessings and curses,
+//! that change how they fight until they wear off. Some of them tick every turn,
+//! and every round of a fight: poison, bleeding and burning eat away at health,
+//! regeneration restores it, and being stunned costs a fighter their turns.
+//! Effects with the same name can stack, making them worse with every dose.
+//! They come from what the player drinks, from enemies' special moves and from
+//! places, like the chill of the Stronghold, that wear on the player while they stay.
+use super::{Attribute, Skill, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A named change to an object's attack or defense that lasts some turns
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct StatusEffect {
+    pub name: String,
+    /// Added to the damage the object deals
+    #[serde(default)]
+    pub attack: i64,
+    /// Taken off the damage the object receives
+    #[serde(default)]
+    pub defense: i64,
+    /// Added to the chance in percent of dodging attacks against the object
+    #[serde(default)]
+    pub dodge: i64,
+    /// Health the object loses at the end of every turn
+    #[serde(default)]
+    pub damage: u64,
+    /// Health the object regains at the end of every turn
+    #[serde(default)]
+    pub heal: u64,
+    /// Leaves the object unable to act while it lasts
+    #[serde(default)]
+    pub stun: bool,
+    pub turns: u64,
+    /// How many doses of the effect have stacked up, where 0 is the same as 1
+    #[serde(default)]
+    pub stacks: u64,
+}
+
+impl StatusEffect {
+    /// Poison eating away at health every turn
+    pub fn poisoned(damage: u64, turns: u64) -> StatusEffect {
+        StatusEffect {
+            name: "Poisoned".to_string(),
+            damage,
+            turns,
+            ..Default::default()
+        }
+    }
+
+    /// An open wound bleeding every turn
+    pub fn bleeding(damage: u64, turns: u64) -> StatusEffect {
+        StatusEffect {
+            name: "Bleeding".to_string(),
+            damage,
+            turns,
+            ..Default::default()
+        }
+    }
+
+    /// Flames burning every turn
+    pub fn burning(damage: u64, turns: u64) -> StatusEffect {
+        StatusEffect {
+            name: "Burning".to_string(),
+            damage,
+            turns,
+            ..Default::default()
+        }
+    }
+
+    /// Wounds closing up every turn
+    pub fn regenerating(heal: u64, turns: u64) -> StatusEffect {
+        StatusEffect {
+            name: "Regenerating".to_string(),
+            heal,
+            turns,
+            ..Default::default()
+        }
+    }
+
+    /// Too dazed to act. An effect put on in a round counts that round down
+    /// too, so this lasts one turn less than it is given.
+    pub fn stunned(turns: u64) -> StatusEffect {
+        StatusEffect {
+            name: "Stunned".to_string(),
+            stun: true,
+            turns,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if all the effect does is heal, so it does nothing for
+    /// someone already at full health
+    pub fn only_heals(&self) -> bool {
+        self.heal > 0
+            && *self
+                == StatusEffect {
+                    name: self.name.clone(),
+                    heal: self.heal,
+                    turns: self.turns,
+                    stacks: self.stacks,
+                    ..Default::default()
+                }
+    }
+}
+
+impl fmt::Display for StatusEffect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.stacks > 1 {
+            write!(f, " x{}", self.stacks)?;
+        }
+        write!(f, " (")?;
+        if self.attack != 0 {
+            write!(f, "{:+} attack, ", self.attack)?;
+        }
+        if self.defense != 0 {
+            write!(f, "{:+} defense, ", self.defense)?;
+        }
+        if self.dodge != 0 {
+            write!(f, "{:+} dodge, ", self.dodge)?;
+        }
+        if self.damage != 0 {
+            write!(f, "-{} health per turn, ", self.damage)?;
+        }
+        if self.heal != 0 {
+            write!(f, "+{} health per turn, ", self.heal)?;
+        }
+        if self.stun {
+            write!(f, "can't act, ")?;
+        }
+        write!(f, "{} turns)", self.turns)
+    }
+}
+
+impl World {
+    /// Returns how much the object's effects, and for the player their decor, skills and
+    /// strength, add to the damage it deals
+    pub fn attack_bonus(&self, index: usize) -> i64 {
+        let player = if index == LOC_PLAYER {
+            self.decor_bonus().attack
+                + 3 * self.skill(Skill::Swordsmanship) as i64
+                + self.attribute_bonus(Attribute::Strength)
+        } else {
+            0
+        };
+        player
+            + self.objects[index]
+                .effects
+                .iter()
+                .map(|effect| effect.attack)
+                .sum::<i64>()
+    }
+
+    /// Returns how much the object's effects, and for the player their decor, skills,
+    /// armor and perks, take off the damage it receives
+    pub fn defense_bonus(&self, index: usize) -> i64 {
+        let player = if index == LOC_PLAYER {
+            self.decor_bonus().defense
+                + 2 * self.skill(Skill::Toughness) as i64
+                + self.armor_defense() as i64
+                + self.steadfast_defense()
+        } else {
+            0
+        };
+        player
+            + self.objects[index]
+                .effects
+                .iter()
+                .map(|effect| effect.defense)
+                .sum::<i64>()
+    }
+
+    /// Returns how much the object's effects, and for the player their agility,
+    /// add to its chance of dodging attacks
+    pub fn dodge_bonus(&self, index: usize) -> i64 {
+        let player = if index == LOC_PLAYER {
+            self.agility_dodge()
+        } else {
+            0
+        };
+        player
+            + self.objects[index]
+                .effects
+                .iter()
+                .map(|effect| effect.dodge)
+                .sum::<i64>()
+    }
+
+    /// Applies an effect bonus to an amount of damage without going below zero
+    pub fn adjust_damage(damage: u64, bonus: i64) -> u64 {
+        damage.saturating_add_signed(bonus)
+    }
+
+    /// Puts an effect on an object, replacing any effect with the same name
+    pub fn add_effect(&mut self, index: usize, effect: StatusEffect) {
+        let effects = &mut self.objects[index].effects;
+        effects.retain(|current| current.name != effect.name);
+        effects.push(StatusEffect {
+            stacks: effect.stacks.max(1),
+            ..effect
+        });
+    }
+
+    /// Returns true if one of the object's effects leaves it unable to act
+    pub fn is_stunned(&self, index: usize) -> bool {
+        self.objects[index].effects.iter().any(|effect| effect.stun)
+    }
+
+    /// Puts an effect on an object, adding it on top of any effect with the same
+    /// name so repeated blows make it worse
+    pub fn stack_effect(&mut self, index: usize, effect: StatusEffect) {
+        let effects = &mut self.objects[index].effects;
+        match effects
+            .iter_mut()
+            .find(|current| current.name == effect.name)
+        {
+            Some(current) => {
+                current.attack += effect.attack;
+                current.defense += effect.defense;
+                current.dodge += effect.dodge;
+                current.damage += effect.damage;
+                current.heal += effect.heal;
+                current.turns = current.turns.max(effect.turns);
+                current.stacks = current.stacks.max(1) + effect.stacks.max(1);
+            }
+            None => effects.push(StatusEffect {
+                stacks: effect.stacks.max(1),
+                ..effect
+            }),
+        }
+    }
+
+    /// Restores some of an object's health, never past the most it can have,
+    /// and returns how much it got back
+    pub(crate) fn restore_health(&mut self, index: usize, amount: u64) -> u64 {
+        let before = self.objects[index].health.unwrap_or(0);
+        let health = (before + amount).min(self.max_health_of(index).max(before));
+        if health > before {
+            self.objects[index].health = Some(health);
+            if self.combat.is_some() {
+                let name = self.objects[index].label[0].clone();
+                self.log_blow(name, "healed", index, health - before);
+            }
+        }
+        health - before
+    }
+
+    /// Gives back the health an object's effects restore this turn
+    fn effect_healing(&mut self, index: usize) -> String {
+        let heal: u64 = self.objects[index].effects.iter().map(|e| e.heal).sum();
+        if heal == 0 || self.objects[index].health.unwrap_or(0) == 0 {
+            return String::new();
+        }
+        let healed = self.restore_health(index, heal);
+        if healed == 0 {
+            return String::new();
+        }
+
+        let name = &self.objects[index].label[0];
+        let health = self.objects[index].health.unwrap_or(0);
+        if index == LOC_PLAYER {
+            format!("\nYou regain {} health. Your health: {}\n", healed, health)
+        } else if self.objects[index].enemy
+            && self.objects[index].location == self.objects[LOC_PLAYER].location
+        {
+            format!(
+                "\nThe {} regains {} health. {} health: {}\n",
+                name, healed, name, health
+            )
+        } else {
+            String::new()
+        }
+    }
+
+    /// Takes the health an object's effects cost it this turn. An enemy worn
+    /// down to nothing is defeated as if the player had struck the last blow,
+    /// though one that dies out of sight is only heard of.
+    fn effect_damage(&mut self, index: usize) -> String {
+        let damage: u64 = self.objects[index].effects.iter().map(|e| e.damage).sum();
+        let Some(before) = self.objects[index].health.filter(|h| *h > 0 && damage > 0) else {
+            return String::new();
+        };
+        let health = before.saturating_sub(damage);
+        self.objects[index].health = Some(health);
+        if self.combat.is_some() {
+            let causes: Vec<String> = self.objects[index]
+                .effects
+                .iter()
+                .filter(|effect| effect.damage > 0)
+                .map(|effect| effect.name.clone())
+                .collect();
+            self.log_blow(causes.join(" and "), "hurt", index, damage);
+        }
+
+        let name = self.objects[index].label[0].clone();
+        if index == LOC_PLAYER {
+            format!("\nYou lose {} health. Your health: {}\n", damage, health)
+        } else if !self.objects[index].enemy {
+            String::new()
+        } else if health > 0 {
+            if self.objects[index].location != self.objects[LOC_PLAYER].location {
+                return String::new();
+            }
+            format!(
+                "\nThe {} loses {} health. {} health: {}\n",
+                name, damage, name, health
+            )
+        } else {
+            let here = self.objects[index].location == self.objects[LOC_PLAYER].location;
+            self.record_defeat(index);
+            let mut output = self.defeat(index);
+            if !here {
+                output = format!("\nSomewhere out of sight, the {} succumbs.\n", name);
+            }
+            if self.combat.is_some() && self.fighting_enemies().is_empty() {
+                self.combat = None;
+            }
+            output
+        }
+    }
+
+    /// Puts the effect of the player's location on them, or keeps it from
+    /// running out while they stay there. Returns what the player sees when
+    /// it first comes over them.
+    pub(crate) fn environment_effect(&mut self) -> String {
+        let Some(effect) = self.objects[LOC_PLAYER]
+            .location
+            .and_then(|location| self.objects[location].environment.clone())
+        else {
+            return String::new();
+        };
+        let fresh = !self.objects[LOC_PLAYER]
+            .effects
+            .iter()
+            .any(|current| current.name == effect.name);
+        let text = format!("\nYou are {}.\n", effect);
+        // A turn longer, as the countdown below takes one off straight away
+        self.add_effect(
+            LOC_PLAYER,
+            StatusEffect {
+                turns: effect.turns + 1,
+                ..effect
+            },
+        );
+        match fresh {
+            true => text,
+            false => String::new(),
+        }
+    }
+
+    /// Counts down every effect and removes the ones that have run out
+    pub(crate) fn run_effects(&mut self) -> String {
+        // Only arriving somewhere tells the player what the place does to them
+        self.environment_effect();
+        let mut output = String::new();
+
+        for index in 0..self.objects.len() {
+            if self.objects[index].effects.is_empty() {
+                continue;
+            }
+            output += &self.effect_healing(index);
+            output += &self.effect_damage(index);
+            for effect in self.objects[index].effects.iter_mut() {
+                effect.turns = effect.turns.saturating_sub(1);
+                if effect.turns == 0 && index == LOC_PLAYER {
+                    output += &format!("\nYour {} wears off.\n", effect.name);
+                }
+            }
+            self.objects[index]
+                .effects
+                .retain(|effect| effect.turns > 0);
+        }
+        output
+    }
+}
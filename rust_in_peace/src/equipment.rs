@@ -0,0 +1,132 @@
+//! The gear the player has ready to use, one piece in each slot: the weapon
+//! they fight with, the armor they wear and a trinket that sharpens their
+//! aim, their eye for weak spots or their footwork.
+use super::{Object, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A place on the player for one piece of gear
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    Weapon,
+    Armor,
+    Trinket,
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Slot::Weapon => write!(f, "weapon"),
+            Slot::Armor => write!(f, "armor"),
+            Slot::Trinket => write!(f, "trinket"),
+        }
+    }
+}
+
+impl World {
+    /// Returns the slot an object goes in, if it can be equipped at all
+    pub fn slot_for(&self, index: usize) -> Option<Slot> {
+        let object = &self.objects[index];
+        if object.armor.is_some() {
+            Some(Slot::Armor)
+        } else if object.trinket {
+            Some(Slot::Trinket)
+        } else if object.item && !object.enemy && object.attack.is_some() {
+            Some(Slot::Weapon)
+        } else {
+            None
+        }
+    }
+
+    /// Returns what the player has equipped in a slot, as long as they still carry it
+    pub fn equipped(&self, slot: Slot) -> Option<usize> {
+        match slot {
+            Slot::Weapon => self.wielding,
+            Slot::Armor => self.wearing,
+            Slot::Trinket => self.trinket,
+        }
+        .filter(|index| self.is_containing(Some(LOC_PLAYER), Some(*index)))
+    }
+
+    /// Adds the stat of the player's trinket to one of their own
+    pub(crate) fn trinket_bonus(&self, fighter: usize, pick: fn(&Object) -> i64) -> i64 {
+        match self.equipped(Slot::Trinket) {
+            Some(trinket) if fighter == LOC_PLAYER => pick(&self.objects[trinket]),
+            _ => 0,
+        }
+    }
+
+    /// Player equips something they carry in the slot it belongs in, swapping
+    /// out whatever was there before
+    pub fn do_equip(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+
+        match self.slot_for(index) {
+            None => format!("You can't equip the {}.\n", name),
+            Some(Slot::Armor) => self.do_wear(noun),
+            Some(_) if !self.is_containing(Some(LOC_PLAYER), Some(index)) => {
+                format!("You are not holding any {}.\n", name)
+            }
+            Some(slot) if self.equipped(slot) == Some(index) => {
+                format!("You already have the {} equipped.\n", name)
+            }
+            Some(slot) => {
+                let output = match self.equipped(slot) {
+                    Some(old) => format!("You put away the {}. ", self.objects[old].label[0]),
+                    None => String::new(),
+                };
+                match slot {
+                    Slot::Weapon => self.wielding = Some(index),
+                    _ => self.trinket = Some(index),
+                }
+                output + &format!("You equip the {} as your {}.\n", name, slot)
+            }
+        }
+    }
+
+    /// Player empties a slot, named either by the slot or by what is in it
+    pub fn do_unequip(&mut self, noun: &String) -> String {
+        let slot = match noun.as_str() {
+            "" | "armor" => Slot::Armor,
+            "weapon" => Slot::Weapon,
+            "trinket" => Slot::Trinket,
+            _ => {
+                let (output, obj_opt) = self.object_visible(noun);
+                let Some(index) = obj_opt else {
+                    return output;
+                };
+                match self.slot_for(index) {
+                    Some(slot) if self.equipped(slot) == Some(index) => slot,
+                    _ => {
+                        return format!(
+                            "You don't have the {} equipped.\n",
+                            self.objects[index].label[0]
+                        )
+                    }
+                }
+            }
+        };
+
+        match (slot, self.equipped(slot)) {
+            (Slot::Armor, _) => self.do_take_off(),
+            (_, None) => format!("You don't have a {} equipped.\n", slot),
+            (_, Some(index)) => {
+                self.put_away(index);
+                format!("You put away the {}.\n", self.objects[index].label[0])
+            }
+        }
+    }
+
+    /// Empties whichever slot an object is equipped in
+    pub(crate) fn put_away(&mut self, index: usize) {
+        for slot in [&mut self.wielding, &mut self.wearing, &mut self.trinket] {
+            if *slot == Some(index) {
+                *slot = None;
+            }
+        }
+    }
+}
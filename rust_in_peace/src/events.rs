@@ -0,0 +1,81 @@
+//! Chance encounters when the player arrives somewhere, drawn from a table in
+//! the world file whose odds shift with the player's karma and reputation.
+use super::{Change, World};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// One in this many arrivals somewhere brings a random event
+const EVENT_ODDS: u32 = 8;
+
+/// Something that may happen to the player on arriving somewhere
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RandomEvent {
+    pub text: String,
+    /// How likely the event is before karma and reputation are counted
+    #[serde(default)]
+    pub weight: i64,
+    /// Added to the weight for every point of karma
+    #[serde(default)]
+    pub karma: i64,
+    /// Added to the weight for every point of reputation
+    #[serde(default)]
+    pub reputation: i64,
+    #[serde(default)]
+    pub changes: Vec<Change>,
+    /// Set for events that can only happen once
+    #[serde(default)]
+    pub once: bool,
+    #[serde(default)]
+    pub fired: bool,
+}
+
+impl World {
+    /// Returns how likely an event is for a player with the current karma and reputation
+    pub fn event_weight(&self, event: &RandomEvent) -> u64 {
+        if event.once && event.fired {
+            return 0;
+        }
+        let weight = event.weight + event.karma * self.karma + event.reputation * self.reputation;
+        weight.max(0) as u64
+    }
+
+    /// Picks an event from the table by weight
+    pub fn pick_event(&self, roll: u64) -> Option<usize> {
+        let mut roll = roll;
+        for (index, event) in self.events.iter().enumerate() {
+            let weight = self.event_weight(event);
+            if roll < weight {
+                return Some(index);
+            }
+            roll -= weight;
+        }
+        None
+    }
+
+    /// Sometimes sets off an event when the player arrives somewhere
+    pub(crate) fn random_event(&mut self) -> String {
+        let total: u64 = self
+            .events
+            .iter()
+            .map(|event| self.event_weight(event))
+            .sum();
+        let mut rng = self.rng();
+        if total == 0 || !rng.gen_ratio(1, EVENT_ODDS) {
+            return String::new();
+        }
+        match self.pick_event(rng.gen_range(0..total)) {
+            Some(index) => self.fire_event(index),
+            None => String::new(),
+        }
+    }
+
+    /// Applies an event's changes and tells the player what happened
+    pub fn fire_event(&mut self, index: usize) -> String {
+        self.events[index].fired = true;
+        self.record(format!("random event {} fired", index));
+        for change in self.events[index].changes.clone() {
+            self.apply_change(change);
+        }
+        format!("\n{}\n", self.events[index].text)
+    }
+}
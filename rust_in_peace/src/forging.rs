@@ -0,0 +1,113 @@
+//! Upgrading weapons at a forge, like the one in the Village. A weapon the
+//! world file gives tiers to can be worked up through them one at a time, each
+//! tier renaming it and adding to its attack, and each asking for more of the
+//! materials the player has to bring, such as whetstones and iron ore.
+use super::{Command, Distance, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// So many of an item that go into forging a tier
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Material {
+    pub item: usize,
+    pub quantity: u64,
+}
+
+/// A step up a weapon can be forged to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tier {
+    /// What the weapon is called once forged to this tier
+    pub name: String,
+    /// Added to the weapon's attack
+    pub attack: u64,
+    pub needs: Vec<Material>,
+}
+
+impl World {
+    /// Returns a forge where the player is, if there is one
+    fn forge_here(&self) -> Option<usize> {
+        (0..self.objects.len()).find(|index| {
+            self.objects[*index].forge
+                && self.get_distance(Some(LOC_PLAYER), Some(*index)) == Distance::Here
+        })
+    }
+
+    /// Names the materials a tier needs, with how many of each
+    fn materials(&self, tier: &Tier) -> String {
+        tier.needs
+            .iter()
+            .map(|material| {
+                format!(
+                    "{} {}",
+                    material.quantity, self.objects[material.item].label[0]
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// Player forges a weapon they carry up to its next tier
+    pub fn do_upgrade(&mut self, noun: &String) -> String {
+        if noun.is_empty() {
+            return "Upgrade what? Try <upgrade <weapon>>.\n".to_string();
+        }
+        let (output, obj_opt) =
+            self.get_possession(Some(LOC_PLAYER), Command::Upgrade(noun.clone()), noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+        if self.objects[index].tiers.is_empty() {
+            return format!("The {} can't be upgraded.\n", name);
+        }
+        let Some(tier) = self.objects[index]
+            .tiers
+            .get(self.objects[index].tier)
+            .cloned()
+        else {
+            return format!("The {} can't be improved any further.\n", name);
+        };
+        let Some(forge) = self.forge_here() else {
+            return format!(
+                "There is no forge here to work the {} at. Try the one in the Village.\n",
+                name
+            );
+        };
+        let materials = self.materials(&tier);
+        if tier.needs.iter().any(|material| {
+            self.carried_like(material.item)
+                .iter()
+                .map(|carried| self.count(*carried))
+                .sum::<u64>()
+                < material.quantity
+        }) {
+            return format!(
+                "To forge the {} into a {} you need: {}.\n",
+                name, tier.name, materials
+            );
+        }
+
+        for material in &tier.needs {
+            for _ in 0..material.quantity {
+                let carried = self.carried_like(material.item);
+                self.use_up(carried[0]);
+            }
+        }
+        let weapon = &mut self.objects[index];
+        // The first tier's name goes in front of the weapon's own names, and
+        // every later one takes the place of the tier before it
+        match weapon.tier {
+            0 => weapon.label.insert(0, tier.name.clone()),
+            _ => weapon.label[0] = tier.name.clone(),
+        }
+        weapon.tier += 1;
+        weapon.attack = Some(weapon.attack.unwrap_or(0) + tier.attack);
+        format!(
+            "You forge the {} into a {} at the {}, using {}. Its attack rises to {}.\n",
+            name,
+            tier.name,
+            self.objects[forge].label[0].to_lowercase(),
+            materials,
+            self.objects[index].attack.unwrap_or(0)
+        )
+    }
+}
@@ -1,8 +1,11 @@
 //! This is the game library module.
 //! It contains critical functions like get_input(), update_state(), and update_screen()
 //! that are crucual for running the game
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::fs::read_to_string;
 use std::io::stdout;
@@ -11,7 +14,129 @@ use std::path::Path;
 use std::thread;
 use std::time::Duration;
 
-// Indices of all the objects in the game
+mod abilities;
+mod aliases;
+mod armor;
+mod attributes;
+mod barricades;
+mod bestiary;
+mod bosses;
+mod brewing;
+mod capture;
+mod carrying;
+mod checks;
+mod choices;
+mod classes;
+mod combat;
+mod combat_log;
+mod companions;
+mod compare;
+mod conditions;
+mod containers;
+mod crafting;
+mod creation;
+mod dialogue;
+mod diseases;
+mod durability;
+mod effects;
+mod equipment;
+mod events;
+mod forging;
+mod grammar;
+mod help;
+mod home;
+mod host;
+mod items;
+mod light;
+mod lint;
+mod loans;
+mod locks;
+mod loot;
+mod mounts;
+mod perks;
+mod persuasion;
+mod quests;
+mod rarity;
+mod reactions;
+mod resting;
+mod roaming;
+mod save;
+mod schedules;
+mod search;
+mod sheet;
+mod shop;
+mod shrines;
+mod stacks;
+mod stamina;
+mod stealth;
+mod story;
+mod suggest;
+mod survival;
+mod talk;
+mod taming;
+mod throwing;
+mod timeline;
+mod trade;
+mod training;
+mod traps;
+mod travel;
+mod validation;
+mod wealth;
+mod weather;
+pub use abilities::{Ability, SpecialMove};
+pub use attributes::Attribute;
+pub use bestiary::BestiaryEntry;
+pub use bosses::Phase;
+pub use brewing::{Brew, Potency};
+pub use capture::Intel;
+pub use checks::SkillCheck;
+pub use choices::Choice;
+pub use classes::Class;
+pub use combat::{CombatState, DamageResult, DamageType, Outcome};
+pub use combat_log::CombatLogEntry;
+pub use companions::{Banter, Companion, Opinion};
+pub use conditions::Condition;
+pub use containers::Container;
+pub use crafting::Recipe;
+pub use creation::CharacterChoices;
+pub use dialogue::{Conversation, DialogueNode, Response};
+pub use diseases::Disease;
+pub use durability::Durability;
+pub use effects::StatusEffect;
+pub use equipment::Slot;
+pub use events::RandomEvent;
+pub use forging::{Material, Tier};
+use grammar::{join_words, split_clause, split_count, strip_articles};
+pub use grammar::{tokenize, ParseError, Token};
+pub use home::Decor;
+pub use host::{serve_metrics, serve_players, Metrics, Reply, SessionHost, MAX_SESSIONS};
+pub use loans::Loan;
+pub use loot::LootDrop;
+pub use perks::Perk;
+use persuasion::Approach;
+pub use quests::{Quest, QuestState};
+pub use rarity::{Quality, Rarity, StatRoll};
+pub use reactions::{Change, Reaction};
+pub use save::Autosave;
+pub use schedules::Stop;
+pub use shop::Stock;
+pub use shrines::Shrine;
+use stamina::{ATTACK_COST, DEFEND_RECOVERY, FLEE_COST, TRAVEL_COST};
+pub use stealth::WeightClass;
+pub use story::Chapter;
+pub use suggest::edit_distance;
+pub use talk::Talk;
+pub use taming::Taming;
+pub use timeline::TimelineEntry;
+pub use training::{Lesson, Skill};
+pub use validation::{Diagnostic, Severity};
+pub use weather::Weather;
+
+// The world file shipped with the game, compiled into the binary
+const BUNDLED_WORLD: &str = include_str!("../game_file.ron");
+
+// Indices of objects in the bundled world. Custom worlds may hold any number of
+// locations, but must keep the player at LOC_PLAYER.
 pub const LOC_FOREST: usize = 0;
 pub const LOC_DUNGEONS: usize = 1;
 pub const LOC_CAVE: usize = 2;
@@ -36,16 +161,114 @@ pub enum Distance {
 }
 
 /// Command enum containing all the command prompts
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Command {
     Drop(String),
     Get(String),
-    Attack(String),
+    GetFrom {
+        item: String,
+        container: String,
+    },
+    Put {
+        item: String,
+        container: Option<String>,
+    },
+    Open(String),
+    Eat(String),
+    Repair(String),
+    Upgrade(String),
+    Respond {
+        npc: usize,
+        node: usize,
+        response: usize,
+    },
+    Throw {
+        item: String,
+        target: Option<String>,
+    },
+    Feed {
+        target: String,
+        food: Option<String>,
+    },
+    Attack {
+        target: String,
+        weapon: Option<String>,
+    },
+    Compare {
+        first: String,
+        second: Option<String>,
+    },
     Look(String),
     Go(String),
+    Capture(String),
+    Loot(String),
+    TurnIn(String),
+    Interrogate(String),
+    Talk(String),
+    Persuade(String),
+    Intimidate(String),
+    Recruit(String),
+    Dismiss(String),
+    Buy(String),
+    Sell(String),
+    Shop,
+    Haggle,
+    Mount(String),
+    Unlock(String),
+    Craft(String),
+    Combine {
+        first: String,
+        second: Option<String>,
+    },
+    Brew(String),
+    Place(String),
+    Barricade(String),
+    Travel(String),
+    Offer(String),
+    Borrow(String),
+    Repay(String),
+    Light(String),
+    Train(String),
+    Use {
+        item: String,
+        target: Option<String>,
+    },
+    Wear(String),
+    Equip(String),
+    Unequip(String),
+    Log(String),
+    Alias(String),
+    #[cfg(feature = "debug")]
+    Timeline(String),
     Unknown(String),
+    Malformed(ParseError),
+    Dismount,
+    Taunt,
+    Sneak,
+    Defend,
+    Run,
+    TakeOff,
+    Yes,
+    No,
+    Rest,
+    Back,
+    Search,
+    Again,
+    Journal,
+    Attributes,
+    Stats,
+    Rolls(String),
+    Survival(String),
+    Wait,
+    Pick(String),
+    Class(String),
+    Perks(String),
+    Raise(String),
+    Bestiary,
+    Time,
     Inventory,
     Quit,
-    Help,
+    Help(String),
     Map,
 }
 
@@ -55,19 +278,129 @@ impl fmt::Display for Command {
         match self {
             Command::Drop(_) => write!(f, "drop"),
             Command::Get(_) => write!(f, "get"),
-            Command::Attack(_) => write!(f, "attack"),
+            Command::GetFrom { .. } => write!(f, "get"),
+            Command::Put { .. } => write!(f, "put"),
+            Command::Open(_) => write!(f, "open"),
+            Command::Eat(_) => write!(f, "eat"),
+            Command::Repair(_) => write!(f, "repair"),
+            Command::Upgrade(_) => write!(f, "upgrade"),
+            Command::Respond { .. } => write!(f, "respond"),
+            Command::Throw { .. } => write!(f, "throw"),
+            Command::Feed { .. } => write!(f, "feed"),
+            Command::Attack { .. } => write!(f, "attack"),
+            Command::Compare { .. } => write!(f, "compare"),
             Command::Go(_) => write!(f, "go"),
+            Command::Capture(_) => write!(f, "capture"),
+            Command::Loot(_) => write!(f, "loot"),
+            Command::TurnIn(_) => write!(f, "turn in"),
+            Command::Interrogate(_) => write!(f, "interrogate"),
+            Command::Talk(_) => write!(f, "talk"),
+            Command::Persuade(_) => write!(f, "persuade"),
+            Command::Intimidate(_) => write!(f, "intimidate"),
+            Command::Journal => write!(f, "journal"),
+            Command::Attributes => write!(f, "attributes"),
+            Command::Stats => write!(f, "stats"),
+            Command::Rolls(_) => write!(f, "rolls"),
+            Command::Survival(_) => write!(f, "survival"),
+            Command::Wait => write!(f, "wait"),
+            Command::Pick(_) => write!(f, "pick"),
+            Command::Class(_) => write!(f, "class"),
+            Command::Perks(_) => write!(f, "perks"),
+            Command::Raise(_) => write!(f, "raise"),
+            Command::Bestiary => write!(f, "bestiary"),
+            Command::Time => write!(f, "time"),
+            Command::Recruit(_) => write!(f, "recruit"),
+            Command::Dismiss(_) => write!(f, "dismiss"),
+            Command::Buy(_) => write!(f, "buy"),
+            Command::Sell(_) => write!(f, "sell"),
+            Command::Shop => write!(f, "shop"),
+            Command::Haggle => write!(f, "haggle"),
+            Command::Mount(_) => write!(f, "mount"),
+            Command::Unlock(_) => write!(f, "unlock"),
+            Command::Craft(_) => write!(f, "craft"),
+            Command::Combine { .. } => write!(f, "combine"),
+            Command::Brew(_) => write!(f, "brew"),
+            Command::Place(_) => write!(f, "place"),
+            Command::Barricade(_) => write!(f, "barricade"),
+            Command::Travel(_) => write!(f, "travel"),
+            Command::Offer(_) => write!(f, "offer"),
+            Command::Borrow(_) => write!(f, "borrow"),
+            Command::Repay(_) => write!(f, "repay"),
+            Command::Light(_) => write!(f, "light"),
+            Command::Train(_) => write!(f, "train"),
+            Command::Use { .. } => write!(f, "use"),
+            Command::Taunt => write!(f, "taunt"),
+            Command::Sneak => write!(f, "sneak"),
+            Command::Defend => write!(f, "defend"),
+            Command::Run => write!(f, "run"),
+            Command::Wear(_) => write!(f, "wear"),
+            Command::Equip(_) => write!(f, "equip"),
+            Command::Unequip(_) => write!(f, "unequip"),
+            Command::TakeOff => write!(f, "take off"),
+            Command::Log(_) => write!(f, "log"),
+            Command::Alias(_) => write!(f, "alias"),
+            #[cfg(feature = "debug")]
+            Command::Timeline(_) => write!(f, "timeline"),
+            Command::Yes => write!(f, "yes"),
+            Command::No => write!(f, "no"),
+            Command::Rest => write!(f, "rest"),
+            Command::Back => write!(f, "back"),
+            Command::Again => write!(f, "again"),
+            Command::Search => write!(f, "search"),
+            Command::Dismount => write!(f, "dismount"),
             Command::Inventory => write!(f, "inventory"),
             Command::Look(_) => write!(f, "look"),
             Command::Quit => write!(f, "quit"),
             Command::Unknown(_) => write!(f, "unknown"),
-            Command::Help => write!(f, "help"),
+            Command::Malformed(_) => write!(f, "malformed"),
+            Command::Help(_) => write!(f, "help"),
             Command::Map => write!(f, "map"),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl Command {
+    /// Whether carrying out the command lets time pass in the world
+    pub fn takes_time(&self) -> bool {
+        !matches!(
+            self,
+            Command::Inventory
+                | Command::Compare { .. }
+                | Command::Quit
+                | Command::Help(_)
+                | Command::Map
+                | Command::Journal
+                | Command::Attributes
+                | Command::Stats
+                | Command::Rolls(_)
+                | Command::Survival(_)
+                | Command::Class(_)
+                | Command::Perks(_)
+                | Command::Raise(_)
+                | Command::Bestiary
+                | Command::Time
+                | Command::Shop
+                | Command::Log(_)
+                | Command::Alias(_)
+                | Command::Yes
+                | Command::No
+                | Command::Again
+                | Command::Unknown(_)
+                | Command::Malformed(_)
+        )
+    }
+}
+
+/// How an enemy picks which party member to attack
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Targeting {
+    #[default]
+    Random,
+    LowestHealth,
+    HighestThreat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 /// The object struct
 pub struct Object {
     pub label: Vec<String>,
@@ -77,8 +410,309 @@ pub struct Object {
     pub item: bool,
     pub enemy: bool,
     pub health: Option<u64>,
+    /// The most health the object can be healed back up to. Worlds that don't
+    /// say get the health the object starts with when they are loaded.
+    #[serde(default)]
+    pub max_health: Option<u64>,
     pub attack: Option<u64>,
     pub consumable: bool,
+    #[serde(default)]
+    pub targeting: Targeting,
+    #[serde(default)]
+    pub threat: BTreeMap<usize, u64>,
+    /// Same as a damage type of Blunt, kept for older world files
+    #[serde(default)]
+    pub blunt: bool,
+    #[serde(default)]
+    pub capturable: bool,
+    #[serde(default)]
+    pub knocked_out: bool,
+    #[serde(default)]
+    pub captured: bool,
+    #[serde(default)]
+    pub bounty: Option<u64>,
+    #[serde(default)]
+    pub bounty_board: bool,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default)]
+    pub intel: Vec<Intel>,
+    #[serde(default)]
+    pub companion: Option<Companion>,
+    /// Gold in the player's purse, the gold an enemy drops when it is defeated,
+    /// what a trader has to pay with, or a pile of it lying around
+    #[serde(default)]
+    pub gold: u64,
+    #[serde(default)]
+    pub price: Option<u64>,
+    /// What an item is worth to a trader, once it has been bought
+    #[serde(default)]
+    pub value: Option<u64>,
+    /// Set on valuables, which are good for nothing but selling
+    #[serde(default)]
+    pub treasure: bool,
+    /// Set on npcs who keep a stock of items to sell and buy items from the player
+    #[serde(default)]
+    pub trader: bool,
+    /// What a trader keeps in stock and restocks every so often
+    #[serde(default)]
+    pub restock: Vec<Stock>,
+    /// Percent a trader takes off its prices, or puts on them, since the player
+    /// last haggled with it. None if they haven't since it last restocked.
+    #[serde(default)]
+    pub haggle: Option<i64>,
+    #[serde(default)]
+    pub mount: bool,
+    /// Weight a mount carries for the player while they ride it
+    #[serde(default)]
+    pub carries: u64,
+    #[serde(default)]
+    pub no_mounts: bool,
+    #[serde(default)]
+    pub stable: bool,
+    #[serde(default)]
+    pub weight_class: WeightClass,
+    #[serde(default)]
+    pub locked_by: Option<usize>,
+    /// Locations a roaming enemy wanders between
+    #[serde(default)]
+    pub roams: Vec<usize>,
+    /// Damage a trap deals to whatever gets caught in it
+    #[serde(default)]
+    pub trap: Option<u64>,
+    #[serde(default)]
+    pub snared_by: Option<usize>,
+    /// Turns a material holds an exit shut when used as a barricade
+    #[serde(default)]
+    pub barricade: Option<u64>,
+    #[serde(default)]
+    pub barricaded: u64,
+    /// Set on a roaming enemy the player ran away from, so it follows them
+    #[serde(default)]
+    pub pursuing: bool,
+    /// What has to happen before a passage can be used
+    #[serde(default)]
+    pub requires: Option<Condition>,
+    #[serde(default)]
+    pub blocked_text: Option<String>,
+    #[serde(default)]
+    pub effects: Vec<StatusEffect>,
+    /// Effect a location has on the player while they are in it, which lasts
+    /// a while after they leave
+    #[serde(default)]
+    pub environment: Option<StatusEffect>,
+    /// Disease an enemy passes on with its blows
+    #[serde(default)]
+    pub disease: Option<Disease>,
+    /// Diseases the player has caught
+    #[serde(default)]
+    pub diseases: Vec<Disease>,
+    /// Names of the diseases a consumable cures
+    #[serde(default)]
+    pub cures: Vec<String>,
+    #[serde(default)]
+    pub shrine: Option<Shrine>,
+    #[serde(default)]
+    pub lender: bool,
+    /// Set on the enemy sent after players who don't pay back a loan
+    #[serde(default)]
+    pub collector: bool,
+    /// Set on the location the player owns
+    #[serde(default)]
+    pub home: bool,
+    /// Breath for strenuous actions, for the player. None never tires.
+    #[serde(default)]
+    pub stamina: Option<u64>,
+    /// Set on locations where the player can rest without being disturbed
+    #[serde(default)]
+    pub safe: bool,
+    #[serde(default)]
+    pub decor: Option<Decor>,
+    /// Set on locations the player can't see in without a light
+    #[serde(default)]
+    pub dark: bool,
+    /// Set on torches and lanterns
+    #[serde(default)]
+    pub light: bool,
+    #[serde(default)]
+    pub lit: bool,
+    /// Set on lights with an open flame, which rain puts out
+    #[serde(default)]
+    pub flame: bool,
+    /// Set on locations under the open sky, where the weather reaches the player
+    #[serde(default)]
+    pub outdoors: bool,
+    /// Set on outdoor locations where snow piles up deep enough to tire the
+    /// player wading through it
+    #[serde(default)]
+    pub snowdrifts: bool,
+    /// Skills a trainer teaches
+    #[serde(default)]
+    pub teaches: Vec<Lesson>,
+    /// Set on things that can't be seen or used until the player searches for them
+    #[serde(default)]
+    pub hidden: bool,
+    /// Damage taken off every blow whoever wears this armor receives
+    #[serde(default)]
+    pub armor: Option<u64>,
+    /// Added to the chance in percent that this fighter's or weapon's attacks land
+    #[serde(default)]
+    pub accuracy: i64,
+    /// Added to the chance in percent that a landed attack is a critical hit
+    #[serde(default)]
+    pub critical: i64,
+    /// Chance in percent of dodging an attack that would otherwise land
+    #[serde(default)]
+    pub dodge: i64,
+    /// Lowest damage this weapon or fighter deals, up to its attack
+    #[serde(default)]
+    pub min_attack: Option<u64>,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Kinds of damage that only do half as much to this fighter
+    #[serde(default)]
+    pub resists: Vec<DamageType>,
+    /// Kinds of damage that do half again as much to this fighter
+    #[serde(default)]
+    pub weak_to: Vec<DamageType>,
+    /// Set on bosses that never let the player run from a fight
+    #[serde(default)]
+    pub blocks_flee: bool,
+    /// Added to the roll deciding who strikes first when a fight starts
+    #[serde(default)]
+    pub initiative: i64,
+    /// Set on enemies that are sleeping until something wakes them
+    #[serde(default)]
+    pub asleep: bool,
+    /// Special moves an enemy may use on its turn in a fight
+    #[serde(default)]
+    pub specials: Vec<SpecialMove>,
+    /// Effect a weapon leaves on the enemies it hits
+    #[serde(default)]
+    pub on_hit: Option<StatusEffect>,
+    /// Effect a consumable puts on the player when they take it
+    #[serde(default)]
+    pub buff: Option<StatusEffect>,
+    /// The stages a boss goes through as its health drops
+    #[serde(default)]
+    pub phases: Vec<Phase>,
+    /// How many of its phases a boss has gone through
+    #[serde(default)]
+    pub phase: usize,
+    /// What this character says when the player talks to them
+    #[serde(default)]
+    pub talk: Option<Talk>,
+    /// What an npc says in a conversation that branches on the player's
+    /// answers, starting from the first node
+    #[serde(default)]
+    pub dialogue: Vec<DialogueNode>,
+    /// Set on the people of the world who aren't out for a fight, which the
+    /// player can talk to but not attack
+    #[serde(default)]
+    pub npc: bool,
+    /// Set on enemies the player has talked out of fighting
+    #[serde(default)]
+    pub calmed: bool,
+    /// Set on trinkets, whose accuracy, critical and dodge add to the player's
+    /// own while equipped
+    #[serde(default)]
+    pub trinket: bool,
+    /// How heavy an item is to carry
+    #[serde(default)]
+    pub weight: u64,
+    /// Decides how much weight a character can carry, with no limit if it isn't set
+    #[serde(default)]
+    pub strength: Option<u64>,
+    /// Adds to the player's chance of dodging attacks
+    #[serde(default)]
+    pub agility: Option<u64>,
+    /// Helps the player in checks like persuading or interrogating someone
+    #[serde(default)]
+    pub intellect: Option<u64>,
+    /// How hard the lock on a passage is to pick, if it can be picked at all
+    #[serde(default)]
+    pub pick_difficulty: Option<i64>,
+    /// How hard a passage is to climb, for passages the player has to climb
+    #[serde(default)]
+    pub climb: Option<i64>,
+    /// The name the player gave their character
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The class the player picked at the start of the game
+    #[serde(default)]
+    pub class: Option<usize>,
+    /// The perks the player picked as they levelled up
+    #[serde(default)]
+    pub perks: Vec<Perk>,
+    /// Shoots from afar, like a bow
+    #[serde(default)]
+    pub ranged: bool,
+    /// How much hunger eating the object takes away
+    #[serde(default)]
+    pub food: u64,
+    /// How much thirst eating or drinking the object takes away
+    #[serde(default)]
+    pub water: u64,
+    /// How many there are of an item that comes in stacks
+    #[serde(default)]
+    pub quantity: Option<u64>,
+    /// Set on objects other objects can be put in
+    #[serde(default)]
+    pub container: Option<Container>,
+    /// How rare a piece of gear is
+    #[serde(default)]
+    pub rarity: Rarity,
+    /// The range a piece of gear's attack or protection is rolled in when the
+    /// world is loaded
+    #[serde(default)]
+    pub stat_roll: Option<StatRoll>,
+    /// How well a piece of gear's stats rolled, once they have been
+    #[serde(default)]
+    pub quality: Option<Quality>,
+    /// What an enemy may drop when it is defeated
+    #[serde(default)]
+    pub loot: Vec<LootDrop>,
+    /// Set on items the story needs, which the player can't drop, throw away or use up
+    #[serde(default)]
+    pub key_item: bool,
+    /// What is known about an enemy, revealed in the bestiary once it is defeated
+    #[serde(default)]
+    pub lore: Option<String>,
+    /// Set on animals that can be tamed with food instead of fought
+    #[serde(default)]
+    pub taming: Option<Taming>,
+    /// Where a character goes at each hour of the day
+    #[serde(default)]
+    pub schedule: Vec<Stop>,
+    /// How much more use a piece of gear can take before it wears out
+    #[serde(default)]
+    pub durability: Option<Durability>,
+    /// Set on fireplaces, whetstones and anything else gear can be repaired with
+    #[serde(default)]
+    pub mends: bool,
+    /// Set on fires and anything else potions can be brewed over
+    #[serde(default)]
+    pub brewing: bool,
+    /// Set on forges and anything else weapons can be upgraded at
+    #[serde(default)]
+    pub forge: bool,
+    /// The tiers a weapon can be forged up through, in order
+    #[serde(default)]
+    pub tiers: Vec<Tier>,
+    /// How many of its tiers the weapon has been forged up through
+    #[serde(default)]
+    pub tier: usize,
+    /// Set on the copies the game makes of objects, such as part of a split
+    /// stack, whose place can be taken by another copy once they are gone
+    #[serde(default)]
+    pub copy: bool,
+}
+
+impl Object {
+    /// Passages are objects that lead from their location to a destination
+    pub fn is_passage(&self) -> bool {
+        self.location.is_some() && self.destination.is_some()
+    }
 }
 
 /// Handles any ambiguous directions
@@ -93,382 +727,170 @@ pub enum AmbiguousOption<T> {
 /// The world struct
 pub struct World {
     pub objects: Vec<Object>,
+    #[serde(default)]
+    pub karma: i64,
+    #[serde(default)]
+    pub reputation: i64,
+    #[serde(default)]
+    pub flags: BTreeSet<String>,
+    #[serde(default)]
+    pub journal: Vec<String>,
+    #[serde(default)]
+    pub turn: u64,
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+    #[serde(default)]
+    pub riding: Option<usize>,
+    /// The armor the player has put on
+    #[serde(default)]
+    pub wearing: Option<usize>,
+    /// The weapon the player fights with unless they name another
+    #[serde(default)]
+    pub wielding: Option<usize>,
+    /// The trinket the player has on, adding its stats to their own
+    #[serde(default)]
+    pub trinket: Option<usize>,
+    #[serde(default)]
+    pub recipes: Vec<Recipe>,
+    /// Potions the player can brew over a fire
+    #[serde(default)]
+    pub brews: Vec<Brew>,
+    /// Quests npcs can give the player
+    #[serde(default)]
+    pub quests: Vec<Quest>,
+    /// Earned by completing quests
+    #[serde(default)]
+    pub experience: u64,
+    /// Whether the player sees the rolls behind skill checks
+    #[serde(default)]
+    pub show_rolls: bool,
+    /// Whether the player has to eat and drink to keep their strength up
+    #[serde(default)]
+    pub survival: bool,
+    /// How hungry the player is, from 0 for full up
+    #[serde(default)]
+    pub hunger: u64,
+    /// How thirsty the player is, from 0 for not at all
+    #[serde(default)]
+    pub thirst: u64,
+    /// What the sky is doing over the outdoor locations
+    #[serde(default)]
+    pub weather: Weather,
+    /// Points the player has yet to spend on their attributes
+    #[serde(default)]
+    pub attribute_points: u64,
+    /// Perks the player has yet to pick
+    #[serde(default)]
+    pub perk_points: u64,
+    /// The classes the player can start the game as
+    #[serde(default)]
+    pub classes: Vec<Class>,
+    /// The chapters of the main storyline, in order
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    /// The chapter the player is in, which is past the last once the story is over
+    #[serde(default)]
+    pub chapter: usize,
+    /// Numbers the story keeps count with, alongside its flags
+    #[serde(default)]
+    pub variables: BTreeMap<String, i64>,
+    /// What the player has learned about the enemies they came across
+    #[serde(default)]
+    pub bestiary: BTreeMap<usize, BestiaryEntry>,
+    #[serde(default)]
+    pub visited: BTreeSet<usize>,
+    /// Locations the player has left, most recent last
+    #[serde(default)]
+    pub history: Vec<usize>,
+    /// Where the player asked to travel while waiting for them to confirm
+    #[serde(default)]
+    pub confirm_travel: Option<usize>,
+    #[serde(default)]
+    pub events: Vec<RandomEvent>,
+    /// Enemies that may come upon the player resting somewhere unsafe
+    #[serde(default)]
+    pub encounters: Vec<usize>,
+    #[serde(default)]
+    pub loan: Option<Loan>,
+    #[serde(default)]
+    pub skills: BTreeMap<Skill, u64>,
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+    /// The fight the player is in, if any
+    #[serde(default)]
+    pub combat: Option<CombatState>,
+    /// Every blow struck in the player's fights, most recent last
+    #[serde(default)]
+    pub combat_log: Vec<CombatLogEntry>,
+    /// Words players can use for commands on top of the built-in ones, such as
+    /// "slay" for "attack"
+    #[serde(default)]
+    pub synonyms: BTreeMap<String, String>,
+    /// Shorthand the player has made up for commands, such as "atk" for
+    /// "attack bandits"
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// The object the player last mentioned, which "it" stands for
+    #[serde(default)]
+    pub last_referenced: Cell<Option<usize>>,
+    /// The command waiting on the player to pick one of several objects by number
+    #[serde(default)]
+    pub choice: Option<Choice>,
+    /// The conversation waiting on the player to answer by number
+    #[serde(default)]
+    pub conversation: Option<Conversation>,
+    /// Objects just listed for the player to pick from
+    #[serde(skip)]
+    pub offered: RefCell<Vec<usize>>,
+    /// The object the player picked, which wins over the others with its name
+    #[serde(default)]
+    pub chosen: Cell<Option<usize>>,
+    /// The last command the game understood, which "again" repeats
+    #[serde(default)]
+    pub last_command: Option<Command>,
+    /// Seed for every dice roll in the game, picked when a new game starts
+    #[serde(default = "rand::random")]
+    pub seed: u64,
+    /// How many dice have been rolled so far
+    #[serde(default)]
+    pub rolls: Cell<u64>,
 }
 
 /// The game struct
 impl World {
+    /// Builds the world bundled with the game from its world file
     pub fn new() -> Self {
-        World {
-            objects: vec![
-                Object {
-                    label: vec!["Forest".to_string()],
-                    description: "Look out for tree people".to_string(),
-                    location: None,
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Dungeons".to_string()],
-                    description: "Be aware of the trolls in the dungeon.".to_string(),
-                    location: None,
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Cave".to_string()],
-                    description: "Watch out for bats and look for light.".to_string(),
-                    location: None,
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Tavern".to_string()],
-                    description:
-                        "The tavern is empty. But the fire is still burning in the fireplace."
-                            .to_string(),
-                    location: None,
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Village".to_string()],
-                    description:
-                        "An abandoned village. It has been ransacked by a group of bandits."
-                            .to_string(),
-                    location: None,
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Stronghold".to_string()],
-                    description:
-                        "A stronghold. It is heavily guarded by a group of bandits."
-                            .to_string(),
-                    location: None,
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Player".to_string()],
-                    description: "You".to_string(),
-                    location: Some(LOC_FOREST),
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: Some(100),
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Bear".to_string()],
-                    description: "A bear (enemy)".to_string(),
-                    location: Some(LOC_CAVE),
-                    destination: None,
-                    item: false,
-                    enemy: true,
-                    health: Some(100),
-                    attack: Some(20),
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Troll (enemy)".to_string()],
-                    description: "A troll".to_string(),
-                    location: Some(LOC_DUNGEONS),
-                    destination: None,
-                    item: false,
-                    enemy: true,
-                    health: Some(100),
-                    attack: Some(20),
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Bandits (enemy)".to_string()],
-                    description: "A group of bandits".to_string(),
-                    location: Some(LOC_STRONGHOLD),
-                    destination: None,
-                    item: false,
-                    enemy: true,
-                    health: Some(100),
-                    attack: Some(30),
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Sword".to_string()],
-                    description: "A rusty sword.".to_string(),
-                    location: Some(LOC_DUNGEONS),
-                    destination: None,
-                    item: true,
-                    enemy: false,
-                    health: None,
-                    attack: Some(20),
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Bow".to_string()],
-                    description: "A bow.".to_string(),
-                    location: Some(LOC_TAVERN),
-                    destination: None,
-                    item: true,
-                    enemy: false,
-                    health: None,
-                    attack: Some(10),
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Bones".to_string()],
-                    description: "Bones of some animal.".to_string(),
-                    location: Some(LOC_CAVE),
-                    destination: None,
-                    item: true,
-                    enemy: false,
-                    health: None,
-                    attack: Some(5),
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Spear".to_string()],
-                    description: "A spear.".to_string(),
-                    location: Some(LOC_VILLAGE),
-                    destination: None,
-                    item: true,
-                    enemy: false,
-                    health: None,
-                    attack: Some(25),
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["Apple".to_string()],
-                    description: "An apple (Get it to increase health)".to_string(),
-                    location: Some(LOC_TAVERN),
-                    destination: None,
-                    item: true,
-                    enemy: false,
-                    health: Some(10),
-                    attack: None,
-                    consumable: true,
-                },
-                Object {
-                    label: vec!["Potion".to_string()],
-                    description: "A vial of healing potion (Get it to increase health)  (Hint: Type <get potion> to consume it)".to_string(),
-                    location: Some(LOC_VILLAGE),
-                    destination: None,
-                    item: true,
-                    enemy: false,
-                    health: Some(20),
-                    attack: None,
-                    consumable: true,
-                },
-                Object {
-                    label: vec!["North".to_string()],
-                    description: "A path to the north leading out of the forest leading to an old Tavern"
-                        .to_string(),
-                    location: Some(LOC_FOREST),
-                    destination: Some(LOC_TAVERN),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["South".to_string()],
-                    description: "A path to the south leading back to the forest".to_string(),
-                    location: Some(LOC_TAVERN),
-                    destination: Some(LOC_FOREST),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["East".to_string()],
-                    description: "A path to the east leading to the Dungeons".to_string(),
-                    location: Some(LOC_TAVERN),
-                    destination: Some(LOC_DUNGEONS),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["West".to_string()],
-                    description: "A path to the west leading to an abandoned village".to_string(),
-                    location: Some(LOC_TAVERN),
-                    destination: Some(LOC_VILLAGE),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["East".to_string()],
-                    description: "A path to the east leading to the tavern".to_string(),
-                    location: Some(LOC_VILLAGE),
-                    destination: Some(LOC_TAVERN),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["North".to_string()],
-                    description: "A path to the north leading to a stronghold".to_string(),
-                    location: Some(LOC_VILLAGE),
-                    destination: Some(LOC_STRONGHOLD),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["South".to_string()],
-                    description: "A path to the south leading to the village".to_string(),
-                    location: Some(LOC_STRONGHOLD),
-                    destination: Some(LOC_VILLAGE),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["West".to_string()],
-                    description: "A path to the west leading to the Tavern".to_string(),
-                    location: Some(LOC_DUNGEONS),
-                    destination: Some(LOC_TAVERN),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["North".to_string()],
-                    description: "A path to the north into a cave".to_string(),
-                    location: Some(LOC_DUNGEONS),
-                    destination: Some(LOC_CAVE),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["South".to_string()],
-                    description: "A path to the south into the dungeons".to_string(),
-                    location: Some(LOC_CAVE),
-                    destination: Some(LOC_DUNGEONS),
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["West".to_string(), "East".to_string(), "South".to_string()],
-                    description: "You see nothing but trees. There is no other path in that direction."
-                        .to_string(),
-                    location: Some(LOC_FOREST),
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["West".to_string(), "East".to_string(), "North".to_string()],
-                    description: "There is no other path in that direction."
-                        .to_string(),
-                    location: Some(LOC_STRONGHOLD),
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["North".to_string(), "".to_string()],
-                    description: "There is no other path in that direction.".to_string(),
-                    location: Some(LOC_TAVERN),
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["East".to_string(),"West".to_string()],
-                    description: "There is no other path in that direction.".to_string(),
-                    location: Some(LOC_VILLAGE),
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["East".to_string(), "South".to_string()],
-                    description:
-                        "You see only big rocks and boulders. There is no other path in that direction."
-                            .to_string(),
-                    location: Some(LOC_DUNGEONS),
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-                Object {
-                    label: vec!["East".to_string(), "North".to_string(), "West".to_string()],
-                    description: "The cave has no paths in that direction".to_string(),
-                    location: Some(LOC_CAVE),
-                    destination: None,
-                    item: false,
-                    enemy: false,
-                    health: None,
-                    attack: None,
-                    consumable: false,
-                },
-            ],
-        }
+        let mut world: World =
+            ron::from_str(BUNDLED_WORLD).expect("The bundled world file is invalid");
+        world.start_game();
+        world
+    }
+
+    /// Readies a world that was just read in for a new game to be played in it
+    pub(crate) fn start_game(&mut self) {
+        self.record_max_health();
+        self.roll_gear();
     }
 
     // We are adding reading from file, first step is to read from file.
     pub fn read_from_file(game_file: &str) -> Result<World, std::io::Error> {
+        let mut world = World::parse_file(game_file)?;
+        let errors: Vec<String> = world
+            .validate()
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        if errors.is_empty() {
+            world.start_game();
+            Ok(world)
+        } else {
+            Err(std::io::Error::other(errors.join("\n")))
+        }
+    }
+
+    /// Deserializes a world file without validating it
+    pub fn parse_file(game_file: &str) -> Result<World, std::io::Error> {
         let game_file_path = Path::new(game_file);
         let game_file_data_res = read_to_string(game_file_path);
 
@@ -478,30 +900,44 @@ impl World {
                     ron::from_str(&game_file_data);
 
                 match deserialized_data {
-                    Ok(deserialized_ron) => Ok(deserialized_ron),
-                    Err(de_err_str) => Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        de_err_str.to_string(),
-                    )),
+                    Ok(mut deserialized_ron) => {
+                        deserialized_ron.record_max_health();
+                        Ok(deserialized_ron)
+                    }
+                    Err(de_err_str) => Err(std::io::Error::other(de_err_str.to_string())),
                 }
             }
             Err(file_err) => Err(file_err),
         }
     }
 
+    /// Dice for the world. Each roll depends only on the seed and how many rolls
+    /// came before it, so replaying the same commands replays the same game.
+    pub fn rng(&self) -> StdRng {
+        let rolls = self.rolls.get();
+        self.rolls.set(rolls + 1);
+        StdRng::seed_from_u64(self.seed ^ rolls.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+    /// Returns true if every enemy the player has to beat is dead or calmed
+    pub fn won(&self) -> bool {
+        let mut required = self
+            .objects
+            .iter()
+            .filter(|object| object.enemy && !object.optional)
+            .peekable();
+        required.peek().is_some() && required.all(|enemy| enemy.health == Some(0) || enemy.calmed)
+    }
+
     /// Check of the game is over
     pub fn game_over(&self) -> bool {
-        if self.objects[LOC_PLAYER].health == Some(0) {
-            true
-        } else if self.objects[LOC_BEAR].health == Some(0)
-            && self.objects[LOC_TROLL].health == Some(0)
-            && self.objects[LOC_BANDITS].health == Some(0)
-        {
-            println!("You have defeated all enemies! You win!");
-            true
-        } else {
-            false
-        }
+        self.objects[LOC_PLAYER].health == Some(0) || self.won()
+    }
+
+    /// Returns what the player is told when they have won the game
+    pub fn win_message(&self) -> Option<&'static str> {
+        self.won()
+            .then_some("You have defeated all enemies! You win!\n")
     }
 
     /// Function for getting the type writer effect
@@ -513,16 +949,40 @@ impl World {
         }
     }
 
-    /// Check if the object has a label
+    /// Check if the object has a label. Words in front of the label, as in
+    /// "rusty sword", have to be found in the object's description or be how
+    /// well it rolled, and stacks also go by the plural of their label.
     fn object_with_label(&self, object: &Object, noun: &str) -> bool {
-        let mut result = false;
-        for (_, label) in object.label.iter().enumerate() {
-            if label.to_lowercase() == noun.to_lowercase() {
-                result = true;
-                break;
+        let noun = noun.to_lowercase();
+        let singular = match object.quantity {
+            Some(_) => noun.strip_suffix('s').unwrap_or(&noun),
+            None => &noun,
+        };
+        let description = object.description.to_lowercase();
+        let quality = object
+            .quality
+            .and_then(|quality| quality.adjective())
+            .map(str::to_lowercase);
+        let described: Vec<&str> = description
+            .split(|c: char| !c.is_alphanumeric())
+            .chain(quality.as_deref())
+            .collect();
+        object.label.iter().any(|label| {
+            let label = label.to_lowercase();
+            match noun
+                .strip_suffix(label.as_str())
+                .or_else(|| singular.strip_suffix(label.as_str()))
+            {
+                Some("") => true,
+                Some(adjectives) => {
+                    adjectives.ends_with(' ')
+                        && adjectives
+                            .split_whitespace()
+                            .all(|adjective| described.contains(&adjective))
+                }
+                None => false,
             }
-        }
-        result
+        })
     }
 
     /// Get the index of the object
@@ -532,19 +992,35 @@ impl World {
         from: Option<usize>,
         max_distance: Distance,
     ) -> AmbiguousOption<usize> {
-        let mut result: AmbiguousOption<usize> = AmbiguousOption::None;
-        for (position, object) in self.objects.iter().enumerate() {
-            if self.object_with_label(object, noun)
-                && self.get_distance(from, Some(position)) <= max_distance
-            {
-                if result == AmbiguousOption::None {
-                    result = AmbiguousOption::Some(position);
-                } else {
-                    result = AmbiguousOption::Ambiguous;
-                }
-            }
+        let matches = self.matching_objects(noun, from, max_distance);
+        match matches[..] {
+            [] => AmbiguousOption::None,
+            [position] => AmbiguousOption::Some(position),
+            _ => match self.chosen.get().filter(|chosen| matches.contains(chosen)) {
+                Some(chosen) => AmbiguousOption::Some(chosen),
+                None => AmbiguousOption::Ambiguous,
+            },
         }
-        result
+    }
+
+    /// Get the indices of every object a name fits
+    fn matching_objects(
+        &self,
+        noun: &str,
+        from: Option<usize>,
+        max_distance: Distance,
+    ) -> Vec<usize> {
+        let referenced = |position| {
+            noun.eq_ignore_ascii_case("it") && self.last_referenced.get() == Some(position)
+        };
+        (0..self.objects.len())
+            .filter(|position| {
+                let object = &self.objects[*position];
+                !object.hidden
+                    && (self.object_with_label(object, noun) || referenced(*position))
+                    && self.get_distance(from, Some(*position)) <= max_distance
+            })
+            .collect()
     }
 
     /// Checks if the object is visible
@@ -553,29 +1029,47 @@ impl World {
         let obj_not_here = self.object_index(noun, Some(LOC_PLAYER), Distance::NotHere);
 
         match (obj_over_there, obj_not_here) {
-            // Return none if not a valid command
-            (AmbiguousOption::None, AmbiguousOption::None) => {
-                ("Invalid command!!".to_string(), None)
+            (AmbiguousOption::None, AmbiguousOption::None) if noun.eq_ignore_ascii_case("it") => {
+                ("I don't know what you mean by 'it'.\n".to_string(), None)
             }
+            // Return none if not a valid command, unless it looks like a typo
+            (AmbiguousOption::None, AmbiguousOption::None) => match self.guess_object(noun) {
+                Some((label, true)) => self.object_visible(&label),
+                Some((label, false)) => (format!("Did you mean '{}'?\n", label), None),
+                None => ("Invalid command!!".to_string(), None),
+            },
             (AmbiguousOption::None, AmbiguousOption::Some(_)) => {
                 (format!("You don't see any '{}' here.\n", noun), None)
             }
             // Ambiguous object name
-            (AmbiguousOption::Ambiguous, _)
-            | (AmbiguousOption::None, AmbiguousOption::Ambiguous) => (
+            (AmbiguousOption::Ambiguous, _) => (
+                self.offer_choice(noun, Some(LOC_PLAYER), Distance::OverThere),
+                None,
+            ),
+            (AmbiguousOption::None, AmbiguousOption::Ambiguous) => (
                 format!("Please be more specific about which {} you mean. Try typing out the location.\n", noun),
                 None,
             ),
-            (AmbiguousOption::Some(index), _) => (String::new(), Some(index)),
+            (AmbiguousOption::Some(index), _) => {
+                self.last_referenced.set(Some(index));
+                (String::new(), Some(index))
+            }
         }
     }
 
     /// Lists all objects in a location
     fn list_objects(&self, location: usize) -> (String, u64) {
+        if self.is_dark(location) {
+            return (
+                "\nIt is too dark to see what is around you.\n".to_string(),
+                0,
+            );
+        }
         let mut result = String::new();
         let mut count: u64 = 0;
         for (pos, object) in self.objects.iter().enumerate() {
             if pos != LOC_PLAYER
+                && !object.hidden
                 && self.is_containing(Some(location), Some(pos))
                 && object.label.len() == 1
             {
@@ -583,7 +1077,24 @@ impl World {
                     result += "\nYou see:\n";
                 }
                 count += 1;
-                result += &format!("{}\n", object.description);
+                match object.price {
+                    Some(price) => {
+                        result += &format!(
+                            "{} (for sale: {} gold)\n",
+                            self.listed_description(pos),
+                            price
+                        )
+                    }
+                    None if object.asleep => {
+                        result += &format!("{} (asleep)\n", object.description)
+                    }
+                    None if object.calmed => result += &format!("{} (calm)\n", object.description),
+                    None if object.companion.as_ref().is_some_and(|c| c.joined) => {
+                        result += &format!("{} (travelling with you)\n", object.description)
+                    }
+                    None => result += &format!("{}\n", self.listed_description(pos)),
+                }
+                result += &self.list_contents(pos);
             }
         }
         (result, count)
@@ -591,141 +1102,282 @@ impl World {
 
     /// Updates state of the game
     pub fn update_state(&mut self, command: &Command) -> String {
+        let choice = self.choice.take();
+        let conversation = self.conversation.take();
+        let expanded = self
+            .make_choice(command, choice)
+            .or_else(|| self.make_response(command, conversation))
+            .or_else(|| self.repeat_command(command))
+            .or_else(|| self.expand_alias(command))
+            .or_else(|| self.expand_synonyms(command))
+            .or_else(|| self.correct_verb(command));
+        let command = expanded.as_ref().unwrap_or(command);
+        let start = self.objects[LOC_PLAYER].location;
+        self.visited.extend(start);
+        let travel = self.confirm_travel.take();
+        let output = match self.fight(command) {
+            Some(output) => output,
+            None => self.run_command(command, travel),
+        };
+        self.chosen.set(None);
+        if !matches!(
+            command,
+            // Answers only make sense in the conversation they were given in
+            Command::Unknown(_) | Command::Malformed(_) | Command::Again | Command::Respond { .. }
+        ) {
+            self.last_command = Some(command.clone());
+        }
+        let offered = self.offered.take();
+        if !offered.is_empty() {
+            self.choice = Some(Choice {
+                command: command.clone(),
+                options: offered,
+            });
+        }
+
+        let turns = if start != self.objects[LOC_PLAYER].location {
+            self.travel_turns()
+        } else if command.takes_time() {
+            1
+        } else {
+            0
+        };
+        (0..turns).fold(output, |output, _| output + &self.tick())
+    }
+
+    /// Swaps "again" for the last command the game understood. Returns None if
+    /// the command is something else or there is nothing to repeat yet.
+    fn repeat_command(&self, command: &Command) -> Option<Command> {
+        match command {
+            Command::Again => self.last_command.clone(),
+            _ => None,
+        }
+    }
+
+    /// Rewrites a command the parser didn't know using the world's own synonyms.
+    /// Returns None if none of them fit.
+    fn expand_synonyms(&self, command: &Command) -> Option<Command> {
+        let Command::Unknown(input) = command else {
+            return None;
+        };
+        self.synonyms.iter().find_map(|(phrase, verb)| {
+            let rest = input.strip_prefix(phrase.to_lowercase().as_str())?;
+            (rest.is_empty() || rest.starts_with(' ')).then(|| parse(format!("{}{}", verb, rest)))
+        })
+    }
+
+    /// Carries out a command outside of a fight
+    fn run_command(&mut self, command: &Command, travel: Option<usize>) -> String {
         match command {
             Command::Look(noun) => self.do_look(noun),
             Command::Go(noun) => self.do_go(noun),
             Command::Quit => "Quitting.\nThank you for playing!".to_string(),
-            Command::Attack(noun) => self.do_attack(noun),
+            Command::Attack { target, weapon } => self.do_attack(target, weapon.as_deref()),
             Command::Drop(noun) => self.do_drop(noun),
             Command::Get(noun) => self.do_get(noun),
+            Command::GetFrom { item, container } => self.do_get_from(item, container),
+            Command::Put { item, container } => self.do_put(item, container.as_ref()),
+            Command::Open(noun) => self.do_open(noun),
+            Command::Eat(noun) => self.do_eat(noun),
+            Command::Repair(noun) => self.do_repair(noun),
+            Command::Upgrade(noun) => self.do_upgrade(noun),
+            Command::Respond {
+                npc,
+                node,
+                response,
+            } => self.do_respond(*npc, *node, *response),
+            Command::Throw { item, target } => self.do_throw(item, target.as_ref()),
+            Command::Feed { target, food } => self.do_feed(target, food.as_ref()),
             Command::Inventory => self.do_inventory(),
-            Command::Help => self.display_help(),
+            Command::Compare { first, second } => self.do_compare(first, second.as_ref()),
+            Command::Help(noun) => self.display_help(noun),
             Command::Map => self.display_locations(),
-            Command::Unknown(_) => {
+            Command::Capture(noun) => self.do_capture(noun),
+            Command::Loot(noun) => self.do_loot(noun),
+            Command::TurnIn(noun) => self.do_turn_in(noun),
+            Command::Interrogate(noun) => self.do_interrogate(noun),
+            Command::Talk(noun) => self.do_talk(noun),
+            Command::Persuade(noun) => self.do_approach(noun, Approach::Persuade),
+            Command::Intimidate(noun) => self.do_approach(noun, Approach::Intimidate),
+            Command::Journal => self.do_journal(),
+            Command::Attributes => self.do_attributes(),
+            Command::Stats => self.do_stats(),
+            Command::Rolls(noun) => self.do_rolls(noun),
+            Command::Survival(noun) => self.do_survival(noun),
+            Command::Wait => self.do_wait(),
+            Command::Pick(noun) => self.do_pick(noun),
+            Command::Class(noun) => self.do_class(noun),
+            Command::Perks(noun) => self.do_perks(noun),
+            Command::Raise(noun) => self.do_raise(noun),
+            Command::Bestiary => self.do_bestiary(),
+            Command::Time => self.do_time(),
+            Command::Log(noun) => self.do_log(noun),
+            Command::Alias(noun) => self.do_alias(noun),
+            Command::Recruit(noun) => self.do_recruit(noun),
+            Command::Dismiss(noun) => self.do_dismiss(noun),
+            Command::Buy(noun) => self.do_buy(noun),
+            Command::Sell(noun) => self.do_sell(noun),
+            Command::Shop => self.do_shop(),
+            Command::Haggle => self.do_haggle(),
+            Command::Mount(noun) => self.do_mount(noun),
+            Command::Unlock(noun) => self.do_unlock(noun),
+            Command::Craft(noun) => self.do_craft(noun),
+            Command::Combine { first, second } => self.do_combine(first, second.as_ref()),
+            Command::Brew(noun) => self.do_brew(noun),
+            Command::Place(noun) => self.do_place(noun),
+            Command::Barricade(noun) => self.do_barricade(noun),
+            Command::Travel(noun) => self.do_travel(noun),
+            Command::Offer(noun) => self.do_offer(noun),
+            Command::Borrow(noun) => self.do_borrow(noun),
+            Command::Repay(noun) => self.do_repay(noun),
+            Command::Rest => self.do_rest(),
+            Command::Light(noun) => self.do_light(noun),
+            Command::Train(noun) => self.do_train(noun),
+            #[cfg(feature = "debug")]
+            Command::Timeline(noun) => self.do_timeline(noun),
+            Command::Back => self.do_back(),
+            Command::Again => "There is nothing to repeat yet.\n".to_string(),
+            Command::Search => self.do_search(),
+            Command::Yes => self.do_confirm(travel, true),
+            Command::No => self.do_confirm(travel, false),
+            Command::Dismount => self.do_dismount(),
+            Command::Wear(noun) => self.do_wear(noun),
+            Command::Equip(noun) => self.do_equip(noun),
+            Command::Unequip(noun) => self.do_unequip(noun),
+            Command::TakeOff => self.do_take_off(),
+            Command::Use { item, target } => self.do_use_item(item, target.as_deref()),
+            Command::Taunt | Command::Sneak | Command::Defend | Command::Run => {
+                "You are not fighting anything.\n".to_string()
+            }
+            Command::Malformed(error) => error.to_string(),
+            Command::Unknown(input) => {
+                if let Some(suggestion) = self.suggest_verb(input) {
+                    return suggestion;
+                }
                 let invalid_msg = String::from("Invalid command!!\n");
-                let help = self.display_help();
+                let help = self.display_help("");
                 invalid_msg + help.as_str()
             }
         }
     }
 
-    /// Function to perform the attack while attacking an enemy
-    pub fn do_use(&mut self, msg: &str, mut obj_health: u64, obj_index: usize) -> u64 {
-        let mut split_input = msg.split_whitespace();
-        let noun = split_input.nth(1).unwrap_or_default().to_string();
-        let (output, obj_opt) = self.object_visible(&noun);
-        match obj_opt {
-            Some(weapon_index) if !self.objects[weapon_index].enemy => {
-                if let Some(attack_pwr) = self.objects[weapon_index].attack {
-                    if let Some(enemy_pwr) = self.objects[obj_index].attack {
-                        obj_health -= attack_pwr;
-                        self.type_writer_effect(&format!(
-                            "You attacked the {}.\nEnemy health: {}",
-                            self.objects[obj_index].label[0], obj_health
-                        ));
-                        if obj_health == 0 {
-                            self.objects[obj_index].health = Some(0);
-                            return obj_health;
-                        }
-                        self.type_writer_effect(&format!(
-                            "\n\nThe {} attacks",
-                            self.objects[obj_index].label[0]
-                        ));
-                        // random attack
-                        let mut rng = rand::thread_rng();
-                        let attack: u64 = rng.gen_range(0..enemy_pwr);
-                        if attack == 0 {
-                            self.type_writer_effect("\nYou dodged the attack");
-                            obj_health
-                        } else {
-                            self.type_writer_effect("\nYou got hit");
-                            self.objects[LOC_PLAYER].health = Some(
-                                self.objects[LOC_PLAYER]
-                                    .health
-                                    .map(|h| h - attack)
-                                    .unwrap_or(0),
-                            );
-                            self.type_writer_effect(&format!(
-                                "\nYour health: {}",
-                                self.objects[LOC_PLAYER].health.unwrap_or(0)
-                            ));
-                            obj_health
-                        }
-                    } else {
-                        obj_health
-                    }
-                } else {
-                    self.type_writer_effect("That is not a weapon!!");
-                    println!("\nHint: Use the following commands: use <weapon name> or run");
-                    obj_health
-                }
-            }
-            Some(_) => {
-                self.type_writer_effect("That is not a weapon!!");
-                println!("\nHint: Use the following commands: use <weapon name> or run");
-                obj_health
-            }
-            None => {
-                self.type_writer_effect(&output);
-                obj_health
+    /// Advances the world by one turn and returns anything the player should hear about
+    pub fn tick(&mut self) -> String {
+        self.turn += 1;
+        self.note_sightings();
+        self.run_reactions()
+            + &self.run_chapters()
+            + &self.run_companions()
+            + &self.run_mounts()
+            + &self.run_roamers()
+            + &self.run_schedules()
+            + &self.run_barricades()
+            + &self.run_effects()
+            + &self.run_weather()
+            + &self.run_survival()
+            + &self.run_diseases()
+            + &self.run_loans()
+            + &self.run_restock()
+    }
+
+    /// Records the defeat of an enemy so the rest of the world can react to it
+    fn record_defeat(&mut self, enemy: usize) {
+        self.note_defeated(enemy);
+        let name = self.objects[enemy].label[0].to_lowercase();
+        if !self.objects[enemy].knocked_out {
+            // Killing an enemy that could have been taken alive is held against the player
+            if self.objects[enemy].capturable {
+                self.karma -= 1;
             }
+            self.set_flag(format!("killed:{}", name));
+        } else {
+            self.set_flag(format!("spared:{}", name));
         }
+        self.set_flag(format!("defeated:{}", name));
+    }
+
+    /// Returns the party members fighting alongside the player
+    pub fn party(&self) -> Vec<usize> {
+        let mut party = vec![LOC_PLAYER];
+        party.extend(self.companions());
+        party
+    }
+
+    /// Returns how much threat an enemy feels from a party member
+    pub fn threat(&self, enemy: usize, member: usize) -> u64 {
+        self.objects[enemy]
+            .threat
+            .get(&member)
+            .copied()
+            .unwrap_or(0)
     }
 
-    /// Function to attack an enemy
-    pub fn do_attack(&mut self, noun: &String) -> String {
+    /// Raises the threat an enemy feels from a party member
+    pub fn add_threat(&mut self, enemy: usize, member: usize, amount: u64) {
+        *self.objects[enemy].threat.entry(member).or_insert(0) += amount;
+    }
+
+    /// Picks the party member an enemy attacks according to its targeting policy
+    pub fn select_target(&self, enemy: usize) -> usize {
+        let alive: Vec<usize> = self
+            .party()
+            .into_iter()
+            .filter(|member| self.objects[*member].health.unwrap_or(0) > 0)
+            .collect();
+
+        let target = match self.objects[enemy].targeting {
+            Targeting::Random if !alive.is_empty() => {
+                Some(alive[self.rng().gen_range(0..alive.len())])
+            }
+            Targeting::Random => None,
+            Targeting::LowestHealth => alive
+                .iter()
+                .copied()
+                .min_by_key(|member| self.objects[*member].health.unwrap_or(0)),
+            Targeting::HighestThreat => alive
+                .iter()
+                .copied()
+                .max_by_key(|member| self.threat(enemy, *member)),
+        };
+        target.unwrap_or(LOC_PLAYER)
+    }
+
+    /// Function to attack an enemy, striking at once if the player names a weapon
+    pub fn do_attack(&mut self, noun: &String, weapon: Option<&str>) -> String {
         let (output, obj_opt) = self.object_visible(noun);
 
         match obj_opt {
             Some(obj_index) => {
                 if self.objects[obj_index].enemy {
-                    let mut obj_health: u64 =
-                        obj_opt.and_then(|a| self.objects[a].health).unwrap_or(0);
-                    if obj_health == 0 {
-                        return format!(
+                    let obj_health: u64 = self.objects[obj_index].health.unwrap_or(0);
+                    if obj_health == 0 && self.objects[obj_index].knocked_out {
+                        format!("The {} is out cold.\n", self.objects[obj_index].label[0])
+                    } else if obj_health == 0 {
+                        format!(
                             "The {} is already dead.\n",
                             self.objects[obj_index].label[0]
-                        );
-                    }
-                    self.type_writer_effect(&format!(
-                        "\nYou are attacking the {}.\n",
-                        self.objects[obj_index].label[0]
-                    ));
-                    println!("\nHint: Use the following commands when attacking: 'use <weapon name>' or 'inventory' or 'run'");
-                    loop {
-                        if self.objects[LOC_PLAYER].health.unwrap_or(0) == 0 {
-                            return "\nYou died".to_string();
-                        }
-                        print!("\n> ");
-                        io::stdout().flush().unwrap();
-
-                        let mut command = String::new();
-                        io::stdin()
-                            .read_line(&mut command)
-                            .expect("Failed to read input");
-                        if command.contains("run") {
-                            break;
-                        } else if command.contains("inventory") {
-                            let list_objects = self.do_inventory();
-                            self.type_writer_effect(&list_objects);
-                            continue;
-                        } else if command.contains("use") {
-                            obj_health = self.do_use(&command, obj_health, obj_index);
-                            if obj_health == 0 {
-                                break;
-                            }
-                        } else {
-                            println!("\nHint: Use the following commands when attacking: 'use <weapon name>' or 'inventory' or 'run'");
-                        }
-                    }
-                    if obj_health == 0 {
-                        format!("\nYou killed the {}.\n", self.objects[obj_index].label[0])
-                    } else {
+                        )
+                    } else if self.fumble_in_dark() {
                         format!(
-                            "You ran away from the {}.\n",
+                            "It is too dark to see the {} well enough to fight it.\n",
                             self.objects[obj_index].label[0]
                         )
+                    } else if let Some(Err(output)) = weapon.map(|w| self.choose_weapon(w)) {
+                        output
+                    } else {
+                        let output = self.start_combat(obj_index);
+                        match weapon {
+                            Some(weapon) if self.combat.is_some() => {
+                                output + &self.do_use(weapon, None, obj_index)
+                            }
+                            _ => output,
+                        }
                     }
+                } else if self.objects[obj_index].npc {
+                    format!(
+                        "The {} has done nothing to deserve that.\n",
+                        self.objects[obj_index].label[0]
+                    )
                 } else {
                     format!(
                         "You can't attack the {}.\n",
@@ -737,7 +1389,8 @@ impl World {
         }
     }
 
-    /// Look around the surroundings of the location the player is in
+    /// Look around the surroundings of the location the player is in, or at
+    /// something in sight
     pub fn do_look(&self, noun: &str) -> String {
         match noun {
             "" => {
@@ -746,9 +1399,28 @@ impl World {
                     " You are in the {}\n {}.\n",
                     self.objects[self.objects[LOC_PLAYER].location.unwrap()].label[0],
                     self.objects[self.objects[LOC_PLAYER].location.unwrap()].description
-                ) + list.as_str()
+                ) + &self.describe_weather()
+                    + list.as_str()
             }
-            _ => "Invalid command!!\n".to_string(),
+            _ => match self.object_visible(&noun.to_string()) {
+                (_, Some(index)) if self.is_notable(index) => {
+                    format!(
+                        "{}\n{}, {}.\n",
+                        self.objects[index].description,
+                        self.gear_name(index),
+                        self.objects[index].rarity
+                    ) + &self.describe_durability(index)
+                }
+                (_, Some(index)) if self.objects[index].trader => {
+                    format!("{}\n", self.objects[index].description) + &self.list_stock(index)
+                }
+                (_, Some(index)) => {
+                    format!("{}\n", self.objects[index].description)
+                        + &self.describe_durability(index)
+                }
+                (output, None) if output == "Invalid command!!" => output + "\n",
+                (output, None) => output,
+            },
         }
     }
 
@@ -758,18 +1430,20 @@ impl World {
 
         match self.get_distance(Some(LOC_PLAYER), obj_opt) {
             Distance::OverThere => {
-                self.objects[LOC_PLAYER].location = obj_opt;
-                "OK.\n".to_string() + &self.do_look("")
-            }
-            Distance::NotHere => {
-                format!("You don't see any '{}' here.\n", noun)
+                match self.passage_index(self.objects[LOC_PLAYER].location, obj_opt) {
+                    Some(passage) => self.go_through(passage),
+                    None => output,
+                }
             }
-            Distance::Unknown => output,
+            Distance::NotHere | Distance::Unknown => match self.walk_to(noun) {
+                Some(walked) => walked,
+                None if obj_opt.is_some() => format!("You don't see any '{}' here.\n", noun),
+                None => output,
+            },
             _ => {
                 let obj_dist = obj_opt.and_then(|a| self.objects[a].destination);
-                if obj_dist.is_some() {
-                    self.objects[LOC_PLAYER].location = obj_dist;
-                    "OK.\n".to_string() + &self.do_look("")
+                if let (Some(passage), Some(_)) = (obj_opt, obj_dist) {
+                    self.go_through(passage)
                 } else {
                     let obj_desc = obj_opt.map(|a| self.objects[a].description.clone());
                     obj_desc.unwrap_or("Invalid command!!\n".to_string())
@@ -778,20 +1452,79 @@ impl World {
         }
     }
 
+    /// Moves the player, and whatever they are riding, to another location
+    fn move_player(&mut self, to: usize) -> String {
+        if let Some(refusal) = self.mount_refusal(to).or_else(|| self.travel_refusal()) {
+            return refusal;
+        }
+        self.step(to);
+        "OK.\n".to_string()
+            + &self.do_look("")
+            + &self.environment_effect()
+            + &self.ambush(to)
+            + &self.random_event()
+    }
+
+    /// Puts the player, their companions, their captives and whatever they ride
+    /// in a location
+    fn step(&mut self, to: usize) {
+        for companion in self.companions() {
+            self.objects[companion].location = Some(to);
+        }
+        let here = self.objects[LOC_PLAYER].location;
+        for object in self.objects.iter_mut() {
+            if object.captured && object.location == here {
+                object.location = Some(to);
+            }
+        }
+        self.record(format!("player entered the {}", self.objects[to].label[0]));
+        self.history.extend(self.objects[LOC_PLAYER].location);
+        self.objects[LOC_PLAYER].location = Some(to);
+        self.visited.insert(to);
+        match self.riding {
+            Some(mount) => self.objects[mount].location = Some(to),
+            None => {
+                self.spend_stamina(TRAVEL_COST);
+            }
+        }
+    }
+
     /// Player drops the specified object
     pub fn do_drop(&mut self, noun: &String) -> String {
+        if noun == "all" {
+            return self.do_drop_all();
+        }
+        let (wanted, noun) = split_count(noun);
         let (output, object_index) =
-            self.get_possession(Some(LOC_PLAYER), Command::Drop("drop".to_string()), noun);
+            self.get_possession(Some(LOC_PLAYER), Command::Drop("drop".to_string()), &noun);
+        let Some(index) = object_index else {
+            return output;
+        };
+        if let Some(refusal) = self.keep_key_item(index) {
+            return output + &refusal;
+        }
+        let count = match self.count_wanted(index, wanted) {
+            Ok(count) => count,
+            Err(refusal) => return output + &refusal,
+        };
 
+        if count == self.count(index) {
+            self.put_away(index);
+        }
         let player_loc = self.objects[LOC_PLAYER].location;
-        output + self.move_object(object_index, player_loc).as_str()
+        output + self.move_some(index, count, player_loc).as_str()
     }
 
     /// Player consumes the specified object
     pub fn do_consume(&mut self, object: Option<usize>) -> String {
         let heal = self.objects[object.unwrap()].health.unwrap_or(0);
+        let buff = self.objects[object.unwrap()].buff.clone();
         let mut player_health = self.objects[LOC_PLAYER].health.unwrap_or(0);
-        if player_health == 100 {
+        if player_health >= self.max_health()
+            && buff.as_ref().is_none_or(StatusEffect::only_heals)
+            && !self.relieves_survival(object.unwrap())
+            && !self.cures_disease(object.unwrap())
+        {
             "You are already at full health".to_string()
         } else {
             self.objects[LOC_PLAYER].health = Some(
@@ -801,19 +1534,57 @@ impl World {
                     .unwrap_or(0),
             );
             player_health = self.objects[LOC_PLAYER].health.unwrap_or(0);
-            if player_health > 100 {
-                self.objects[LOC_PLAYER].health = Some(100);
+            if player_health > self.max_health() {
+                self.objects[LOC_PLAYER].health = Some(self.max_health());
+            }
+            let fed = self.eat_and_drink(object.unwrap()) + &self.cure_with(object.unwrap());
+            self.use_up(object.unwrap());
+            let effect = fed
+                + &match buff {
+                    Some(buff) => {
+                        let text = format!("You are under {}.\n", buff);
+                        self.add_effect(LOC_PLAYER, buff);
+                        text
+                    }
+                    None => String::new(),
+                };
+            if heal == 0 {
+                return "You have consumed the item.\n".to_string() + &effect;
             }
-            self.objects[object.unwrap()].location = None;
             "You have consumed the item. Your health has increased to ".to_string()
                 + &self.objects[LOC_PLAYER].health.unwrap_or(0).to_string()
                 + "\n"
+                + &effect
         }
     }
 
     /// Player gets the specified object
     pub fn do_get(&mut self, noun: &String) -> String {
+        if noun == "all" {
+            return self.do_get_all();
+        }
+        let (wanted, noun) = split_count(noun);
+        let noun = &noun;
+        if self.chosen.get().is_none() {
+            self.chosen.set(self.stack_here(noun));
+        }
         let (output, obj_opt) = self.object_visible(noun);
+        if let Some(index) = obj_opt {
+            if let Some(price) = self.objects[index].price {
+                return format!(
+                    "The {} is for sale for {} gold. Try <buy {}>.\n",
+                    self.objects[index].label[0], price, noun
+                );
+            }
+            if self.get_distance(Some(LOC_PLAYER), obj_opt) == Distance::Here
+                && self.fumble_in_dark()
+            {
+                return format!(
+                    "You grope around in the dark but can't find the {}.\n",
+                    self.objects[index].label[0]
+                );
+            }
+        }
         let obj_item = obj_opt.map(|a| self.objects[a].item).unwrap_or(false);
         let player_to_obj = self.get_distance(Some(LOC_PLAYER), obj_opt);
         let obj_consumable = obj_opt
@@ -833,18 +1604,116 @@ impl World {
             (Distance::OverThere, _, false, false) => output + "You cannot get that!!\n",
             (Distance::Here, _, false, false) => output + "You cannot get that!!\n",
             (Distance::Unknown, _, false, false) => output,
-            (Distance::Here, _, true, true) => self.do_consume(obj_opt),
+            (Distance::Here, Some(obj_index), true, _) => {
+                match self.count_wanted(obj_index, wanted) {
+                    Ok(count) => output + &self.carry(obj_index, count),
+                    Err(refusal) => output + &refusal,
+                }
+            }
             _ => self.move_object(obj_opt, Some(LOC_PLAYER)),
         }
     }
 
+    /// Player picks up every item lying around, leaving anything they would have
+    /// to eat or pay for where it is
+    fn do_get_all(&mut self) -> String {
+        let here = self.objects[LOC_PLAYER].location;
+        let items: Vec<usize> = (0..self.objects.len())
+            .filter(|index| {
+                let object = &self.objects[*index];
+                object.item
+                    && !object.consumable
+                    && !object.hidden
+                    && object.price.is_none()
+                    && object.location == here
+            })
+            .collect();
+
+        if items.is_empty() {
+            "There is nothing here you can pick up.\n".to_string()
+        } else if self.fumble_in_dark() {
+            "You grope around in the dark but can't find anything.\n".to_string()
+        } else {
+            items
+                .into_iter()
+                .map(|index| self.carry(index, self.count(index)))
+                .collect()
+        }
+    }
+
+    /// Player empties their inventory onto the ground
+    fn do_drop_all(&mut self) -> String {
+        let held: Vec<usize> = (0..self.objects.len())
+            .filter(|index| self.objects[*index].location == Some(LOC_PLAYER))
+            .collect();
+        if held.is_empty() {
+            return "You currently do not have anything in your inventory.\n".to_string();
+        }
+
+        let player_loc = self.objects[LOC_PLAYER].location;
+        held.into_iter()
+            .map(|index| match self.objects[index].key_item {
+                true => format!("You hold on to the {}.\n", self.objects[index].label[0]),
+                false => {
+                    self.put_away(index);
+                    self.move_object(Some(index), player_loc)
+                }
+            })
+            .collect()
+    }
+
+    /// Player reads back everything they have learned
+    pub fn do_journal(&self) -> String {
+        let quests = self.describe_quests();
+        let chapter = self.describe_chapter();
+        if self.journal.is_empty() && quests.is_empty() && chapter.is_empty() {
+            "Your journal is empty.\n".to_string()
+        } else {
+            chapter
+                + &self
+                    .journal
+                    .iter()
+                    .map(|entry| format!("- {}\n", entry))
+                    .collect::<String>()
+                + &quests
+        }
+    }
+
     /// Player checks the inventory
     pub fn do_inventory(&self) -> String {
         let (list_string, count) = self.list_objects(LOC_PLAYER);
         if count == 0 {
             "You currently do not have anything in your inventory.\n".to_string()
+                + &self.describe_wealth()
         } else {
+            let effects: String = self.objects[LOC_PLAYER]
+                .effects
+                .iter()
+                .map(|effect| format!("You are under {}.\n", effect))
+                .collect();
+            let armor = match self.worn_armor() {
+                Some(index) => format!("You are wearing the {}.\n", self.objects[index].label[0]),
+                None => String::new(),
+            };
+            let weapon = match self.equipped(Slot::Weapon) {
+                Some(index) => format!("You are wielding the {}.\n", self.objects[index].label[0]),
+                None => String::new(),
+            };
+            let trinket = match self.equipped(Slot::Trinket) {
+                Some(index) => format!(
+                    "You have the {} on as a trinket.\n",
+                    self.objects[index].label[0]
+                ),
+                None => String::new(),
+            };
             list_string
+                + &self.describe_wealth()
+                + &self.describe_load()
+                + &format!("Your gear is {}.\n", self.noise_level())
+                + &weapon
+                + &armor
+                + &trinket
+                + &effects
         }
     }
 
@@ -883,7 +1752,7 @@ impl World {
             (Some(obj_opt_idx), _, Some(to_idx), Some(player_loc_idx))
                 if to_idx == player_loc_idx =>
             {
-                format!("You have dropped {}.\n", self.objects[obj_opt_idx].label[0])
+                format!("You have dropped {}.\n", self.stack_name(obj_opt_idx))
             }
             (Some(obj_opt_idx), _, Some(to_idx), _) if to_idx != LOC_PLAYER => {
                 format!(
@@ -894,7 +1763,7 @@ impl World {
             (Some(obj_opt_idx), Some(obj_loc_idx), _, Some(player_loc_idx))
                 if obj_loc_idx == player_loc_idx =>
             {
-                format!("You pick up the {}.\n", self.objects[obj_opt_idx].label[0])
+                format!("You pick up the {}.\n", self.stack_name(obj_opt_idx))
             }
             (Some(obj_opt_idx), Some(obj_loc_idx), _, _) => format!(
                 "You got {} from {}.\n",
@@ -916,6 +1785,7 @@ impl World {
             (Some(obj_idx), Some(_), Some(to_idx)) => {
                 let output = self.describe_move(obj_opt, to);
                 self.objects[obj_idx].location = Some(to_idx);
+                self.merge_stack(obj_idx);
                 output
             }
         }
@@ -923,29 +1793,27 @@ impl World {
 
     /// Gets the index of the passage if visible
     fn passage_index(&self, from: Option<usize>, to: Option<usize>) -> Option<usize> {
-        let mut result: Option<usize> = None;
-
         match (from, to) {
-            (Some(from), Some(to)) => {
-                for (pos, object) in self.objects.iter().enumerate() {
-                    let obj_loc = object.location;
-                    let obj_dest = object.destination;
-                    match (obj_loc, obj_dest) {
-                        (Some(location), Some(destination))
-                            if location == from && destination == to =>
-                        {
-                            result = Some(pos);
-                            break;
-                        }
-                        _ => continue,
-                    }
-                }
-                result
-            }
-            _ => result,
+            (Some(from), Some(to)) => self
+                .exits(from)
+                .into_iter()
+                .find(|pos| self.objects[*pos].destination == Some(to)),
+            _ => None,
         }
     }
 
+    /// Returns the passages leading out of a location, leaving out hidden ones
+    pub fn exits(&self, location: usize) -> Vec<usize> {
+        self.objects
+            .iter()
+            .enumerate()
+            .filter(|(_, object)| {
+                object.is_passage() && !object.hidden && object.location == Some(location)
+            })
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
     /// Returns the index of the object if it is visible
     pub fn get_possession(
         &mut self,
@@ -975,14 +1843,13 @@ impl World {
                 ),
                 None,
             ),
-            (Some(_), AmbiguousOption::Ambiguous, _) => (
-                format!(
-                    "Please be more specific about which {} you want to {}.\n",
-                    noun, command
-                ),
-                None,
-            ),
-            (Some(_), AmbiguousOption::Some(object_held), _) => ("".to_string(), Some(object_held)),
+            (Some(_), AmbiguousOption::Ambiguous, _) => {
+                (self.offer_choice(noun, from, Distance::Held), None)
+            }
+            (Some(_), AmbiguousOption::Some(object_held), _) => {
+                self.last_referenced.set(Some(object_held));
+                ("".to_string(), Some(object_held))
+            }
             (Some(_), AmbiguousOption::None, AmbiguousOption::Some(_))
             | (Some(_), AmbiguousOption::None, AmbiguousOption::Ambiguous) => {
                 (format!("You don't see any {} here.\n", noun), None)
@@ -1007,40 +1874,40 @@ impl World {
         player_loc
     }
 
-    pub fn display_help(&self) -> String {
-        "Available commands are\n
-        look\n
-        attack <enemy name>\n
-        go <location>\n
-        get <item name>\n
-        drop <item name>\n
-        inventory \n
-        map \n
-        quit\n
-        help\n"
-            .to_string()
-    }
-
     pub fn display_locations(&self) -> String {
         let mut result = String::new();
-        result += "Available locations:\n";
-        let mut destinations = std::collections::HashSet::new();
-
-        for object in &self.objects {
-            if let Some(destination) = object.destination {
-                destinations.insert(destination);
-            }
-        }
+        result += "Explored locations:\n";
 
-        for (index, object) in self.objects.iter().enumerate() {
-            //let location = &self.objects[locations];
-            if destinations.contains(&index) {
-                //println!("{}: {}", index, object.label[0]);
-                result += &format!("{}: {}\n", index, object.label[0]);
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let explored =
+            |location: usize| self.visited.contains(&location) || Some(location) == player_loc;
+        for index in self
+            .locations()
+            .into_iter()
+            .filter(|index| explored(*index))
+        {
+            result += &format!("{}: {}\n", index, self.objects[index].label[0]);
+            for passage in self.exits(index) {
+                let to = self.objects[passage].destination.unwrap_or_default();
+                let name = if explored(to) {
+                    self.objects[to].label[0].as_str()
+                } else {
+                    "???"
+                };
+                result += &format!("    {}: {}\n", self.objects[passage].label[0], name);
             }
         }
         result
     }
+
+    /// Returns the indices of every object that a passage leads to
+    pub fn locations(&self) -> BTreeSet<usize> {
+        self.objects
+            .iter()
+            .filter_map(|object| object.destination)
+            .filter(|destination| *destination < self.objects.len())
+            .collect()
+    }
 }
 
 /// Default implementation for World
@@ -1050,30 +1917,181 @@ impl Default for World {
     }
 }
 
+/// Expands a compass direction or its abbreviation to the passage name it stands for
+pub fn expand_direction(word: &str) -> Option<&'static str> {
+    match word {
+        "n" | "north" => Some("north"),
+        "s" | "south" => Some("south"),
+        "e" | "east" => Some("east"),
+        "w" | "west" => Some("west"),
+        "u" | "up" => Some("up"),
+        "d" | "down" => Some("down"),
+        _ => None,
+    }
+}
+
 /// Function that parses user's commands into a verb and a noun
 pub fn parse(input: String) -> Command {
-    let input = input.to_lowercase();
-    let mut split_input = input.split_whitespace();
+    let raw = input;
+    let input = raw.to_lowercase();
+    let tokens = match tokenize(&input) {
+        Ok(tokens) => tokens,
+        Err(error) => return Command::Malformed(error),
+    };
 
-    let verb = split_input.next().unwrap_or_default().to_string();
-    let noun = split_input.fold("".to_string(), |accum, item| {
-        if accum.is_empty() {
-            accum + item
-        } else {
-            accum + " " + item
-        }
-    });
+    let verb = tokens
+        .first()
+        .map(|token| token.text.clone())
+        .unwrap_or_default();
+    let words = strip_articles(tokens.get(1..).unwrap_or_default());
+    let noun = join_words(&words);
 
     match verb.as_str() {
-        "look" => Command::Look(noun),
-        "go" => Command::Go(noun),
+        "look" | "examine" | "x" => Command::Look(noun),
+        "go" | "walk" | "move" if noun == "back" => Command::Back,
+        "go" | "walk" | "move" => match expand_direction(&noun) {
+            Some(direction) => Command::Go(direction.to_string()),
+            None => Command::Go(noun),
+        },
+        direction if noun.is_empty() && expand_direction(direction).is_some() => {
+            Command::Go(expand_direction(direction).unwrap_or_default().to_string())
+        }
         "quit" => Command::Quit,
-        "attack" => Command::Attack(noun),
+        "attack" | "fight" | "hit" | "kill" => {
+            let (target, weapon) = split_clause(&words, &["with", "using"]);
+            Command::Attack { target, weapon }
+        }
+        "use" => {
+            let (item, target) = split_clause(&words, &["on", "at"]);
+            Command::Use { item, target }
+        }
+        "taunt" => Command::Taunt,
+        "sneak" => Command::Sneak,
+        "defend" | "block" => Command::Defend,
+        "run" | "flee" => Command::Run,
         "drop" => Command::Drop(noun),
-        "get" => Command::Get(noun),
-        "help" => Command::Help,
+        "get" | "grab" | "take" if words.iter().any(|word| word.text == "from") => {
+            let (item, container) = split_clause(&words, &["from"]);
+            Command::GetFrom {
+                item,
+                container: container.unwrap_or_default(),
+            }
+        }
+        "get" | "grab" => Command::Get(noun),
+        "put" => {
+            let (item, container) = split_clause(&words, &["in", "into"]);
+            Command::Put { item, container }
+        }
+        "open" => Command::Open(noun),
+        "eat" | "drink" | "consume" => Command::Eat(noun),
+        "repair" | "mend" | "fix" => Command::Repair(noun),
+        "upgrade" | "forge" | "sharpen" => Command::Upgrade(noun),
+        "throw" | "hurl" => {
+            let (item, target) = split_clause(&words, &["at"]);
+            Command::Throw { item, target }
+        }
+        "feed" => match split_clause(&words, &["to"]) {
+            // "feed apple to bear" names the food first
+            (food, Some(target)) => Command::Feed {
+                target,
+                food: Some(food),
+            },
+            (_, None) => {
+                let (target, food) = split_clause(&words, &["with"]);
+                Command::Feed { target, food }
+            }
+        },
+        "pick" if noun == "up" || noun.starts_with("up ") => {
+            Command::Get(noun[2..].trim().to_string())
+        }
+        "help" => Command::Help(noun),
         "inventory" => Command::Inventory,
+        "compare" => {
+            let (first, second) = split_clause(&words, &["with", "and", "to", "against"]);
+            Command::Compare { first, second }
+        }
         "map" => Command::Map,
+        "capture" => Command::Capture(noun),
+        "loot" => Command::Loot(noun),
+        "turn" if noun.starts_with("in ") => Command::TurnIn(noun[3..].to_string()),
+        "interrogate" => Command::Interrogate(noun),
+        "talk" | "speak" => match noun.split_once(' ') {
+            Some(("to" | "with", name)) => Command::Talk(name.to_string()),
+            _ => Command::Talk(noun),
+        },
+        "persuade" | "convince" => Command::Persuade(noun),
+        "intimidate" | "threaten" => Command::Intimidate(noun),
+        "journal" => Command::Journal,
+        "attributes" => Command::Attributes,
+        "stats" | "sheet" | "character" => Command::Stats,
+        "rolls" => Command::Rolls(noun),
+        "survival" => Command::Survival(noun),
+        "wait" => Command::Wait,
+        "class" => Command::Class(noun),
+        "perks" | "perk" => Command::Perks(noun),
+        "raise" => Command::Raise(noun),
+        "bestiary" | "codex" => Command::Bestiary,
+        "time" => Command::Time,
+        "log" => Command::Log(noun),
+        "alias" => Command::Alias(noun),
+        "recruit" => Command::Recruit(noun),
+        "dismiss" => Command::Dismiss(noun),
+        "buy" => Command::Buy(noun),
+        "sell" => Command::Sell(noun),
+        "shop" | "wares" => Command::Shop,
+        "haggle" | "barter" => Command::Haggle,
+        "mount" | "ride" => Command::Mount(noun),
+        "dismount" => Command::Dismount,
+        "wear" => Command::Wear(noun),
+        "equip" | "wield" => Command::Equip(noun),
+        "take" if noun == "off" || noun.starts_with("off ") => Command::TakeOff,
+        "unequip" => Command::Unequip(noun),
+        "take" => Command::Get(noun),
+        "craft" | "make" => Command::Craft(noun),
+        "brew" | "distil" | "distill" => Command::Brew(noun),
+        "combine" | "mix" => {
+            let (first, second) = split_clause(&words, &["with", "and"]);
+            Command::Combine { first, second }
+        }
+        "place" | "set" => Command::Place(noun),
+        "travel" => Command::Travel(noun),
+        "offer" => Command::Offer(noun),
+        "borrow" => Command::Borrow(noun),
+        "repay" => Command::Repay(noun),
+        "light" => Command::Light(noun),
+        "train" | "learn" => Command::Train(noun),
+        #[cfg(feature = "debug")]
+        // A path to dump the timeline to keeps its case
+        "timeline" => Command::Timeline(
+            raw.trim_start()
+                .get(verb.len()..)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        ),
+        "rest" | "sleep" => Command::Rest,
+        "back" => Command::Back,
+        "search" => Command::Search,
+        "again" | "g" => Command::Again,
+        "yes" | "y" => Command::Yes,
+        "no" => Command::No,
+        "barricade" => match expand_direction(&noun) {
+            Some(direction) => Command::Barricade(direction.to_string()),
+            None => Command::Barricade(noun),
+        },
+        "unlock" => match expand_direction(&noun) {
+            Some(direction) => Command::Unlock(direction.to_string()),
+            None => Command::Unlock(noun),
+        },
+        // "pick lock on north" and "lockpick north" both name the way the lock is on
+        "pick" | "lockpick" => {
+            let noun = noun.strip_prefix("lock").unwrap_or(&noun).trim();
+            let noun = noun.strip_prefix("on ").unwrap_or(noun).trim().to_string();
+            match expand_direction(&noun) {
+                Some(direction) => Command::Pick(direction.to_string()),
+                None => Command::Pick(noun),
+            }
+        }
         _ => Command::Unknown(input.trim().to_string()),
     }
 }
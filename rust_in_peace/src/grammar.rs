@@ -0,0 +1,133 @@
+//! Breaking what the player typed into words before it is turned into a command.
+//! Names can be put in quotes to keep them in one piece, articles like "the"
+//! are left out, and every word remembers where it was typed so mistakes can
+//! be pointed out.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+// Words that are dropped from names, so "get the sword" is "get sword"
+const ARTICLES: &[&str] = &["the", "a", "an"];
+
+/// A word the player typed, or a whole name they put in quotes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Token {
+    pub text: String,
+    /// Where in the input the word was typed, quotes included
+    pub span: Range<usize>,
+    pub quoted: bool,
+}
+
+/// Input that can't be broken into words, with the part at fault
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub input: String,
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    /// Shows the input with the part at fault marked underneath it
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let indent = self.input[..self.span.start].chars().count();
+        let width = self.input[self.span.clone()].chars().count().max(1);
+        writeln!(f, "{}", self.input)?;
+        writeln!(
+            f,
+            "{}{} {}",
+            " ".repeat(indent),
+            "^".repeat(width),
+            self.message
+        )
+    }
+}
+
+/// Splits input into words, keeping anything in quotes together as one
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let error = |span: Range<usize>, message: &str| ParseError {
+        input: input.to_string(),
+        span,
+        message: message.to_string(),
+    };
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '"' {
+            let Some((end, _)) = chars.find(|(_, c)| *c == '"') else {
+                return Err(error(start..input.len(), "This quote is never closed."));
+            };
+            let text = input[start + 1..end].trim();
+            if text.is_empty() {
+                return Err(error(
+                    start..end + 1,
+                    "There is nothing between these quotes.",
+                ));
+            }
+            tokens.push(Token {
+                text: text.to_string(),
+                span: start..end + 1,
+                quoted: true,
+            });
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        while let Some((at, c)) = chars.next_if(|(_, c)| !c.is_whitespace() && *c != '"') {
+            end = at + c.len_utf8();
+        }
+        tokens.push(Token {
+            text: input[start..end].to_string(),
+            span: start..end,
+            quoted: false,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Leaves out the articles in a name, unless they were typed in quotes
+pub(crate) fn strip_articles(tokens: &[Token]) -> Vec<Token> {
+    tokens
+        .iter()
+        .filter(|token| token.quoted || !ARTICLES.contains(&token.text.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Puts words back together into a name
+pub(crate) fn join_words(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| token.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits a count off the front of a name, so "3 apples" is 3 of "apples"
+pub(crate) fn split_count(noun: &str) -> (Option<u64>, String) {
+    match noun.split_once(' ') {
+        Some((count, name)) => match count.parse() {
+            Ok(count) => (Some(count), name.trim().to_string()),
+            Err(_) => (None, noun.to_string()),
+        },
+        None => (None, noun.to_string()),
+    }
+}
+
+/// Splits words at the first of the given ones, such as "troll with sword"
+/// into "troll" and "sword". Words in quotes are never split at.
+pub(crate) fn split_clause(tokens: &[Token], words: &[&str]) -> (String, Option<String>) {
+    let at = tokens
+        .iter()
+        .position(|token| !token.quoted && words.contains(&token.text.as_str()));
+    match at {
+        Some(at) => (
+            join_words(&tokens[..at]),
+            Some(join_words(&tokens[at + 1..])),
+        ),
+        None => (join_words(tokens), None),
+    }
+}
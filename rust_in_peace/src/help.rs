@@ -0,0 +1,480 @@
+//! Help for every command. The list shown by "help" and the details shown by
+//! "help <command>" both come from the one registry below, so a new command
+//! only needs an entry here to be documented in both, and for its verbs to be
+//! known when guessing at mistyped ones.
+use super::{parse, World};
+
+/// What the player is told about one command
+struct HelpEntry {
+    /// The command's names, as the commands themselves display them, and
+    /// every other word the parser takes for it
+    names: &'static [&'static str],
+    usage: &'static str,
+    text: &'static str,
+    examples: &'static [&'static str],
+}
+
+const HELP: &[HelpEntry] = &[
+    HelpEntry {
+        names: &["look", "examine"],
+        usage: "look [object]",
+        text: "Describes where you are, or an object you can see. Also works as \"examine\" or \"x\".",
+        examples: &["look", "look troll"],
+    },
+    HelpEntry {
+        names: &["attack", "fight", "hit", "kill"],
+        usage: "attack <enemy name> [with <weapon name>]",
+        text: "Starts a fight with an enemy next to you. Naming a weapon strikes the first blow straight away. In the middle of a fight, \"attack\" on its own carries on with the best weapon you carry. Also works as \"fight\", \"hit\" or \"kill\".",
+        examples: &["attack troll", "attack bandits with bow"],
+    },
+    HelpEntry {
+        names: &["use"],
+        usage: "use <weapon name or fists> [on <enemy>]",
+        text: "During a fight, strikes with a weapon or your bare fists, and naming an enemy picks which one to hit when there are several. Outside a fight it does whatever suits the thing: lights a torch or lantern, takes a potion, unlocks the way a key fits, or starts a fight with the enemy a weapon is used on.",
+        examples: &["use sword", "use spear on brute", "use fists", "use iron key", "use torch"],
+    },
+    HelpEntry {
+        names: &["taunt"],
+        usage: "taunt",
+        text: "Draws the enemy's attention to you and away from your companions. Only works during a fight.",
+        examples: &["taunt"],
+    },
+    HelpEntry {
+        names: &["sneak"],
+        usage: "sneak",
+        text: "Slips out of the enemy's attention so it goes after your companions instead. Only works during a fight.",
+        examples: &["sneak"],
+    },
+    HelpEntry {
+        names: &["defend", "block"],
+        usage: "defend",
+        text: "Halves the damage you take this round and readies a counterattack that makes your next attack stronger. Also works as \"block\". Only works during a fight.",
+        examples: &["defend"],
+    },
+    HelpEntry {
+        names: &["run", "flee"],
+        usage: "run",
+        text: "Tries to get away from the fight. Each enemy may cut you off, and if one does they all get a free attack. Also works as \"flee\". Only works during a fight.",
+        examples: &["run"],
+    },
+    HelpEntry {
+        names: &["throw", "hurl"],
+        usage: "throw <item name> [at <enemy>]",
+        text: "Throws something you carry at an enemy next to you. Weapons hit for their attack and heavy things hurt more. The item lands where the enemy stands, so you can pick it up again. Outside a fight this starts one, and in a fight it takes your turn.",
+        examples: &["throw bones at troll", "throw spear"],
+    },
+    HelpEntry {
+        names: &["go", "walk", "move"],
+        usage: "go <location or direction>",
+        text: "Walks somewhere next to where you are. Directions can be shortened to n, s, e, w, u and d, and typed on their own. Also works as \"walk\" or \"move\".",
+        examples: &["go tavern", "go north", "n"],
+    },
+    HelpEntry {
+        names: &["get", "grab", "take"],
+        usage: "get [number] <item name or all> [from <container>]",
+        text: "Picks up an item where you are, or everything you can carry with \"all\". Items that come in stacks, such as apples, can be picked up a few at a time. You can only carry so much weight, and are warned when your pack gets heavy. Also works as \"take\", \"grab\" or \"pick up\".",
+        examples: &["get sword", "get 3 apples", "get all", "get elixir from chest"],
+    },
+    HelpEntry {
+        names: &["drop"],
+        usage: "drop [number] <item name or all>",
+        text: "Puts down an item you carry, or everything with \"all\". Naming a number puts down only that many from a stack. Key items the story needs stay with you.",
+        examples: &["drop rope", "drop 2 apples", "drop all"],
+    },
+    HelpEntry {
+        names: &["eat", "drink", "consume"],
+        usage: "eat <food or potion>",
+        text: "Eats or drinks a healing item you carry or that is lying where you are. Some draughts raise your attack, defense or dodge for a few turns instead. During a fight it costs you your turn. Also works as \"drink\" or \"consume\".",
+        examples: &["eat apple", "drink potion"],
+    },
+    HelpEntry {
+        names: &["repair", "mend", "fix"],
+        usage: "repair <item name>",
+        text: "Repairs a weapon or armor you carry that has been worn down in fights. By the fireplace in the Tavern it costs a gold coin for every point of durability, and a whetstone you carry does the job anywhere but is used up. Worn out gear only does half its job.",
+        examples: &["repair sword", "mend chainmail"],
+    },
+    HelpEntry {
+        names: &["upgrade", "forge", "sharpen"],
+        usage: "upgrade <weapon>",
+        text: "Forges a weapon you carry up to its next tier at the forge in the Village, which renames it and raises its attack. Every tier takes more materials than the last, such as whetstones and iron ore, which are used up.",
+        examples: &["upgrade sword", "sharpen sword"],
+    },
+    HelpEntry {
+        names: &["open"],
+        usage: "open <container>",
+        text: "Opens a chest, bag or other container so you can see what is inside and put things in or take them out.",
+        examples: &["open chest"],
+    },
+    HelpEntry {
+        names: &["put"],
+        usage: "put [number] <item name> in <container>",
+        text: "Puts an item you carry into an open container. Whatever is in a bag you carry still counts towards your load.",
+        examples: &["put rope in bag", "put 2 apples in chest"],
+    },
+    HelpEntry {
+        names: &["capture"],
+        usage: "capture <enemy name>",
+        text: "Ties up an enemy that has been knocked out, so it can be turned in or questioned.",
+        examples: &["capture bandits"],
+    },
+    HelpEntry {
+        names: &["loot"],
+        usage: "loot <enemy name>",
+        text: "Takes whatever a defeated enemy was carrying.",
+        examples: &["loot troll"],
+    },
+    HelpEntry {
+        names: &["turn in"],
+        usage: "turn in <enemy name>",
+        text: "Hands a captured enemy over for a bounty.",
+        examples: &["turn in bandits"],
+    },
+    HelpEntry {
+        names: &["interrogate"],
+        usage: "interrogate <enemy name>",
+        text: "Questions a captured enemy for what it knows.",
+        examples: &["interrogate bandits"],
+    },
+    HelpEntry {
+        names: &["persuade", "convince", "intimidate", "threaten"],
+        usage: "persuade <enemy> / intimidate <enemy>",
+        text: "Tries to talk an enemy that will hear you out into leaving without a fight. Persuading rolls a d20 plus your karma, intimidating a d20 plus how fearsome your weapon is. If it doesn't work, the enemy attacks. Talking to an enemy tells you whether it will listen. Also works as \"convince\" and \"threaten\".",
+        examples: &["persuade bandits", "intimidate bandits"],
+    },
+    HelpEntry {
+        names: &["talk", "speak"],
+        usage: "talk <name>",
+        text: "Talks to someone you can see. People with more to say tell you the next thing each time you talk to them again, and some give you numbered answers to pick from by typing the number. Some enemies can be talked out of a fight, but one that won't listen attacks you. Also works as \"speak\", and \"talk to <name>\" works too.",
+        examples: &["talk soldier", "talk to lookout"],
+    },
+    HelpEntry {
+        names: &["journal"],
+        usage: "journal",
+        text: "Shows what you have found out so far, the quests you have taken on and the experience they have earned you. Doesn't use up a turn.",
+        examples: &["journal"],
+    },
+    HelpEntry {
+        names: &["class"],
+        usage: "class <name>",
+        text: "Picks the class you play as, which sets your attributes, gives you a starting item and a perk of its own. It can only be done before your first turn, and \"class\" on its own lists the classes or tells you which one you are. Doesn't use up a turn.",
+        examples: &["class", "class ranger"],
+    },
+    HelpEntry {
+        names: &["attributes"],
+        usage: "attributes",
+        text: "Shows your strength, agility and intellect, what each adds to your rolls, your level and how close you are to the next. Doesn't use up a turn, even during a fight.",
+        examples: &["attributes"],
+    },
+    HelpEntry {
+        names: &["stats", "sheet", "character"],
+        usage: "stats",
+        text: "Shows your character sheet: your name, class and level, your health, attributes and perks, the gear you have equipped, the effects you are under, your gold and valuables, and your karma and reputation. Also works as \"sheet\" or \"character\". Doesn't use up a turn, even during a fight.",
+        examples: &["stats"],
+    },
+    HelpEntry {
+        names: &["raise"],
+        usage: "raise <attribute>",
+        text: "Spends an attribute point to raise strength, agility or intellect by one. You start with a few points and earn more every time you level up. Doesn't use up a turn.",
+        examples: &["raise strength", "raise intellect"],
+    },
+    HelpEntry {
+        names: &["perks", "perk"],
+        usage: "perk <name>",
+        text: "Picks a perk, a small edge of your own such as more health or a better chance of finding loot. You get to pick one every time you level up, and \"perks\" on its own lists the perks you have and the ones you could pick. Doesn't use up a turn.",
+        examples: &["perks", "perk thick skin"],
+    },
+    HelpEntry {
+        names: &["bestiary", "codex"],
+        usage: "bestiary",
+        text: "Lists the enemies you have come across. Fighting one reveals its stats, resistances and weaknesses, and defeating it reveals what is known about it. Also works as \"codex\". Doesn't use up a turn, even during a fight.",
+        examples: &["bestiary"],
+    },
+    HelpEntry {
+        names: &["time"],
+        usage: "time",
+        text: "Tells you the hour of the day and how long until night falls or the sun rises. Some people keep to a routine and are only found in certain places at certain hours. Doesn't use up a turn.",
+        examples: &["time"],
+    },
+    HelpEntry {
+        names: &["log"],
+        usage: "log [number of entries]",
+        text: "Shows the last blows struck in your fights, ten unless you ask for more. Doesn't use up a turn, even during a fight.",
+        examples: &["log", "log 30"],
+    },
+    HelpEntry {
+        names: &["alias"],
+        usage: "alias [<name> = <command>]",
+        text: "Makes your own shorthand for a command. Anything typed after an alias goes on the end of what it stands for. \"alias\" on its own lists your aliases and \"alias <name> =\" removes one.",
+        examples: &["alias atk = attack bandits", "alias wield = use", "alias atk ="],
+    },
+    HelpEntry {
+        names: &["feed"],
+        usage: "feed <animal> [with <food>]",
+        text: "Feeds an animal something you carry. Some wild animals can be tamed by feeding them enough of their favourite food, and then they follow you as a companion instead of fighting you.",
+        examples: &["feed bear", "feed apple to bear", "feed bear with apple"],
+    },
+    HelpEntry {
+        names: &["recruit"],
+        usage: "recruit <npc>",
+        text: "Asks someone here to travel with you. Companions follow you between places and attack alongside you every round of a fight.",
+        examples: &["recruit hermit"],
+    },
+    HelpEntry {
+        names: &["dismiss"],
+        usage: "dismiss <companion>",
+        text: "Sends a companion travelling with you back home.",
+        examples: &["dismiss hermit"],
+    },
+    HelpEntry {
+        names: &["buy"],
+        usage: "buy <item name>",
+        text: "Buys an item that is for sale where you are, or one a trader here has in stock. Looking at a trader shows what it sells.",
+        examples: &["buy chainmail", "buy bandage"],
+    },
+    HelpEntry {
+        names: &["sell"],
+        usage: "sell [<count>] <item name>",
+        text: "Sells something you carry to a trader where you are, for half of what it is worth, or more if it gives you a good price. Traders only pay for things that have a price and only as much gold as they have.",
+        examples: &["sell throwing knife", "sell 2 bandages"],
+    },
+    HelpEntry {
+        names: &["shop", "wares"],
+        usage: "shop",
+        text: "Lists what a trader where you are has for sale and what it charges you. Traders give a better price to someone with a good reputation. Doesn't use up a turn.",
+        examples: &["shop"],
+    },
+    HelpEntry {
+        names: &["haggle", "barter"],
+        usage: "haggle",
+        text: "Haggles with a trader where you are, rolling a check of your intellect. Haggle well and it knocks some off its prices, badly and it puts some on, until it next restocks.",
+        examples: &["haggle"],
+    },
+    HelpEntry {
+        names: &["mount", "ride"],
+        usage: "mount <animal>",
+        text: "Climbs on an animal to ride it, which makes travelling quicker and lets you carry more. Also works as \"ride\".",
+        examples: &["mount horse"],
+    },
+    HelpEntry {
+        names: &["dismount"],
+        usage: "dismount",
+        text: "Gets off the animal you are riding.",
+        examples: &["dismount"],
+    },
+    HelpEntry {
+        names: &["wear"],
+        usage: "wear <armor>",
+        text: "Puts on a piece of armor you carry.",
+        examples: &["wear leather armor"],
+    },
+    HelpEntry {
+        names: &["equip", "wield"],
+        usage: "equip <item>",
+        text: "Readies a weapon, armor or trinket you carry in its slot. Your equipped weapon is the one you fight with when you don't name another, and a trinket adds its bonuses to your own. Also works as \"wield\".",
+        examples: &["equip sword", "equip lucky charm"],
+    },
+    HelpEntry {
+        names: &["unequip"],
+        usage: "unequip [weapon, armor, trinket or item]",
+        text: "Empties one of your equipment slots, named by the slot or by what is in it. On its own it takes off your armor.",
+        examples: &["unequip weapon", "unequip lucky charm"],
+    },
+    HelpEntry {
+        names: &["take off"],
+        usage: "take off",
+        text: "Removes the armor you are wearing.",
+        examples: &["take off"],
+    },
+    HelpEntry {
+        names: &["unlock"],
+        usage: "unlock <direction>",
+        text: "Unlocks a locked way out with a key you carry.",
+        examples: &["unlock north"],
+    },
+    HelpEntry {
+        names: &["pick", "lockpick"],
+        usage: "pick lock <direction>",
+        text: "Tries to pick the lock on a way out without its key, with an agility check against how hard the lock is. Some locks are too sturdy to pick. \"pick <direction>\" and \"lockpick <direction>\" work too.",
+        examples: &["pick lock up", "lockpick up"],
+    },
+    HelpEntry {
+        names: &["rolls"],
+        usage: "rolls [on|off]",
+        text: "Shows or hides the numbers behind skill checks, such as sneaking, picking locks, searching, persuading or climbing: the d20, the bonus added to it and the difficulty it had to reach. On its own it switches between the two. Doesn't use up a turn.",
+        examples: &["rolls on", "rolls off"],
+    },
+    HelpEntry {
+        names: &["survival"],
+        usage: "survival [on|off]",
+        text: "Turns hunger and thirst on or off. With them on your food and water run down as time passes, and running low saps your strength and agility until you eat or drink something, like an apple or a draught. Your character sheet shows how much you have left. On its own it switches between the two. Doesn't use up a turn.",
+        examples: &["survival on", "survival off"],
+    },
+    HelpEntry {
+        names: &["craft", "make"],
+        usage: "craft <item>",
+        text: "Makes something, such as a trap, out of the items it needs. Also works as \"make\".",
+        examples: &["craft snare", "craft crude spear"],
+    },
+    HelpEntry {
+        names: &["combine", "mix"],
+        usage: "combine <item> [with <item>]",
+        text: "Puts things you carry together to make something out of them, if they make anything. Some things, like herbs, make something on their own.",
+        examples: &["combine bones with branch", "combine herbs"],
+    },
+    HelpEntry {
+        names: &["brew", "distil", "distill"],
+        usage: "brew [<potion>]",
+        text: "Brews a potion over a fire, like the fireplace in the Tavern, out of ingredients you carry. How well it comes out depends on a check of your intellect: weak potions do half as much and potent ones half as much again. On its own, lists the potions you know how to brew.",
+        examples: &["brew", "brew regeneration potion"],
+    },
+    HelpEntry {
+        names: &["place", "set"],
+        usage: "place <trap>",
+        text: "Sets a trap you carry where you are. Also works as \"set\".",
+        examples: &["place snare"],
+    },
+    HelpEntry {
+        names: &["barricade"],
+        usage: "barricade <direction>",
+        text: "Blocks a way into your location so enemies can't come through it.",
+        examples: &["barricade east"],
+    },
+    HelpEntry {
+        names: &["travel"],
+        usage: "travel <visited location>",
+        text: "Walks the whole way to a location you have been to before. You are asked first if the way is dangerous.",
+        examples: &["travel village"],
+    },
+    HelpEntry {
+        names: &["yes", "no"],
+        usage: "yes, no",
+        text: "Answers a question the game asked you, such as whether to carry on with a dangerous journey.",
+        examples: &["yes", "no"],
+    },
+    HelpEntry {
+        names: &["offer"],
+        usage: "offer <gold or item>",
+        text: "Leaves an offering at a shrine in return for its blessing.",
+        examples: &["offer gold", "offer apple"],
+    },
+    HelpEntry {
+        names: &["borrow"],
+        usage: "borrow <amount>",
+        text: "Borrows gold from the Moneylender. Interest builds up until you repay it.",
+        examples: &["borrow 20"],
+    },
+    HelpEntry {
+        names: &["repay"],
+        usage: "repay [amount]",
+        text: "Pays back some or all of what you owe the Moneylender.",
+        examples: &["repay", "repay 10"],
+    },
+    HelpEntry {
+        names: &["rest", "sleep"],
+        usage: "rest",
+        text: "Rests to get health back a little every turn, or sleeps the night away in your own room to wake up at full health. The Tavern is safe to rest in, but resting anywhere else may bring something upon you in your sleep. You can't rest with an enemy about. Also works as \"sleep\".",
+        examples: &["rest"],
+    },
+    HelpEntry {
+        names: &["wait"],
+        usage: "wait",
+        text: "Lets a turn pass while you catch your breath, getting back some stamina. Attacks, running from a fight and walking from place to place all cost stamina, and without it your blows land weakly, you can't run and you can't walk on.",
+        examples: &["wait"],
+    },
+    HelpEntry {
+        names: &["light"],
+        usage: "light <torch or lantern>",
+        text: "Lights a torch or lantern you carry, so you can see in the dark.",
+        examples: &["light lantern"],
+    },
+    HelpEntry {
+        names: &["train", "learn"],
+        usage: "train <skill>",
+        text: "Pays a trainer to teach you a skill. \"train\" on its own lists what the trainer teaches. Also works as \"learn\".",
+        examples: &["train", "train stealth"],
+    },
+    HelpEntry {
+        names: &["back"],
+        usage: "back",
+        text: "Goes back to the location you came from, the same as \"go back\".",
+        examples: &["back"],
+    },
+    HelpEntry {
+        names: &["again"],
+        usage: "again (or g)",
+        text: "Repeats the last command the game understood.",
+        examples: &["again", "g"],
+    },
+    HelpEntry {
+        names: &["search"],
+        usage: "search",
+        text: "Searches where you are for hidden things such as secret passages.",
+        examples: &["search"],
+    },
+    HelpEntry {
+        names: &["inventory"],
+        usage: "inventory",
+        text: "Lists what you carry, your gold, how much it all weighs and what you have equipped. Doesn't use up a turn.",
+        examples: &["inventory"],
+    },
+    HelpEntry {
+        names: &["compare"],
+        usage: "compare <weapon name> [with <weapon name>]",
+        text: "Shows the stats of two weapons side by side. With only one named, it is compared with the weapon you have ready. Doesn't use up a turn, even in a fight.",
+        examples: &["compare bow with spear", "compare bow spear", "compare sword"],
+    },
+    HelpEntry {
+        names: &["map"],
+        usage: "map",
+        text: "Shows the locations you have explored and the ways out of them. Doesn't use up a turn.",
+        examples: &["map"],
+    },
+    HelpEntry {
+        names: &["quit"],
+        usage: "quit",
+        text: "Ends the game.",
+        examples: &["quit"],
+    },
+    HelpEntry {
+        names: &["help"],
+        usage: "help [command]",
+        text: "Lists every command, or tells you more about one of them.",
+        examples: &["help", "help attack"],
+    },
+];
+
+/// Returns every verb the commands go by
+pub(crate) fn verbs<'a>() -> impl Iterator<Item = &'a str> {
+    HELP.iter()
+        .flat_map(|entry| entry.names)
+        .filter_map(|name| name.split(' ').next())
+}
+
+impl World {
+    /// Lists every command, or describes one in detail if the player names it
+    pub fn display_help(&self, noun: &str) -> String {
+        if noun.is_empty() {
+            return HELP
+                .iter()
+                .fold("Available commands are\n".to_string(), |output, entry| {
+                    output + &format!("\n        {}\n", entry.usage)
+                })
+                + "\nType \"help <command>\" to find out more about one of them.\n";
+        }
+
+        // Other words for a command get the help for the command itself
+        let command = parse(noun.to_string()).to_string();
+        match HELP
+            .iter()
+            .find(|entry| entry.names.contains(&noun) || entry.names.contains(&command.as_str()))
+        {
+            Some(entry) => format!(
+                "{}\n\n{}\n\nExamples: {}\n",
+                entry.usage,
+                entry.text,
+                entry.examples.join(", ")
+            ),
+            None => format!("There is no command called '{}'.\n", noun),
+        }
+    }
+}
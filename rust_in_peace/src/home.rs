@@ -0,0 +1,31 @@
+//! A room of the player's own: somewhere to rest for free, keep their things
+//! and put up decor that gives them small bonuses.
+use super::World;
+use serde::{Deserialize, Serialize};
+
+/// The bonuses a piece of decor gives while it is in the player's home
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Decor {
+    #[serde(default)]
+    pub attack: i64,
+    #[serde(default)]
+    pub defense: i64,
+}
+
+impl World {
+    /// Returns the total bonus from decor put up in the player's home
+    pub fn decor_bonus(&self) -> Decor {
+        self.objects
+            .iter()
+            .filter(|object| {
+                object
+                    .location
+                    .is_some_and(|location| self.objects[location].home)
+            })
+            .filter_map(|object| object.decor.as_ref())
+            .fold(Decor::default(), |total, decor| Decor {
+                attack: total.attack + decor.attack,
+                defense: total.defense + decor.defense,
+            })
+    }
+}
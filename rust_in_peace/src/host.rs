@@ -0,0 +1,318 @@
+//! Hosting many games at once in server mode. Every session is its own world
+//! behind its own lock, so players never see each other's games, and commands
+//! are carried out by a fixed pool of worker threads. World files are read and
+//! checked once and shared by every session started from them. There is a cap
+//! on how many sessions can run at once, and players who connect past it are
+//! turned away. Players who go quiet for too long are disconnected so they
+//! don't hold on to a session forever.
+use super::{parse, Command, Severity, World, BUNDLED_WORLD};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::read_to_string;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The most sessions a host runs at once
+pub const MAX_SESSIONS: usize = 64;
+
+// How long a connection may sit without sending or taking a line before the
+// player is disconnected
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What a session said back after carrying out a command
+pub struct Reply {
+    pub output: String,
+    pub game_over: bool,
+}
+
+/// How busy a host is, as reported on its metrics endpoint
+#[derive(Debug, Clone, Copy)]
+pub struct Metrics {
+    pub sessions: usize,
+    pub turns: u64,
+    pub turns_per_sec: f64,
+    pub memory_bytes: u64,
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rip_sessions {}", self.sessions)?;
+        writeln!(f, "rip_turns_total {}", self.turns)?;
+        writeln!(f, "rip_turns_per_second {:.2}", self.turns_per_sec)?;
+        writeln!(f, "rip_resident_memory_bytes {}", self.memory_bytes)
+    }
+}
+
+/// Runs independent game sessions across a pool of worker threads
+pub struct SessionHost {
+    templates: Mutex<HashMap<String, Arc<str>>>,
+    sessions: RwLock<HashMap<u64, Arc<Mutex<World>>>>,
+    next_id: AtomicU64,
+    turns: Arc<AtomicU64>,
+    started: Instant,
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl SessionHost {
+    /// Starts a host with the given number of worker threads
+    pub fn new(workers: usize) -> SessionHost {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    let job = receiver.lock().expect("Worker queue poisoned").recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+
+        SessionHost {
+            templates: Mutex::new(HashMap::new()),
+            sessions: RwLock::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            turns: Arc::new(AtomicU64::new(0)),
+            started: Instant::now(),
+            jobs: Some(sender),
+            workers,
+        }
+    }
+
+    /// Returns the contents of a world file, reading and checking it only the
+    /// first time it is asked for. `None` stands for the bundled world.
+    fn template(&self, game_file: Option<&str>) -> io::Result<Arc<str>> {
+        let key = game_file.unwrap_or_default().to_string();
+        let mut templates = self.templates.lock().expect("Template cache poisoned");
+        if let Some(template) = templates.get(&key) {
+            return Ok(Arc::clone(template));
+        }
+
+        let data: Arc<str> = match game_file {
+            Some(path) => read_to_string(path)?.into(),
+            None => BUNDLED_WORLD.into(),
+        };
+        let errors: Vec<String> = World::from_template(&data)?
+            .validate()
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        if !errors.is_empty() {
+            return Err(io::Error::other(errors.join("\n")));
+        }
+        templates.insert(key, Arc::clone(&data));
+        Ok(data)
+    }
+
+    /// Starts a new session on a world file, or the bundled world, and returns
+    /// its id. Fails if the host already runs as many sessions as it can.
+    pub fn open(&self, game_file: Option<&str>) -> io::Result<u64> {
+        let world = World::from_template(&self.template(game_file)?)?;
+        let mut sessions = self.sessions.write().expect("Session table poisoned");
+        if sessions.len() >= MAX_SESSIONS {
+            return Err(io::Error::other("The server is full"));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        sessions.insert(id, Arc::new(Mutex::new(world)));
+        Ok(id)
+    }
+
+    /// Ends a session, returning false if there was no such session
+    pub fn close(&self, id: u64) -> bool {
+        self.sessions
+            .write()
+            .expect("Session table poisoned")
+            .remove(&id)
+            .is_some()
+    }
+
+    /// Hands a command for a session to the worker threads. The reply arrives on
+    /// the returned channel, or `None` is returned if there is no such session.
+    pub fn submit(&self, id: u64, command: Command) -> Option<Receiver<Reply>> {
+        let world = Arc::clone(self.sessions.read().ok()?.get(&id)?);
+        let turns = Arc::clone(&self.turns);
+        let (sender, receiver) = mpsc::channel();
+
+        let job: Job = Box::new(move || {
+            let mut world = world.lock().expect("Session poisoned");
+            let before = world.turn;
+            let mut output = world.update_state(&command);
+            turns.fetch_add(world.turn - before, Ordering::Relaxed);
+            if let Some(message) = world.win_message() {
+                output = output + "\n" + message;
+            }
+            sender
+                .send(Reply {
+                    output,
+                    game_over: world.game_over(),
+                })
+                .ok();
+        });
+        self.jobs.as_ref()?.send(job).ok()?;
+        Some(receiver)
+    }
+
+    /// Reports how many sessions are running and how fast they are being played
+    pub fn metrics(&self) -> Metrics {
+        let turns = self.turns.load(Ordering::Relaxed);
+        let elapsed = self.started.elapsed().as_secs_f64();
+        Metrics {
+            sessions: self.sessions.read().map(|s| s.len()).unwrap_or(0),
+            turns,
+            turns_per_sec: if elapsed > 0.0 {
+                turns as f64 / elapsed
+            } else {
+                0.0
+            },
+            memory_bytes: resident_memory(),
+        }
+    }
+}
+
+impl Drop for SessionHost {
+    fn drop(&mut self) {
+        // Closing the queue lets every worker finish its job and stop
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            worker.join().ok();
+        }
+    }
+}
+
+impl World {
+    /// Builds a world from the contents of a world file
+    fn from_template(data: &str) -> io::Result<World> {
+        let mut world: World =
+            ron::from_str(data).map_err(|de_err| io::Error::other(de_err.to_string()))?;
+        world.start_game();
+        Ok(world)
+    }
+}
+
+/// Returns the memory the process holds, where the system tells us
+fn resident_memory() -> u64 {
+    // The resident set size, which status gives in kilobytes whatever the page size
+    read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("VmRSS:"))?
+                .split_whitespace()
+                .next()?
+                .parse::<u64>()
+                .ok()
+        })
+        .map(|kilobytes| kilobytes * 1024)
+        .unwrap_or(0)
+}
+
+/// Accepts players on a TCP address, giving each connection its own session.
+/// Players type commands one line at a time, just like in the terminal. No
+/// more than `MAX_SESSIONS` players are served at once.
+pub fn serve_players(
+    host: Arc<SessionHost>,
+    addr: &str,
+    game_file: Option<String>,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    let players = Arc::new(AtomicUsize::new(0));
+    for mut stream in listener.incoming().flatten() {
+        if players.load(Ordering::Acquire) >= MAX_SESSIONS {
+            stream
+                .write_all(b"The server is full. Try again later.\n")
+                .ok();
+            continue;
+        }
+        players.fetch_add(1, Ordering::AcqRel);
+        let players = Arc::clone(&players);
+        let host = Arc::clone(&host);
+        let game_file = game_file.clone();
+        thread::spawn(move || {
+            if let Err(error) = play_session(&host, stream, game_file.as_deref()) {
+                eprintln!("Session ended with an error: {}", error);
+            }
+            players.fetch_sub(1, Ordering::AcqRel);
+        });
+    }
+    Ok(())
+}
+
+/// Plays one connected player's game until they quit, hang up, go idle or the
+/// game ends
+fn play_session(host: &SessionHost, stream: TcpStream, game_file: Option<&str>) -> io::Result<()> {
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    stream.set_write_timeout(Some(IDLE_TIMEOUT))?;
+    let id = host.open(game_file)?;
+    let result = run_session(host, id, stream);
+    host.close(id);
+    result
+}
+
+/// Passes the player's commands to their session and writes back the replies
+fn run_session(host: &SessionHost, id: u64, stream: TcpStream) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    writer.write_all(
+        b"Welcome to Rust In Peace\nHint: Enter <help> to display the commands available\n\n> ",
+    )?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                writer
+                    .write_all(b"\nYou have been idle too long. Goodbye!\n")
+                    .ok();
+                break;
+            }
+            line => line?,
+        };
+        let command = parse(line);
+        let quit = matches!(command, Command::Quit);
+        let Some(reply) = host.submit(id, command).and_then(|reply| reply.recv().ok()) else {
+            break;
+        };
+        writer.write_all(reply.output.as_bytes())?;
+        if quit || reply.game_over {
+            writer.write_all(b"\nGoodbye!\n")?;
+            break;
+        }
+        writer.write_all(b"\n> ")?;
+    }
+    Ok(())
+}
+
+/// Answers every HTTP request on the address with the host's metrics
+pub fn serve_metrics(host: Arc<SessionHost>, addr: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for mut stream in listener.incoming().flatten() {
+        // Only the request line matters, the rest of the request is ignored
+        let mut request = String::new();
+        BufReader::new(&stream).read_line(&mut request).ok();
+        let body = host.metrics().to_string();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .ok();
+    }
+    Ok(())
+}
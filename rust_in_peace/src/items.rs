@@ -0,0 +1,110 @@
+//! Using things outside of a fight. What "use" does depends on the thing:
+//! lights are lit, food and potions are taken, keys open the locks they fit
+//! and weapons start a fight with whoever they are used on.
+use super::{expand_direction, AmbiguousOption, Distance, World, LOC_PLAYER};
+
+impl World {
+    /// Player uses something outside of a fight, optionally on something else
+    pub fn do_use_item(&mut self, noun: &String, target: Option<&str>) -> String {
+        if matches!(noun.as_str(), "fists" | "fist" | "hands") {
+            return match target {
+                Some(target) => self.do_attack(&target.to_string(), Some(noun)),
+                None => "You are not fighting anything.\n".to_string(),
+            };
+        }
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(item) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[item].label[0].clone();
+
+        if self.objects[item].light {
+            return self.do_light(noun);
+        }
+        if self.objects[item].consumable {
+            return self.do_eat(noun);
+        }
+        // Anything used on a way out is tried as its key
+        if let Some(direction) = target.and_then(expand_direction) {
+            return self.do_unlock(&direction.to_string());
+        }
+        if target.is_none() && self.opens_lock(item) {
+            return self.use_key(item);
+        }
+        if self.objects[item].item && self.objects[item].attack.is_some() {
+            return match target {
+                Some(target) => self.do_attack(&target.to_string(), Some(noun)),
+                None => "You are not fighting anything.\n".to_string(),
+            };
+        }
+        format!("You can't think of a way to use the {} here.\n", name)
+    }
+
+    /// Player eats or drinks something they carry or that is lying where they are
+    pub fn do_eat(&mut self, noun: &String) -> String {
+        // Food the player carries is eaten before any lying around
+        if let AmbiguousOption::Some(held) =
+            self.object_index(noun, Some(LOC_PLAYER), Distance::Held)
+        {
+            if self.chosen.get().is_none() {
+                self.chosen.set(Some(held));
+            }
+        }
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(item) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[item].label[0].clone();
+
+        if let Some(refusal) = self.keep_key_item(item) {
+            return refusal;
+        }
+        if !self.objects[item].consumable {
+            return format!("You can't eat or drink the {}.\n", name);
+        }
+        if self.get_distance(Some(LOC_PLAYER), Some(item)) > Distance::Here {
+            return format!("The {} is not here.\n", name);
+        }
+        if self.get_distance(Some(LOC_PLAYER), Some(item)) == Distance::Here
+            && self.fumble_in_dark()
+        {
+            return format!(
+                "You grope around in the dark but can't find the {}.\n",
+                name
+            );
+        }
+        self.do_consume(Some(item))
+    }
+
+    /// Returns why the player won't part with an item, if the story needs it
+    pub(crate) fn keep_key_item(&self, index: usize) -> Option<String> {
+        self.objects[index].key_item.then(|| {
+            format!(
+                "You can't part with the {}. You will need it later.\n",
+                self.objects[index].label[0]
+            )
+        })
+    }
+
+    /// Returns true if an object is the key to any lock in the world
+    fn opens_lock(&self, item: usize) -> bool {
+        self.objects
+            .iter()
+            .any(|object| object.locked_by == Some(item))
+    }
+
+    /// Unlocks the passage at the player's location a key fits
+    fn use_key(&mut self, key: usize) -> String {
+        let here = self.objects[LOC_PLAYER].location;
+        let fits = (0..self.objects.len()).find(|index| {
+            self.objects[*index].location == here && self.objects[*index].locked_by == Some(key)
+        });
+        match fits {
+            Some(passage) => self.unlock(passage),
+            None => format!(
+                "There is nothing here the {} unlocks.\n",
+                self.objects[key].label[0]
+            ),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Dark places where the player can barely see without a lit torch or lantern.
+use super::{Weather, World, LOC_PLAYER};
+use rand::Rng;
+
+// One in this many attempts to find something in the dark succeeds
+const DARK_SUCCESS_ODDS: u32 = 5;
+
+impl World {
+    /// Returns true if a location is dark and there is no lit light in it
+    pub fn is_dark(&self, location: usize) -> bool {
+        self.objects[location].dark
+            && !self.objects.iter().any(|object| {
+                object.lit
+                    && (object.location == Some(location)
+                        || (object.location == Some(LOC_PLAYER)
+                            && self.objects[LOC_PLAYER].location == Some(location)))
+            })
+    }
+
+    /// Returns true if the player fails to find what they reach for in the dark
+    pub(crate) fn fumble_in_dark(&self) -> bool {
+        self.objects[LOC_PLAYER]
+            .location
+            .is_some_and(|location| self.is_dark(location))
+            && !self.rng().gen_ratio(1, DARK_SUCCESS_ODDS)
+    }
+
+    /// Player lights a torch or lantern they carry
+    pub fn do_light(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+
+        if !self.objects[index].light {
+            format!("You can't light the {}.\n", name)
+        } else if !self.is_containing(Some(LOC_PLAYER), Some(index)) {
+            format!("You are not holding any {}.\n", name)
+        } else if self.objects[index].lit {
+            format!("The {} is already lit.\n", name)
+        } else if self.objects[index].flame && self.weather == Weather::Rain && self.is_outdoors() {
+            format!("The rain would put the {} straight out.\n", name)
+        } else {
+            self.objects[index].lit = true;
+            format!("You light the {}. It casts a warm glow around you.\n", name)
+        }
+    }
+}
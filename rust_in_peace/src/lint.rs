@@ -0,0 +1,259 @@
+//! Advice for world authors that goes beyond validation. Nothing found here
+//! breaks a game, but each warning points at something players may trip over.
+use super::{Change, Condition, Diagnostic, World, LOC_PLAYER};
+use std::collections::{BTreeSet, HashMap};
+
+// Text wider than this wraps awkwardly in most terminals
+const LINE_WIDTH: usize = 80;
+
+impl World {
+    /// Runs the authoring checks on a world that has passed validation
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let reachable = self.reachable_locations();
+        let brought_in = self.brought_in();
+
+        for (index, object) in self.objects.iter().enumerate() {
+            if object.item {
+                match self.place_of(index) {
+                    Some(location) if !reachable.contains(&location) => {
+                        diagnostics.push(Diagnostic::warning(format!(
+                            "item '{}' ({}) is in a location the player can't reach",
+                            object.label[0], index
+                        )))
+                    }
+                    None if !brought_in.contains(&index) => {
+                        diagnostics.push(Diagnostic::warning(format!(
+                            "item '{}' ({}) is not in the world and nothing brings it in",
+                            object.label[0], index
+                        )))
+                    }
+                    _ => {}
+                }
+            }
+            if object.enemy
+                && object.gold == 0
+                && object.loot.is_empty()
+                && !self
+                    .objects
+                    .iter()
+                    .any(|carried| carried.location == Some(index))
+            {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "enemy '{}' ({}) drops no loot",
+                    object.label[0], index
+                )));
+            }
+            if object.description.chars().count() > LINE_WIDTH {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "the description of '{}' ({}) is longer than {} characters",
+                    object.label[0], index, LINE_WIDTH
+                )));
+            }
+        }
+
+        let mut descriptions: HashMap<&str, usize> = HashMap::new();
+        for location in self.locations() {
+            let object = &self.objects[location];
+            let description = object.description.trim();
+            if description.is_empty() || description.eq_ignore_ascii_case(&object.label[0]) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "location '{}' ({}) says nothing more than its name",
+                    object.label[0], location
+                )));
+            } else if let Some(first) = descriptions.get(description) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "location '{}' ({}) has the same description as location {}",
+                    object.label[0], location, first
+                )));
+            } else {
+                descriptions.insert(description, location);
+            }
+        }
+
+        let settable = self.settable_flags();
+        let objectives = self
+            .reactions
+            .iter()
+            .enumerate()
+            .map(|(index, reaction)| (format!("reaction {}", index), &reaction.when));
+        let passages = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter_map(|(index, object)| match &object.requires {
+                Some(Condition::Flag(flag)) => {
+                    Some((format!("passage '{}' ({})", object.label[0], index), flag))
+                }
+                _ => None,
+            });
+        let companions = self
+            .objects
+            .iter()
+            .filter_map(|object| Some((object, object.companion.as_ref()?)))
+            .flat_map(|(object, companion)| {
+                let opinions = companion.opinions.iter().map(|opinion| &opinion.flag);
+                let banter = companion.banter.iter().filter_map(|b| b.flag.as_ref());
+                opinions
+                    .chain(banter)
+                    .map(move |flag| (format!("companion '{}'", object.label[0]), flag))
+            });
+        let quests =
+            self.quests
+                .iter()
+                .enumerate()
+                .filter_map(|(index, quest)| match &quest.objective {
+                    Condition::Flag(flag) => Some((format!("quest {}", index), flag)),
+                    _ => None,
+                });
+        let chapters =
+            self.chapters
+                .iter()
+                .enumerate()
+                .filter_map(|(index, chapter)| match &chapter.complete {
+                    Condition::Flag(flag) => Some((format!("chapter {}", index), flag)),
+                    _ => None,
+                });
+        for (what, flag) in objectives
+            .chain(passages)
+            .chain(companions)
+            .chain(quests)
+            .chain(chapters)
+        {
+            if !settable.contains(flag) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "{} waits for flag '{}', which nothing in the world sets",
+                    what, flag
+                )));
+            }
+        }
+
+        let messages = self
+            .reactions
+            .iter()
+            .filter_map(|reaction| reaction.message.as_ref())
+            .chain(self.events.iter().map(|event| &event.text));
+        for message in messages {
+            if message.chars().count() > LINE_WIDTH {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "the message \"{}...\" is longer than {} characters",
+                    message.chars().take(20).collect::<String>(),
+                    LINE_WIDTH
+                )));
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Follows an object out through whatever holds it to the location it is in
+    fn place_of(&self, object: usize) -> Option<usize> {
+        let locations = self.locations();
+        let mut current = object;
+        // Bounded so a container holding itself can't loop forever
+        for _ in 0..self.objects.len() {
+            let holder = self.objects[current].location?;
+            if holder == LOC_PLAYER {
+                return self.objects[LOC_PLAYER].location;
+            }
+            if locations.contains(&holder) {
+                return Some(holder);
+            }
+            current = holder;
+        }
+        None
+    }
+
+    /// Returns the objects that reactions, events, recipes, brews, loot tables,
+    /// quest rewards and classes can bring into the world
+    fn brought_in(&self) -> BTreeSet<usize> {
+        let changes = self
+            .reactions
+            .iter()
+            .flat_map(|reaction| &reaction.changes)
+            .chain(self.events.iter().flat_map(|event| &event.changes))
+            .chain(self.dialogue_changes())
+            .chain(self.chapters.iter().flat_map(|chapter| &chapter.changes));
+        changes
+            .filter_map(|change| match change {
+                Change::Move {
+                    object,
+                    to: Some(_),
+                } => Some(*object),
+                Change::Summon(object) => Some(*object),
+                _ => None,
+            })
+            .chain(self.recipes.iter().map(|recipe| recipe.makes))
+            .chain(self.brews.iter().map(|brew| brew.makes))
+            .chain(
+                self.objects
+                    .iter()
+                    .flat_map(|object| &object.loot)
+                    .filter_map(|drop| drop.item),
+            )
+            .chain(
+                self.quests
+                    .iter()
+                    .flat_map(|quest| quest.items.iter().copied()),
+            )
+            .chain(self.classes.iter().filter_map(|class| class.item))
+            .collect()
+    }
+
+    /// Returns every change the answers in the npcs' conversations can make
+    fn dialogue_changes(&self) -> impl Iterator<Item = &Change> {
+        self.objects
+            .iter()
+            .flat_map(|object| &object.dialogue)
+            .flat_map(|node| &node.responses)
+            .flat_map(|response| &response.changes)
+    }
+
+    /// Returns every flag the world or the game itself can set
+    fn settable_flags(&self) -> BTreeSet<String> {
+        let mut flags = BTreeSet::new();
+        let changes = self
+            .reactions
+            .iter()
+            .flat_map(|reaction| &reaction.changes)
+            .chain(self.events.iter().flat_map(|event| &event.changes))
+            .chain(self.dialogue_changes())
+            .chain(self.chapters.iter().flat_map(|chapter| &chapter.changes));
+        for change in changes {
+            if let Change::Flag(flag) = change {
+                flags.insert(flag.clone());
+            }
+        }
+
+        flags.extend((1..=self.chapters.len()).map(|chapter| format!("chapter:{}", chapter)));
+        for object in self.objects.iter() {
+            flags.extend(object.intel.iter().map(|intel| intel.flag.clone()));
+            if let Some(flag) = object.companion.as_ref().and_then(|c| c.quest_flag.clone()) {
+                flags.insert(flag);
+            }
+            if object.enemy {
+                let name = object.label[0].to_lowercase();
+                flags.insert(format!("defeated:{}", name));
+                flags.insert(format!("killed:{}", name));
+                if object.capturable {
+                    flags.insert(format!("spared:{}", name));
+                }
+                if object.taming.is_some() {
+                    flags.insert(format!("tamed:{}", name));
+                }
+                if let Some(talk) = object.talk.as_ref() {
+                    if talk.persuade.is_some() {
+                        flags.insert(format!("persuaded:{}", name));
+                    }
+                    if talk.intimidate.is_some() {
+                        flags.insert(format!("intimidated:{}", name));
+                    }
+                    if talk.persuade.is_some() || talk.intimidate.is_some() {
+                        flags.insert(format!("spared:{}", name));
+                    }
+                }
+            }
+        }
+        flags
+    }
+}
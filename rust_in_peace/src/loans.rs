@@ -0,0 +1,144 @@
+//! Borrowing gold from a moneylender, the interest that piles up on it and the
+//! debt collector sent after players who don't pay it back in time.
+use super::{World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+// The most a moneylender will lend at once
+const MAX_LOAN: u64 = 50;
+// Turns the player has to pay a loan back
+const LOAN_TERM: u64 = 30;
+// Every this many turns interest is added to what the player owes
+const INTEREST_INTERVAL: u64 = 10;
+const INTEREST_PERCENT: u64 = 10;
+// Turns between the debt collector's visits once a loan is overdue
+const COLLECTION_INTERVAL: u64 = 10;
+
+/// Gold the player owes and the turn by which it has to be paid back
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Loan {
+    pub owed: u64,
+    pub due: u64,
+}
+
+impl World {
+    /// Finds a moneylender at the player's location
+    fn lender_here(&self) -> Option<usize> {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        (0..self.objects.len()).find(|index| {
+            self.objects[*index].lender && self.objects[*index].location == player_loc
+        })
+    }
+
+    /// Player borrows gold from a moneylender
+    pub fn do_borrow(&mut self, noun: &str) -> String {
+        let Some(lender) = self.lender_here() else {
+            return "There is nobody here to lend you gold.\n".to_string();
+        };
+        let name = self.objects[lender].label[0].clone();
+
+        match (noun.trim().parse::<u64>(), &self.loan) {
+            (_, Some(loan)) => format!(
+                "The {} won't lend you more until you pay back the {} gold you owe.\n",
+                name, loan.owed
+            ),
+            (Ok(amount), None) if amount > 0 && amount <= MAX_LOAN => {
+                self.objects[LOC_PLAYER].gold += amount;
+                self.loan = Some(Loan {
+                    owed: amount,
+                    due: self.turn + LOAN_TERM,
+                });
+                format!(
+                    "The {} hands you {} gold. Pay it back within {} turns. Interest is added every {} turns.\n",
+                    name, amount, LOAN_TERM, INTEREST_INTERVAL
+                )
+            }
+            _ => format!(
+                "The {} will lend you anything up to {} gold. Try <borrow 20>.\n",
+                name, MAX_LOAN
+            ),
+        }
+    }
+
+    /// Player pays back some or all of what they owe
+    pub fn do_repay(&mut self, noun: &str) -> String {
+        let Some(lender) = self.lender_here() else {
+            return "There is nobody here to pay back.\n".to_string();
+        };
+        let Some(loan) = self.loan.clone() else {
+            return "You don't owe anyone anything.\n".to_string();
+        };
+        let name = self.objects[lender].label[0].clone();
+        let gold = self.objects[LOC_PLAYER].gold;
+        let amount = noun
+            .trim()
+            .parse::<u64>()
+            .unwrap_or(loan.owed)
+            .min(loan.owed)
+            .min(gold);
+
+        if amount == 0 {
+            return format!("You have no gold to pay the {} with.\n", name);
+        }
+        self.objects[LOC_PLAYER].gold -= amount;
+        if amount < loan.owed {
+            self.loan = Some(Loan {
+                owed: loan.owed - amount,
+                ..loan
+            });
+            return format!(
+                "You pay the {} {} gold. You still owe {} gold.\n",
+                name,
+                amount,
+                loan.owed - amount
+            );
+        }
+
+        self.loan = None;
+        for index in 0..self.objects.len() {
+            if self.objects[index].collector && self.objects[index].health.unwrap_or(0) > 0 {
+                self.objects[index].location = None;
+            }
+        }
+        format!(
+            "You pay the {} {} gold. Your debt is settled.\n",
+            name, amount
+        )
+    }
+
+    /// Adds interest to the player's loan and sends the debt collector once it is overdue
+    pub(crate) fn run_loans(&mut self) -> String {
+        let Some(loan) = self.loan.as_mut() else {
+            return String::new();
+        };
+        let mut output = String::new();
+
+        if self.turn.is_multiple_of(INTEREST_INTERVAL) {
+            loan.owed += (loan.owed * INTEREST_PERCENT / 100).max(1);
+        }
+        if self.turn < loan.due || !(self.turn - loan.due).is_multiple_of(COLLECTION_INTERVAL) {
+            return output;
+        }
+        if self.turn == loan.due {
+            output += &format!(
+                "\nYour loan is overdue. You owe {} gold and someone is coming to collect it.\n",
+                loan.owed
+            );
+        }
+
+        let player_loc = self.objects[LOC_PLAYER].location;
+        for index in 0..self.objects.len() {
+            let collector = &self.objects[index];
+            if collector.collector
+                && collector.health.unwrap_or(0) > 0
+                && collector.location != player_loc
+            {
+                self.objects[index].location = player_loc;
+                output += &format!(
+                    "\nThe {} has tracked you down and wants the gold you owe.\n",
+                    self.objects[index].label[0]
+                );
+            }
+        }
+        output
+    }
+}
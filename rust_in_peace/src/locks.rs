@@ -0,0 +1,130 @@
+//! Passages that stay shut until the player has the right key, or picks their
+//! lock, and passages the player has to climb.
+use super::{Attribute, Distance, World, LOC_PLAYER};
+
+impl World {
+    /// Takes the player through a passage if its condition is met, unlocking it
+    /// first if they hold its key and pulling down any barricade in the way
+    pub(crate) fn go_through(&mut self, passage: usize) -> String {
+        let Some(destination) = self.objects[passage].destination else {
+            return self.objects[passage].description.clone();
+        };
+
+        if let Some(refusal) = self.passage_refusal(passage) {
+            return refusal;
+        }
+        if self.objects[passage].barricaded > 0 {
+            return self.tear_down(passage);
+        }
+        let locked_by = self.objects[passage].locked_by;
+        if let Some(key) = locked_by.filter(|key| !self.is_containing(Some(LOC_PLAYER), Some(*key)))
+        {
+            return format!(
+                "The way {} is locked. You need the {}.\n",
+                self.objects[passage].label[0].to_lowercase(),
+                self.objects[key].label[0]
+            );
+        }
+
+        let mut output = String::new();
+        if let Some(difficulty) = self.objects[passage].climb {
+            let check = self.skill_check(Attribute::Strength, 0, difficulty);
+            let note = self.roll_note(&check);
+            if !check.passed() {
+                return format!("You lose your grip and slide back down.{}\n", note);
+            }
+            output += &format!("You make the climb.{}\n", note);
+        }
+        if let Some(key) = locked_by {
+            self.objects[passage].locked_by = None;
+            output += &format!(
+                "You unlock the way with the {}.\n",
+                self.objects[key].label[0]
+            );
+        }
+        output + &self.move_player(destination)
+    }
+
+    /// Player tries to pick the lock on a passage at their location, with an
+    /// agility check against how hard the lock is
+    pub fn do_pick(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(passage) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[passage].label[0].to_lowercase();
+
+        if !self.objects[passage].is_passage()
+            || self.get_distance(Some(LOC_PLAYER), Some(passage)) != Distance::Here
+        {
+            return format!("There is no lock to pick {}.\n", name);
+        }
+        if self.objects[passage].locked_by.is_none() {
+            return format!("The way {} is not locked.\n", name);
+        }
+        let Some(difficulty) = self.objects[passage].pick_difficulty else {
+            return format!("The lock on the way {} is too sturdy to pick.\n", name);
+        };
+        let check = self.skill_check(Attribute::Agility, 0, difficulty);
+        let note = self.roll_note(&check);
+        if check.passed() {
+            self.objects[passage].locked_by = None;
+            format!("You pick the lock on the way {}.{}\n", name, note)
+        } else {
+            format!(
+                "Your pick slips and the lock on the way {} holds.{}\n",
+                name, note
+            )
+        }
+    }
+
+    /// Player unlocks a passage at their location with a key they hold
+    pub fn do_unlock(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(passage) = obj_opt else {
+            return output;
+        };
+        self.unlock(passage)
+    }
+
+    /// Unlocks a passage at the player's location if they hold its key
+    pub(crate) fn unlock(&mut self, passage: usize) -> String {
+        let name = self.objects[passage].label[0].to_lowercase();
+
+        if !self.objects[passage].is_passage()
+            || self.get_distance(Some(LOC_PLAYER), Some(passage)) != Distance::Here
+        {
+            return format!("There is nothing to unlock {}.\n", name);
+        }
+        match self.objects[passage].locked_by {
+            None => format!("The way {} is not locked.\n", name),
+            Some(key) if !self.is_containing(Some(LOC_PLAYER), Some(key)) => {
+                format!("You need the {}.\n", self.objects[key].label[0])
+            }
+            Some(key) => {
+                self.objects[passage].locked_by = None;
+                format!(
+                    "You unlock the way {} with the {}.\n",
+                    name, self.objects[key].label[0]
+                )
+            }
+        }
+    }
+
+    /// Drops everything a dead enemy was carrying where it fell
+    pub fn drop_carried(&mut self, enemy: usize) -> String {
+        let location = self.objects[enemy].location;
+        let mut output = String::new();
+
+        for index in 0..self.objects.len() {
+            if self.is_containing(Some(enemy), Some(index)) {
+                self.objects[index].location = location;
+                output += &format!(
+                    "The {} drops the {}.\n",
+                    self.objects[enemy].label[0], self.objects[index].label[0]
+                );
+            }
+        }
+        output
+    }
+}
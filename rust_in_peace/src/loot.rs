@@ -0,0 +1,57 @@
+//! What enemies leave behind. Each enemy can have a loot table of items and
+//! gold that may drop when it is defeated, on top of whatever it was carrying
+//! and the gold in its purse.
+use super::{World, LOC_PLAYER};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Something an enemy may drop when it is defeated
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LootDrop {
+    /// An item waiting outside the world until it drops
+    #[serde(default)]
+    pub item: Option<usize>,
+    #[serde(default)]
+    pub gold: u64,
+    /// Chance in percent that this drops
+    pub chance: u64,
+}
+
+impl World {
+    /// Rolls an enemy's loot table. Items that drop are left on the enemy, to
+    /// fall where it dies or be looted from it, and gold, like the gold in its
+    /// purse, goes straight to the player.
+    pub(crate) fn roll_loot(&mut self, enemy: usize) -> String {
+        let mut rng = self.rng();
+        let mut output = String::new();
+        let purse = std::mem::take(&mut self.objects[enemy].gold);
+        if purse > 0 {
+            self.objects[LOC_PLAYER].gold += purse;
+            output += &format!(
+                "You find {} gold on the {}.\n",
+                purse, self.objects[enemy].label[0]
+            );
+        }
+
+        for drop in self.objects[enemy].loot.clone() {
+            if rng.gen_range(1..=100) > drop.chance + self.scavenging() {
+                continue;
+            }
+            // An item only drops once, even if it is on more than one table
+            if let Some(item) = drop
+                .item
+                .filter(|item| self.objects[*item].location.is_none())
+            {
+                self.objects[item].location = Some(enemy);
+            }
+            if drop.gold > 0 {
+                self.objects[LOC_PLAYER].gold += drop.gold;
+                output += &format!(
+                    "You find {} gold on the {}.\n",
+                    drop.gold, self.objects[enemy].label[0]
+                );
+            }
+        }
+        output
+    }
+}
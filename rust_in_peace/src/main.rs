@@ -2,15 +2,28 @@ use clearscreen::clear;
 use regex::Regex;
 use std::io::{stdout, Write};
 use std::process::exit;
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use std::{io, println};
 
 const GAME_FILE_LOCATION: &str = "./game_file.ron";
-
-pub mod game_lib;
+const AUTOSAVE_LOCATION: &str = "./autosave.ron";
+// Worker threads a server runs sessions on unless told otherwise
+const DEFAULT_WORKERS: usize = 4;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--validate") {
+        exit(validate_files(&args[1..], false));
+    }
+    if args.first().map(String::as_str) == Some("--lint") {
+        exit(validate_files(&args[1..], true));
+    }
+    if args.first().map(String::as_str) == Some("--serve") {
+        exit(serve(&args[1..]));
+    }
+
     let world_result = init_game(GAME_FILE_LOCATION);
 
     match world_result {
@@ -23,6 +36,96 @@ fn main() {
         }
     }
 }
+
+/// Loads each world file, prints every problem found, and with `lint` the authoring
+/// advice too, and returns the exit code
+fn validate_files(files: &[String], lint: bool) -> i32 {
+    if files.is_empty() {
+        println!("Usage: main --validate|--lint <world file>...");
+        return 2;
+    }
+
+    let mut failed = false;
+    for file in files {
+        match game_lib::World::parse_file(file) {
+            Ok(world) => {
+                let mut diagnostics = world.validate();
+                // Linting a world with errors would trip over its bad indices
+                if lint
+                    && diagnostics
+                        .iter()
+                        .all(|d| d.severity != game_lib::Severity::Error)
+                {
+                    diagnostics.extend(world.lint());
+                }
+                for diagnostic in &diagnostics {
+                    println!("{}: {}", file, diagnostic);
+                }
+                let errors = diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.severity == game_lib::Severity::Error)
+                    .count();
+                println!(
+                    "{}: {} error(s), {} warning(s)",
+                    file,
+                    errors,
+                    diagnostics.len() - errors
+                );
+                failed |= errors > 0;
+            }
+            Err(file_err) => {
+                println!("{}: error: {}", file, file_err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        1
+    } else {
+        0
+    }
+}
+
+/// Hosts games for players connecting over TCP until the process is stopped
+fn serve(args: &[String]) -> i32 {
+    let Some(addr) = args.first() else {
+        println!(
+            "Usage: main --serve <address> [--metrics <address>] [--workers <count>] [world file]"
+        );
+        return 2;
+    };
+    let mut metrics_addr = None;
+    let mut workers = DEFAULT_WORKERS;
+    let mut game_file = None;
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--metrics" => metrics_addr = rest.next().cloned(),
+            "--workers" => workers = rest.next().and_then(|n| n.parse().ok()).unwrap_or(workers),
+            _ => game_file = Some(arg.clone()),
+        }
+    }
+
+    let host = Arc::new(game_lib::SessionHost::new(workers));
+    if let Some(metrics_addr) = metrics_addr {
+        let host = Arc::clone(&host);
+        thread::spawn(move || {
+            if let Err(net_err) = game_lib::serve_metrics(host, &metrics_addr) {
+                println!("Metrics error: {}", net_err);
+            }
+        });
+    }
+    println!("Serving games on {} with {} workers", addr, workers);
+    match game_lib::serve_players(host, addr, game_file) {
+        Ok(()) => 0,
+        Err(net_err) => {
+            println!("Error: {}", net_err);
+            1
+        }
+    }
+}
+
 fn init_game(file_location: &str) -> Result<game_lib::World, std::io::Error> {
     //Here we will read the file and return the world we created.
 
@@ -50,6 +153,14 @@ fn do_game(mut world: game_lib::World) {
         std::process::exit(0);
     }
 
+    let mut autosave = match continue_or_start(&mut world) {
+        Ok(autosave) => Some(autosave),
+        Err(save_err) => {
+            println!("Autosave is off: {}", save_err);
+            None
+        }
+    };
+
     //https://docs.rs/clearscreen/latest/clearscreen/
     clear().expect("Failed to clear screen");
 
@@ -61,6 +172,13 @@ fn do_game(mut world: game_lib::World) {
     }
 
     println!("Hint: Enter <help> to display the commands available");
+    print!("{}", world.describe_chapter());
+    if world.attribute_points > 0 {
+        println!(
+            "You have {} attribute points to spend. Enter <attributes> to see them.",
+            world.attribute_points
+        );
+    }
     let mut command: game_lib::Command;
     //let mut world = game_lib::World::new();
     let mut output: String;
@@ -68,6 +186,12 @@ fn do_game(mut world: game_lib::World) {
     // Main game loop
     loop {
         if world.game_over() {
+            if let Some(message) = world.win_message() {
+                print!("{}", message);
+            }
+            if let Some(autosave) = autosave.take() {
+                autosave.remove().ok();
+            }
             println!("\nWould you like to play again?");
             println!("\n> ");
             io::stdout().flush().unwrap();
@@ -97,6 +221,11 @@ fn do_game(mut world: game_lib::World) {
         command = game_lib::get_input();
         output = world.update_state(&command);
         game_lib::update_screen(output);
+        if let Some(save) = autosave.as_mut() {
+            if let Err(save_err) = save.record(&command, &world) {
+                println!("\nCould not autosave: {}", save_err);
+            }
+        }
 
         if matches!(command, game_lib::Command::Quit) {
             println!("\nGoodbye!");
@@ -106,3 +235,81 @@ fn do_game(mut world: game_lib::World) {
 
     println!("Goodbye!");
 }
+
+/// Offers to continue from the autosave if there is one, otherwise starts a new save
+fn continue_or_start(world: &mut game_lib::World) -> Result<game_lib::Autosave, std::io::Error> {
+    if game_lib::Autosave::exists(AUTOSAVE_LOCATION) {
+        println!("Would you like to continue your last game? (Y/N)");
+        let mut answer = String::new();
+        io::stdin()
+            .read_line(&mut answer)
+            .expect("Failed to read input");
+        if answer.trim().to_lowercase().starts_with('y') {
+            let (saved, autosave) = game_lib::Autosave::load(AUTOSAVE_LOCATION)?;
+            *world = saved;
+            return Ok(autosave);
+        }
+    }
+    // With no more input there is no one left to play
+    if create_character(world).is_none() {
+        println!("\nGoodbye!");
+        exit(0);
+    }
+    game_lib::Autosave::create(AUTOSAVE_LOCATION, world)
+}
+
+/// Prints a question and returns the player's trimmed answer, or None once
+/// the input has run out
+fn ask(question: &str) -> Option<String> {
+    println!("{}", question);
+    print!("> ");
+    stdout().flush().unwrap();
+    let mut answer = String::new();
+    match io::stdin()
+        .read_line(&mut answer)
+        .expect("Failed to read input")
+    {
+        0 => None,
+        _ => Some(answer.trim().to_string()),
+    }
+}
+
+/// Asks the player for their character's name, class and starting attributes
+/// until the answers make a character. Returns None if the input runs out first.
+fn create_character(world: &mut game_lib::World) -> Option<()> {
+    loop {
+        let mut choices = game_lib::CharacterChoices {
+            name: ask("What is your name?")?,
+            ..Default::default()
+        };
+        if !world.classes.is_empty() {
+            print!("{}", world.describe_classes());
+            let class = ask("Which class will you be? (Leave it empty to go without one)")?;
+            choices.class = Some(class).filter(|class| !class.is_empty());
+        }
+        let mut points = world.attribute_points;
+        while points > 0 {
+            let answer = ask(&format!(
+                "You have {} attribute points. Raise strength, agility or intellect? (Leave it empty to keep them for later)",
+                points
+            ))?;
+            if answer.is_empty() {
+                break;
+            }
+            match game_lib::Attribute::named(&answer) {
+                Some(attribute) => {
+                    choices.raises.push(attribute);
+                    points -= 1;
+                }
+                None => println!("There is no attribute called {}.", answer),
+            }
+        }
+        match world.create_character(&choices) {
+            Ok(output) => {
+                print!("{}", output);
+                return Some(());
+            }
+            Err(problem) => println!("{} Let's try that again.", problem),
+        }
+    }
+}
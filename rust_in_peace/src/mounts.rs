@@ -0,0 +1,127 @@
+//! Riding animals: faster travel, places they cannot go, and the risk of
+//! leaving them unattended.
+use super::{Distance, World, LOC_PLAYER};
+use rand::Rng;
+
+// Turns it takes to walk or ride from one location to the next
+const TURNS_ON_FOOT: u64 = 2;
+const TURNS_RIDING: u64 = 1;
+
+// One in this many turns a thief takes a mount left somewhere dangerous
+const THEFT_ODDS: u32 = 10;
+
+impl World {
+    /// Player climbs onto a mount they own
+    pub fn do_mount(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+
+        if !self.objects[index].mount {
+            format!("You can't ride the {}.\n", name)
+        } else if self.riding.is_some() {
+            "You are already riding.\n".to_string()
+        } else if self.get_distance(Some(LOC_PLAYER), Some(index)) != Distance::Here {
+            format!("You don't see any '{}' here.\n", noun)
+        } else if let Some(price) = self.objects[index].price {
+            format!(
+                "The {} is not yours. It costs {} gold to buy.\n",
+                name, price
+            )
+        } else {
+            self.riding = Some(index);
+            format!("You climb onto the {}.\n", name)
+        }
+    }
+
+    /// Player gets off their mount, leaving it where they stand
+    pub fn do_dismount(&mut self) -> String {
+        match self.riding.take() {
+            None => "You are not riding anything.\n".to_string(),
+            Some(mount) => {
+                let here = self.objects[LOC_PLAYER].location.unwrap_or_default();
+                if self.objects[here].stable {
+                    format!(
+                        "You lead the {} into the stable.\n",
+                        self.objects[mount].label[0]
+                    )
+                } else {
+                    format!(
+                        "You hitch the {} here. It may not be safe to leave it for long.\n",
+                        self.objects[mount].label[0]
+                    )
+                }
+            }
+        }
+    }
+
+    /// Returns how many turns it takes to reach the next location
+    pub(crate) fn travel_turns(&self) -> u64 {
+        if self.riding.is_some() {
+            TURNS_RIDING
+        } else {
+            TURNS_ON_FOOT
+        }
+    }
+
+    /// Explains why the player can't ride into a location, if they can't
+    pub(crate) fn mount_refusal(&self, to: usize) -> Option<String> {
+        let mount = self.riding?;
+        if self.objects[to].no_mounts {
+            Some(format!(
+                "You can't ride the {} into the {}. Dismount first.\n",
+                self.objects[mount].label[0], self.objects[to].label[0]
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// A location is dangerous while a living enemy is in it or next to it
+    fn is_dangerous(&self, location: usize) -> bool {
+        let mut nearby: Vec<usize> = self
+            .exits(location)
+            .into_iter()
+            .filter_map(|passage| self.objects[passage].destination)
+            .collect();
+        nearby.push(location);
+
+        self.objects.iter().any(|object| {
+            object.enemy
+                && object.health.unwrap_or(0) > 0
+                && object.location.is_some_and(|loc| nearby.contains(&loc))
+        })
+    }
+
+    /// Gives thieves a chance at mounts left unattended in dangerous places
+    pub(crate) fn run_mounts(&mut self) -> String {
+        let mut output = String::new();
+        let player_loc = self.objects[LOC_PLAYER].location;
+
+        for index in 0..self.objects.len() {
+            let object = &self.objects[index];
+            let Some(location) = object.location else {
+                continue;
+            };
+            if !object.mount
+                || object.price.is_some()
+                || self.riding == Some(index)
+                || Some(location) == player_loc
+                || self.objects[location].stable
+                || !self.is_dangerous(location)
+            {
+                continue;
+            }
+            if self.rng().gen_ratio(1, THEFT_ODDS) {
+                output += &format!(
+                    "\nWord reaches you that your {} was stolen from the {}.\n",
+                    self.objects[index].label[0], self.objects[location].label[0]
+                );
+                self.objects[index].location = None;
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,221 @@
+//! Perks the player has, each a small edge that a part of the game looks out
+//! for. Every class comes with a perk of its own, and the player picks one of
+//! the others every time they level up.
+use super::{World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Health the player starts with and heals up to
+const PLAYER_HEALTH: u64 = 100;
+
+// Damage a steadfast player shrugs off from every blow
+const STEADFAST_DEFENSE: i64 = 2;
+
+// What an eloquent player adds to their persuading and interrogating rolls
+const ELOQUENT_BONUS: i64 = 3;
+
+// Health Thick Skin adds on top of the player's usual
+const THICK_SKIN_HEALTH: u64 = 10;
+
+// Chance in percent Scavenger adds to every drop on an enemy's loot table
+const SCAVENGER_CHANCE: u64 = 20;
+
+// What Light Step adds to the player's sneaking rolls
+const LIGHT_STEP_BONUS: i64 = 3;
+
+/// A perk the player can have
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Perk {
+    /// Takes less damage from every blow
+    Steadfast,
+    /// Never misses with a ranged weapon
+    SureShot,
+    /// Rolls higher when persuading or interrogating someone
+    Eloquent,
+    /// Has more health
+    ThickSkin,
+    /// Finds loot on defeated enemies more often
+    Scavenger,
+    /// Rolls higher when sneaking
+    LightStep,
+}
+
+impl Perk {
+    /// The perks the player can pick when they level up, as opposed to the
+    /// ones that come with a class
+    const PICKABLE: [Perk; 3] = [Perk::ThickSkin, Perk::Scavenger, Perk::LightStep];
+
+    /// Returns the perk's name, such as "Thick Skin"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Perk::Steadfast => "Steadfast",
+            Perk::SureShot => "Sure Shot",
+            Perk::Eloquent => "Eloquent",
+            Perk::ThickSkin => "Thick Skin",
+            Perk::Scavenger => "Scavenger",
+            Perk::LightStep => "Light Step",
+        }
+    }
+}
+
+impl fmt::Display for Perk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.name())?;
+        match self {
+            Perk::Steadfast => write!(f, "blows deal {} less damage to you", STEADFAST_DEFENSE),
+            Perk::SureShot => write!(f, "you never miss with a bow"),
+            Perk::Eloquent => write!(f, "+{} to persuading and interrogating", ELOQUENT_BONUS),
+            Perk::ThickSkin => write!(f, "+{} max health", THICK_SKIN_HEALTH),
+            Perk::Scavenger => write!(
+                f,
+                "+{}% chance of finding loot on enemies",
+                SCAVENGER_CHANCE
+            ),
+            Perk::LightStep => write!(f, "+{} to sneaking", LIGHT_STEP_BONUS),
+        }
+    }
+}
+
+impl World {
+    /// Returns every perk the player has, their class's first
+    pub fn player_perks(&self) -> Vec<Perk> {
+        let player = &self.objects[LOC_PLAYER];
+        player
+            .class
+            .and_then(|class| self.classes.get(class))
+            .map(|class| class.perk)
+            .into_iter()
+            .chain(player.perks.iter().copied())
+            .collect()
+    }
+
+    /// Returns true if the player has the perk, from their class or picked
+    pub fn has_perk(&self, perk: Perk) -> bool {
+        self.player_perks().contains(&perk)
+    }
+
+    /// Returns the most health the player can have
+    pub fn max_health(&self) -> u64 {
+        match self.has_perk(Perk::ThickSkin) {
+            true => PLAYER_HEALTH + THICK_SKIN_HEALTH,
+            false => PLAYER_HEALTH,
+        }
+    }
+
+    /// Returns the most health an object can be healed back up to, which for
+    /// anyone but the player is never more than it was given
+    pub fn max_health_of(&self, index: usize) -> u64 {
+        match index {
+            LOC_PLAYER => self.max_health(),
+            _ => self.objects[index]
+                .max_health
+                .or(self.objects[index].health)
+                .unwrap_or(0),
+        }
+    }
+
+    /// Notes down the health every object starts with as the most it can be
+    /// healed back up to, unless the world file already says
+    pub(crate) fn record_max_health(&mut self) {
+        for object in self.objects.iter_mut() {
+            object.max_health = object.max_health.or(object.health);
+        }
+    }
+
+    /// Returns how much the player's perks take off every blow they receive
+    pub(crate) fn steadfast_defense(&self) -> i64 {
+        match self.has_perk(Perk::Steadfast) {
+            true => STEADFAST_DEFENSE,
+            false => 0,
+        }
+    }
+
+    /// Returns what the player's perks add to persuading and interrogating
+    pub(crate) fn eloquence(&self) -> i64 {
+        match self.has_perk(Perk::Eloquent) {
+            true => ELOQUENT_BONUS,
+            false => 0,
+        }
+    }
+
+    /// Returns what the player's perks add to sneaking
+    pub(crate) fn light_step(&self) -> i64 {
+        match self.has_perk(Perk::LightStep) {
+            true => LIGHT_STEP_BONUS,
+            false => 0,
+        }
+    }
+
+    /// Returns what the player's perks add to the chance of each loot drop
+    pub(crate) fn scavenging(&self) -> u64 {
+        match self.has_perk(Perk::Scavenger) {
+            true => SCAVENGER_CHANCE,
+            false => 0,
+        }
+    }
+
+    /// Returns true if an attack by the attacker with the weapon can't miss
+    pub(crate) fn sure_shot(&self, attacker: usize, weapon: Option<usize>) -> bool {
+        attacker == LOC_PLAYER
+            && weapon.is_some_and(|weapon| self.objects[weapon].ranged)
+            && self.has_perk(Perk::SureShot)
+    }
+
+    /// Player picks a perk, or with no name lists the perks they have and the
+    /// ones they could pick
+    pub fn do_perks(&mut self, noun: &str) -> String {
+        let Some(perk) = Perk::PICKABLE
+            .into_iter()
+            .find(|perk| perk.name().to_lowercase() == noun.to_lowercase())
+        else {
+            return self.describe_perks();
+        };
+        if self.has_perk(perk) {
+            return format!("You already have {}.\n", perk.name());
+        }
+        if self.perk_points == 0 {
+            return "You have no perks to pick. You get to pick one every level.\n".to_string();
+        }
+
+        self.perk_points -= 1;
+        self.objects[LOC_PLAYER].perks.push(perk);
+        if perk == Perk::ThickSkin {
+            let player = &mut self.objects[LOC_PLAYER];
+            player.health = player.health.map(|health| health + THICK_SKIN_HEALTH);
+        }
+        self.record(format!("player picked the {} perk", perk.name()));
+        format!("You pick {}.\n", perk)
+    }
+
+    /// Lists the perks the player has and the ones they could pick
+    fn describe_perks(&self) -> String {
+        let owned = self.player_perks();
+        let mut output = match owned.is_empty() {
+            true => "You have no perks yet.\n".to_string(),
+            false => "Your perks:\n".to_string(),
+        };
+        for perk in &owned {
+            output += &format!("- {}\n", perk);
+        }
+        let available: Vec<Perk> = Perk::PICKABLE
+            .into_iter()
+            .filter(|perk| !owned.contains(perk))
+            .collect();
+        if available.is_empty() {
+            return output;
+        }
+        output += "Perks you can pick:\n";
+        for perk in &available {
+            output += &format!("- {}\n", perk);
+        }
+        output
+            + &match self.perk_points {
+                0 => "You get to pick one every level.\n".to_string(),
+                points => format!(
+                    "You have {} to pick. Try <perk {}>.\n",
+                    points,
+                    available[0].name().to_lowercase()
+                ),
+            }
+    }
+}
@@ -0,0 +1,122 @@
+//! Talking enemies out of a fight. Some enemies will hear the player out, and
+//! a persuasion or intimidation check against how stubborn they are can send
+//! them on their way without a blow struck. Persuasion rolls a d20 plus karma
+//! and intellect, intimidation a d20 plus how fearsome the player's weapon is
+//! and their strength.
+use super::{Attribute, SkillCheck, World, LOC_PLAYER};
+
+// Points of weapon attack that add one to an intimidation roll
+const ATTACK_PER_THREAT: u64 = 5;
+
+/// The two ways of talking an enemy down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Approach {
+    Persuade,
+    Intimidate,
+}
+
+impl World {
+    /// Lists the ways the player can try to talk an enemy out of fighting
+    pub(crate) fn describe_approaches(&self, enemy: usize) -> String {
+        let Some(talk) = self.objects[enemy].talk.as_ref() else {
+            return String::new();
+        };
+        let name = self.objects[enemy].label[0].to_lowercase();
+        let mut options = Vec::new();
+        if talk.persuade.is_some() {
+            options.push(format!("<persuade {}>", name));
+        }
+        if talk.intimidate.is_some() {
+            options.push(format!("<intimidate {}>", name));
+        }
+        match options.is_empty() {
+            true => String::new(),
+            false => format!("You could try to {}.\n", options.join(" or ")),
+        }
+    }
+
+    /// Rolls a check of how convincing the player is with an approach
+    fn approach_check(&self, approach: Approach, difficulty: i64) -> SkillCheck {
+        match approach {
+            Approach::Persuade => self.skill_check(
+                Attribute::Intellect,
+                self.karma + self.eloquence(),
+                difficulty,
+            ),
+            Approach::Intimidate => {
+                let attack = match self.choose_weapon("") {
+                    Ok(Some(weapon)) => self.objects[weapon].attack.unwrap_or(0),
+                    _ => self.objects[LOC_PLAYER].attack.unwrap_or(0),
+                };
+                self.skill_check(
+                    Attribute::Strength,
+                    (attack / ATTACK_PER_THREAT) as i64,
+                    difficulty,
+                )
+            }
+        }
+    }
+
+    /// Player tries to talk an enemy here out of fighting. An enemy that is
+    /// won over leaves for good, and one that isn't attacks.
+    pub(crate) fn do_approach(&mut self, noun: &String, approach: Approach) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+        let difficulty = self.objects[index]
+            .talk
+            .as_ref()
+            .and_then(|talk| match approach {
+                Approach::Persuade => talk.persuade,
+                Approach::Intimidate => talk.intimidate,
+            });
+        let verb = match approach {
+            Approach::Persuade => "persuade",
+            Approach::Intimidate => "intimidate",
+        };
+        if !self.objects[index].enemy || self.objects[index].health.unwrap_or(0) == 0 {
+            return format!("There is no need to {} the {}.\n", verb, name);
+        }
+        if self.objects[index].location != self.objects[LOC_PLAYER].location {
+            return format!("The {} is too far away to hear you.\n", name);
+        }
+        let Some(difficulty) = difficulty else {
+            return format!("The {} won't be talked out of a fight.\n", name);
+        };
+
+        let check = self.approach_check(approach, difficulty);
+        let note = self.roll_note(&check);
+        if !check.passed() {
+            let output = match approach {
+                Approach::Persuade => format!("Your words don't move the {}.{}\n", name, note),
+                Approach::Intimidate => {
+                    format!("Your threats don't scare the {}.{}\n", name, note)
+                }
+            };
+            return output + &self.start_combat(index);
+        }
+        let output = match approach {
+            Approach::Persuade => format!(
+                "You talk the {} round. They put away their weapons and leave in peace.{}\n",
+                name, note
+            ),
+            Approach::Intimidate => {
+                format!(
+                    "You stare the {} down until they turn and flee.{}\n",
+                    name, note
+                )
+            }
+        };
+        let flag = name.to_lowercase();
+        // Talked down for good, which counts as dealt with for winning the game
+        self.objects[index].calmed = true;
+        self.objects[index].location = None;
+        self.objects[index].pursuing = false;
+        self.set_flag(format!("{}d:{}", verb, flag));
+        self.set_flag(format!("spared:{}", flag));
+        self.set_flag(format!("defeated:{}", flag));
+        output
+    }
+}
@@ -0,0 +1,115 @@
+//! Quests npcs give the player. A quest is taken on in a conversation, listed
+//! in the journal while it is open, and rewarded by the npc who gave it once
+//! the player comes back with its objective met.
+use super::{Condition, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// How far the player has got with a quest
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QuestState {
+    #[default]
+    NotStarted,
+    Accepted,
+    Completed,
+}
+
+/// A task an npc asks of the player, and what they give for it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Quest {
+    pub name: String,
+    /// The npc who gives the quest and hands out its reward
+    pub giver: usize,
+    /// What has to hold before the reward is handed out
+    pub objective: Condition,
+    #[serde(default)]
+    pub gold: u64,
+    /// Items waiting outside the world until the reward is handed out
+    #[serde(default)]
+    pub items: Vec<usize>,
+    #[serde(default)]
+    pub experience: u64,
+    #[serde(default)]
+    pub state: QuestState,
+}
+
+impl World {
+    /// Takes on a quest, unless the player already has
+    pub(crate) fn start_quest(&mut self, quest: usize) {
+        if let Some(quest) = self
+            .quests
+            .get_mut(quest)
+            .filter(|quest| quest.state == QuestState::NotStarted)
+        {
+            quest.state = QuestState::Accepted;
+        }
+    }
+
+    /// Hands out the rewards for every quest an npc gave whose objective the
+    /// player has met
+    pub(crate) fn reward_quests(&mut self, npc: usize) -> String {
+        let mut output = String::new();
+        for index in 0..self.quests.len() {
+            let quest = &self.quests[index];
+            if quest.giver != npc
+                || quest.state != QuestState::Accepted
+                || !self.condition_met(&quest.objective)
+            {
+                continue;
+            }
+            let quest = quest.clone();
+            self.quests[index].state = QuestState::Completed;
+
+            let mut rewards = Vec::new();
+            if quest.gold > 0 {
+                self.objects[LOC_PLAYER].gold += quest.gold;
+                rewards.push(format!("{} gold", quest.gold));
+            }
+            for item in quest.items {
+                self.objects[item].location = Some(LOC_PLAYER);
+                rewards.push(format!("the {}", self.objects[item].label[0]));
+            }
+            let mut level_up = String::new();
+            if quest.experience > 0 {
+                level_up = self.gain_experience(quest.experience);
+                rewards.push(format!("{} experience", quest.experience));
+            }
+            self.record(format!("quest {} completed", index));
+            output += &format!(
+                "The {} thanks you for completing \"{}\".\n",
+                self.objects[npc].label[0], quest.name
+            );
+            if !rewards.is_empty() {
+                output += &format!("You receive {}.\n", rewards.join(", "));
+            }
+            output += &level_up;
+        }
+        output
+    }
+
+    /// Lists the quests the player has taken on, for the journal
+    pub fn describe_quests(&self) -> String {
+        let quests: String = self
+            .quests
+            .iter()
+            .filter_map(|quest| {
+                let state = match quest.state {
+                    QuestState::NotStarted => return None,
+                    QuestState::Accepted => "open",
+                    QuestState::Completed => "done",
+                };
+                Some(format!(
+                    "- {} (for the {}, {})\n",
+                    quest.name, self.objects[quest.giver].label[0], state
+                ))
+            })
+            .collect();
+        if quests.is_empty() {
+            return String::new();
+        }
+        let mut output = "Quests:\n".to_string() + &quests;
+        if self.experience > 0 {
+            output += &format!("Experience: {}\n", self.experience);
+        }
+        output
+    }
+}
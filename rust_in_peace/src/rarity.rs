@@ -0,0 +1,121 @@
+//! How rare and how good a piece of gear is. Gear can have a range its attack
+//! or protection is rolled in when a world is loaded, so one playthrough finds
+//! a Fine Sword where another finds a Rusty one, and rare and legendary gear
+//! stands out wherever it is named.
+use super::World;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Rolls in the bottom of these bands make gear rusty, and in the top make it fine
+const QUALITY_BANDS: u64 = 3;
+
+/// How rare a piece of gear is
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Rare,
+    Legendary,
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rarity::Common => write!(f, "common"),
+            Rarity::Rare => write!(f, "rare"),
+            Rarity::Legendary => write!(f, "legendary"),
+        }
+    }
+}
+
+/// The range a weapon's attack or a piece of armor's protection is rolled in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatRoll {
+    pub min: u64,
+    pub max: u64,
+}
+
+/// How well the stats of a piece of gear rolled
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quality {
+    Rusty,
+    Standard,
+    Fine,
+}
+
+impl Quality {
+    /// Returns the word put in front of the gear's name, if any
+    pub fn adjective(&self) -> Option<&'static str> {
+        match self {
+            Quality::Rusty => Some("Rusty"),
+            Quality::Standard => None,
+            Quality::Fine => Some("Fine"),
+        }
+    }
+}
+
+impl World {
+    /// Rolls the stats of every piece of gear that has a range for them and
+    /// hasn't been rolled yet, so a saved game keeps the gear it found
+    pub fn roll_gear(&mut self) {
+        let mut rng = self.rng();
+        for object in self
+            .objects
+            .iter_mut()
+            .filter(|object| object.quality.is_none())
+        {
+            let Some(roll) = object.stat_roll else {
+                continue;
+            };
+            let value = rng.gen_range(roll.min..=roll.max);
+            let band = (value - roll.min) * QUALITY_BANDS / (roll.max - roll.min + 1);
+            object.quality = Some(match band {
+                0 => Quality::Rusty,
+                band if band == QUALITY_BANDS - 1 => Quality::Fine,
+                _ => Quality::Standard,
+            });
+            match object.armor {
+                Some(_) => object.armor = Some(value),
+                None => object.attack = Some(value),
+            }
+        }
+    }
+
+    /// Returns true if a piece of gear was rolled or is rarer than common,
+    /// which is when its name is worth showing along with its description
+    pub fn is_notable(&self, index: usize) -> bool {
+        self.objects[index].quality.is_some() || self.objects[index].rarity > Rarity::Common
+    }
+
+    /// Describes an object as it is listed, naming notable gear after its description
+    pub(crate) fn listed_description(&self, index: usize) -> String {
+        match self.is_notable(index) {
+            true => format!(
+                "{} - {}",
+                self.stack_description(index),
+                self.gear_name(index)
+            ),
+            false => self.stack_description(index),
+        }
+    }
+
+    /// Names a piece of gear with how well it rolled and its stat, such as
+    /// "Fine Sword (+24)". Rare gear is marked with one star on each side and
+    /// legendary gear with two.
+    pub fn gear_name(&self, index: usize) -> String {
+        let object = &self.objects[index];
+        let mut name = match object.quality.and_then(|quality| quality.adjective()) {
+            Some(adjective) => format!("{} {}", adjective, object.label[0]),
+            None => object.label[0].clone(),
+        };
+        if let Some(stat) = object.armor.or(object.attack) {
+            name += &format!(" (+{})", stat);
+        }
+        match object.rarity {
+            Rarity::Common => name,
+            Rarity::Rare => format!("*{}*", name),
+            Rarity::Legendary => format!("**{}**", name),
+        }
+    }
+}
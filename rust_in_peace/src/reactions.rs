@@ -0,0 +1,111 @@
+//! Declarative rules in the world file that change the world some turns after
+//! something important happens, like villagers returning once the bandits are gone.
+use super::{World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// A single change a reaction makes to the world
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Change {
+    /// Moves an object to another location, or out of the world with `None`
+    Move { object: usize, to: Option<usize> },
+    /// Replaces the description of an object
+    Describe { object: usize, text: String },
+    /// Sets a flag, which may in turn set off other reactions
+    Flag(String),
+    /// Brings an object to wherever the player is
+    Summon(usize),
+    /// Gives the player gold, or takes it away if negative
+    Gold(i64),
+    /// Heals the player, or hurts them if negative
+    Health(i64),
+    /// Writes an entry in the player's journal
+    Journal(String),
+    /// Takes on a quest
+    StartQuest(usize),
+    /// Adds to a story variable, or takes away if negative
+    Variable { name: String, add: i64 },
+}
+
+/// A rule that applies its changes a number of turns after a flag is set
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Reaction {
+    pub when: String,
+    #[serde(default)]
+    pub delay: u64,
+    #[serde(default)]
+    pub message: Option<String>,
+    pub changes: Vec<Change>,
+    #[serde(default)]
+    pub armed_at: Option<u64>,
+    #[serde(default)]
+    pub fired: bool,
+}
+
+impl World {
+    /// Arms reactions whose flag has been set and fires the ones that are due
+    pub(crate) fn run_reactions(&mut self) -> String {
+        let mut output = String::new();
+
+        for index in 0..self.reactions.len() {
+            let reaction = &mut self.reactions[index];
+            if reaction.fired || !self.flags.contains(&reaction.when) {
+                continue;
+            }
+            let armed_at = *reaction.armed_at.get_or_insert(self.turn);
+            if self.turn < armed_at + reaction.delay {
+                continue;
+            }
+
+            reaction.fired = true;
+            if let Some(message) = &reaction.message {
+                output += &format!("\n{}\n", message);
+            }
+            let record = format!("reaction {} fired after {}", index, reaction.when);
+            let changes = reaction.changes.clone();
+            self.record(record);
+            for change in changes {
+                self.apply_change(change);
+            }
+        }
+        output
+    }
+
+    /// Applies one change from a reaction
+    pub(crate) fn apply_change(&mut self, change: Change) {
+        match change {
+            Change::Move { object, to } => {
+                if let Some(object) = self.objects.get_mut(object) {
+                    object.location = to;
+                }
+            }
+            Change::Describe { object, text } => {
+                if let Some(object) = self.objects.get_mut(object) {
+                    object.description = text;
+                }
+            }
+            Change::Flag(flag) => self.set_flag(flag),
+            Change::Summon(object) => {
+                let player_loc = self.objects[LOC_PLAYER].location;
+                if let Some(object) = self.objects.get_mut(object) {
+                    object.location = player_loc;
+                }
+            }
+            Change::Gold(amount) => {
+                let player = &mut self.objects[LOC_PLAYER];
+                player.gold = player.gold.saturating_add_signed(amount);
+            }
+            Change::Health(amount) => {
+                let max_health = self.max_health();
+                let player = &mut self.objects[LOC_PLAYER];
+                player.health = player
+                    .health
+                    .map(|health| health.saturating_add_signed(amount).min(max_health));
+            }
+            Change::Journal(text) => self.journal.push(text),
+            Change::StartQuest(quest) => self.start_quest(quest),
+            Change::Variable { name, add } => {
+                *self.variables.entry(name).or_insert(0) += add;
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Resting to get health back. The player sleeps soundly in their own room and
+//! safely in places like the Tavern, where a good rest also shakes off any
+//! disease, but anywhere else something may come upon them while they rest and
+//! cut it short.
+use super::{World, LOC_PLAYER};
+use rand::Rng;
+
+// Turns that pass while the player sleeps
+const REST_TURNS: u64 = 8;
+
+// Health the player gets back for every turn of rest away from home
+const REST_HEALING: u64 = 10;
+
+// Stamina the player gets back for every turn of rest away from home
+const REST_RECOVERY: u64 = 15;
+
+// One in this many turns of rest somewhere unsafe brings an encounter
+const ENCOUNTER_ODDS: u32 = 6;
+
+impl World {
+    /// Returns true if nothing will disturb the player resting where they are
+    pub fn safe_to_rest(&self) -> bool {
+        self.objects[LOC_PLAYER]
+            .location
+            .is_some_and(|location| self.objects[location].safe || self.objects[location].home)
+    }
+
+    /// Returns the first enemy standing in the player's way of resting
+    fn enemy_nearby(&self) -> Option<usize> {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        (0..self.objects.len()).find(|index| {
+            let object = &self.objects[*index];
+            object.enemy
+                && !object.hidden
+                && !object.calmed
+                && object.location == player_loc
+                && object.health.unwrap_or(0) > 0
+        })
+    }
+
+    /// Rolls whether an encounter comes upon the player resting somewhere
+    /// unsafe, bringing it to the player and starting a fight. An encounter
+    /// with a territory only comes upon the player inside it.
+    fn rest_encounter(&mut self) -> Option<String> {
+        let player_loc = self.objects[LOC_PLAYER].location?;
+        let enemy = self.encounters.iter().copied().find(|enemy| {
+            let object = &self.objects[*enemy];
+            object.location.is_none()
+                && object.health.unwrap_or(0) > 0
+                && (object.roams.is_empty() || object.roams.contains(&player_loc))
+        })?;
+        if self.safe_to_rest() || !self.rng().gen_ratio(1, ENCOUNTER_ODDS) {
+            return None;
+        }
+        self.objects[enemy].location = self.objects[LOC_PLAYER].location;
+        self.record(format!(
+            "player's rest was interrupted by the {}",
+            self.objects[enemy].label[0]
+        ));
+        Some(
+            format!(
+                "\nA {} comes upon you in your sleep!\n",
+                self.objects[enemy].label[0]
+            ) + &self.start_combat(enemy),
+        )
+    }
+
+    /// Player rests, sleeping the night away at home and getting health back
+    /// a turn at a time anywhere else
+    pub fn do_rest(&mut self) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        if let Some(enemy) = self.enemy_nearby() {
+            return format!(
+                "You can't rest with the {} about.\n",
+                self.objects[enemy].label[0]
+            );
+        }
+        if player_loc.is_some_and(|location| self.objects[location].home) {
+            let health = self.max_health();
+            let mut output = format!(
+                "You sleep in your own bed and wake up fully rested. Your health is {}.\n",
+                health
+            );
+            self.objects[LOC_PLAYER].health = Some(health);
+            self.recover_stamina(self.max_stamina());
+            output += &self.cure_all();
+            for _ in 1..REST_TURNS {
+                output += &self.tick();
+            }
+            return output;
+        }
+        if self.objects[LOC_PLAYER].health.unwrap_or(0) >= self.max_health()
+            && self.has_stamina(self.max_stamina())
+            && (self.objects[LOC_PLAYER].diseases.is_empty() || !self.safe_to_rest())
+        {
+            return "You are already fully rested.\n".to_string();
+        }
+
+        let mut output = match self.safe_to_rest() {
+            true => "You settle down for a safe rest.\n".to_string(),
+            false => "You settle down to rest, keeping one eye open.\n".to_string(),
+        };
+        // The command's own turn is ticked once the rest is over
+        for turn in 1..=REST_TURNS {
+            let max_health = self.max_health();
+            let player = &mut self.objects[LOC_PLAYER];
+            player.health = player
+                .health
+                .map(|health| (health + REST_HEALING).min(max_health));
+            self.recover_stamina(REST_RECOVERY);
+            if let Some(encounter) = self.rest_encounter() {
+                return output + &encounter;
+            }
+            let rested = self.objects[LOC_PLAYER].health >= Some(max_health)
+                && self.has_stamina(self.max_stamina());
+            if turn == REST_TURNS || rested {
+                break;
+            }
+            output += &self.tick();
+            if self.combat.is_some() || self.enemy_nearby().is_some() {
+                return output + "\nYour rest is cut short.\n";
+            }
+        }
+        output += &format!(
+            "You get up rested. Your health is {}.\n",
+            self.objects[LOC_PLAYER].health.unwrap_or(0)
+        );
+        match self.safe_to_rest() {
+            true => output + &self.cure_all(),
+            false => output,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Enemies that wander between the locations of their territory instead of
+//! waiting for the player to find them.
+use super::{World, LOC_PLAYER};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// One in this many turns a roaming enemy moves on
+const ROAM_ODDS: u32 = 3;
+
+impl World {
+    /// Returns true if the enemy is free to wander around
+    fn can_roam(&self, enemy: usize) -> bool {
+        let object = &self.objects[enemy];
+        object.enemy
+            && !object.roams.is_empty()
+            && object.health.unwrap_or(0) > 0
+            && !object.captured
+            && object.location.is_some()
+            && object.location != self.objects[LOC_PLAYER].location
+    }
+
+    /// Moves a pursuing enemy after the player if they are only one passage away
+    fn pursue(&mut self, enemy: usize) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let passage = self
+            .exits(self.objects[enemy].location.unwrap_or_default())
+            .into_iter()
+            .find(|passage| self.objects[*passage].destination == player_loc);
+
+        match passage {
+            None => {
+                self.objects[enemy].pursuing = false;
+                String::new()
+            }
+            Some(passage) if self.objects[passage].barricaded > 0 => self.batter(enemy, passage),
+            Some(_) => {
+                self.objects[enemy].location = player_loc;
+                format!(
+                    "\nThe {} has followed you here!\n",
+                    self.objects[enemy].label[0]
+                ) + &self.spring_traps(enemy)
+            }
+        }
+    }
+
+    /// Moves roaming enemies through open passages within their territory, or
+    /// after the player if they ran from a fight
+    pub(crate) fn run_roamers(&mut self) -> String {
+        let mut output = String::new();
+        let player_loc = self.objects[LOC_PLAYER].location;
+
+        for index in 0..self.objects.len() {
+            if self.can_roam(index) && self.objects[index].pursuing {
+                output += &self.pursue(index);
+                continue;
+            }
+            if !self.can_roam(index)
+                || self.objects[index].asleep
+                || !self.rng().gen_ratio(1, ROAM_ODDS)
+            {
+                continue;
+            }
+            let destinations: Vec<usize> = self
+                .exits(self.objects[index].location.unwrap_or_default())
+                .into_iter()
+                .filter(|passage| {
+                    self.objects[*passage].locked_by.is_none()
+                        && self.objects[*passage].barricaded == 0
+                })
+                .filter_map(|passage| self.objects[passage].destination)
+                .filter(|destination| self.objects[index].roams.contains(destination))
+                .collect();
+            let Some(&destination) = destinations.choose(&mut self.rng()) else {
+                continue;
+            };
+
+            self.objects[index].location = Some(destination);
+            if Some(destination) == player_loc {
+                output += &format!("\nThe {} wanders in.\n", self.objects[index].label[0]);
+            }
+            output += &self.spring_traps(index);
+        }
+        output
+    }
+}
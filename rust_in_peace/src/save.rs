@@ -0,0 +1,120 @@
+//! Autosaves. Writing out the whole world after every command gets slow for big
+//! worlds, so a save is a full checkpoint of the world plus a journal of the
+//! commands entered since. Saving appends one line to the journal, loading
+//! replays the journal on top of the checkpoint. The journal starts with a
+//! fingerprint of the checkpoint it belongs to, so one left over from an older
+//! checkpoint is never replayed on top of a newer one.
+use super::{Command, World};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+// A fresh checkpoint is written after this many journaled commands
+const CHECKPOINT_INTERVAL: usize = 50;
+
+/// An autosave on disk that the game keeps up to date as it is played
+pub struct Autosave {
+    path: PathBuf,
+    journaled: usize,
+}
+
+impl Autosave {
+    /// Returns true if there is a save at the path to continue from
+    pub fn exists(path: &str) -> bool {
+        Path::new(path).exists()
+    }
+
+    /// Starts a new save for the world, replacing any save already at the path
+    pub fn create(path: &str, world: &World) -> io::Result<Autosave> {
+        let mut save = Autosave {
+            path: PathBuf::from(path),
+            journaled: 0,
+        };
+        save.checkpoint(world)?;
+        Ok(save)
+    }
+
+    /// Loads the checkpoint at the path and replays the journal on top of it
+    pub fn load(path: &str) -> io::Result<(World, Autosave)> {
+        let mut world = World::parse_file(path)?;
+        let checkpoint = fingerprint(&fs::read_to_string(path)?);
+        let mut save = Autosave {
+            path: PathBuf::from(path),
+            journaled: 0,
+        };
+
+        match fs::File::open(save.journal_path()) {
+            Ok(file) => {
+                let mut lines = BufReader::new(file).lines();
+                // A crash between writing a checkpoint and starting its journal
+                // leaves the old journal behind, already part of the checkpoint
+                let tag = lines.next().transpose()?;
+                if tag.and_then(|tag| tag.parse::<u64>().ok()) != Some(checkpoint) {
+                    return Ok((world, save));
+                }
+                for line in lines {
+                    let line = line?;
+                    // A crash in the middle of saving can leave half a line behind
+                    let Ok(command) = ron::from_str::<Command>(&line) else {
+                        break;
+                    };
+                    world.update_state(&command);
+                    save.journaled += 1;
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+        Ok((world, save))
+    }
+
+    /// Saves a command the player entered, given the world after carrying it out
+    pub fn record(&mut self, command: &Command, world: &World) -> io::Result<()> {
+        if self.journaled >= CHECKPOINT_INTERVAL {
+            return self.checkpoint(world);
+        }
+        let line = ron::to_string(command).map_err(io::Error::other)?;
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.journal_path())?;
+        writeln!(journal, "{}", line)?;
+        self.journaled += 1;
+        Ok(())
+    }
+
+    /// Deletes the save, such as when the game is over
+    pub fn remove(self) -> io::Result<()> {
+        fs::remove_file(&self.path)?;
+        match fs::remove_file(self.journal_path()) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the whole world out and starts an empty journal for it
+    fn checkpoint(&mut self, world: &World) -> io::Result<()> {
+        let data = ron::to_string(world).map_err(io::Error::other)?;
+        // Write next to the old checkpoint first so a crash never leaves a broken one
+        let staging = self.path.with_extension("tmp");
+        fs::write(&staging, &data)?;
+        fs::rename(&staging, &self.path)?;
+        fs::write(self.journal_path(), format!("{}\n", fingerprint(&data)))?;
+        self.journaled = 0;
+        Ok(())
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".journal");
+        PathBuf::from(path)
+    }
+}
+
+/// Tells checkpoints apart with an FNV-1a hash, which stays the same from one
+/// build to the next
+fn fingerprint(data: &str) -> u64 {
+    data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,107 @@
+//! Daily routines. Every turn is an hour of the day, and npcs and enemies with a
+//! schedule move between locations as the hours pass, like a merchant who
+//! trades in the village by day and sits in the tavern at night.
+use super::{World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+// Hours in a day, and the hours the sun rises and night falls
+const DAY_LENGTH: u64 = 24;
+const DAWN: u64 = 6;
+const NIGHTFALL: u64 = 18;
+
+// The hour the player sets out on the first turn
+const START_HOUR: u64 = 8;
+
+/// Where a character goes at an hour of the day, staying until its next stop
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stop {
+    pub hour: u64,
+    pub location: usize,
+}
+
+impl World {
+    /// Returns the hour of the day it is
+    pub fn hour(&self) -> u64 {
+        (self.turn + START_HOUR) % DAY_LENGTH
+    }
+
+    /// Returns true between nightfall and dawn
+    pub fn is_night(&self) -> bool {
+        !(DAWN..NIGHTFALL).contains(&self.hour())
+    }
+
+    /// Returns where a character's schedule has it at the hour it is, which is
+    /// the last stop of the day before its first one
+    fn scheduled_location(&self, index: usize) -> Option<usize> {
+        let schedule = &self.objects[index].schedule;
+        schedule
+            .iter()
+            .filter(|stop| stop.hour <= self.hour())
+            .max_by_key(|stop| stop.hour)
+            .or_else(|| schedule.iter().max_by_key(|stop| stop.hour))
+            .map(|stop| stop.location)
+    }
+
+    /// Returns true if a character is free to follow its schedule. Characters
+    /// not in the world yet, down, caught or in a fight stay where they are.
+    fn keeps_schedule(&self, index: usize) -> bool {
+        let object = &self.objects[index];
+        !object.schedule.is_empty()
+            && object.location.is_some()
+            && object.health != Some(0)
+            && !object.captured
+            && object.snared_by.is_none()
+            && !object.companion.as_ref().is_some_and(|c| c.joined)
+            && !self
+                .combat
+                .as_ref()
+                .is_some_and(|combat| combat.enemies.contains(&index))
+    }
+
+    /// Moves characters to where their schedules have them, telling the player
+    /// about anyone who leaves or arrives where they are
+    pub(crate) fn run_schedules(&mut self) -> String {
+        let mut output = String::new();
+        let player_loc = self.objects[LOC_PLAYER].location;
+
+        for index in 0..self.objects.len() {
+            if !self.keeps_schedule(index) {
+                continue;
+            }
+            let Some(to) = self.scheduled_location(index) else {
+                continue;
+            };
+            let from = self.objects[index].location;
+            if from == Some(to) {
+                continue;
+            }
+            self.objects[index].location = Some(to);
+            let name = &self.objects[index].label[0];
+            if from == player_loc {
+                output += &format!(
+                    "\nThe {} leaves for the {}.\n",
+                    name, self.objects[to].label[0]
+                );
+            } else if Some(to) == player_loc {
+                output += &format!("\nThe {} arrives.\n", name);
+            }
+        }
+        output
+    }
+
+    /// Tells the player what time of day it is and how long until that changes
+    pub fn do_time(&self) -> String {
+        let hour = self.hour();
+        let (now, change, at) = match self.is_night() {
+            true => ("night", "Dawn comes", DAWN),
+            false => ("day", "Night falls", NIGHTFALL),
+        };
+        match (at + DAY_LENGTH - hour) % DAY_LENGTH {
+            1 => format!("It is {}, hour {}. {} next turn.\n", now, hour, change),
+            turns => format!(
+                "It is {}, hour {}. {} in {} turns.\n",
+                now, hour, change, turns
+            ),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Hidden things, like secret passages, that only show up once the player
+//! searches for them.
+use super::{Attribute, Skill, World, LOC_PLAYER};
+
+// An intellect check helped by twice the player's perception at or above this
+// finds a hidden thing
+const SEARCH_ROLL: i64 = 14;
+
+impl World {
+    /// Player searches their location for hidden things
+    pub fn do_search(&mut self) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        if player_loc.is_some_and(|location| self.is_dark(location)) {
+            return "It is too dark to search here.\n".to_string();
+        }
+
+        let mut output = String::new();
+        for index in 0..self.objects.len() {
+            if !self.objects[index].hidden || self.objects[index].location != player_loc {
+                continue;
+            }
+            let check = self.skill_check(
+                Attribute::Intellect,
+                2 * self.skill(Skill::Perception) as i64,
+                SEARCH_ROLL,
+            );
+            // Failed checks stay quiet, or they would give away what is hidden
+            if check.passed() {
+                self.objects[index].hidden = false;
+                output += &format!(
+                    "You find something: {}{}\n",
+                    self.objects[index].description,
+                    self.roll_note(&check)
+                );
+            }
+        }
+        if output.is_empty() {
+            "You search around but find nothing.\n".to_string()
+        } else {
+            output
+        }
+    }
+}
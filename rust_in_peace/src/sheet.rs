@@ -0,0 +1,66 @@
+//! The player's character sheet, gathering everything about them in one place:
+//! who they are, how healthy they are, their attributes and perks, the gear
+//! they have ready and how much noise it makes, the effects they are under and
+//! how wealthy they are.
+use super::{Slot, World, LOC_PLAYER};
+
+impl World {
+    /// Shows the player's character sheet
+    pub fn do_stats(&self) -> String {
+        let player = &self.objects[LOC_PLAYER];
+        let name = player.name.as_deref().unwrap_or("Nameless");
+        let class = player
+            .class
+            .and_then(|class| self.classes.get(class))
+            .map_or("adventurer", |class| class.name.as_str());
+        let mut output = format!(
+            "{}, level {} {}\nhealth: {}/{}\n",
+            name,
+            self.level(),
+            class,
+            player.health.unwrap_or(0),
+            self.max_health()
+        );
+        if let Some(stamina) = player.stamina {
+            output += &format!("stamina: {}/{}\n", stamina, self.max_stamina());
+        }
+        output += &self.describe_survival();
+        output += &self.describe_attributes();
+
+        for slot in [Slot::Weapon, Slot::Armor, Slot::Trinket] {
+            let gear = self
+                .equipped(slot)
+                .map_or("nothing", |index| self.objects[index].label[0].as_str());
+            output += &format!("{}: {}\n", slot, gear);
+        }
+        output += &format!("noise: {}\n", self.noise_level());
+        let list = |names: Vec<String>| match names.is_empty() {
+            true => "none".to_string(),
+            false => names.join(", "),
+        };
+        output += &format!(
+            "perks: {}\neffects: {}\ngold: {}, valuables: {}\nkarma: {}, reputation: {}\n",
+            list(
+                self.player_perks()
+                    .iter()
+                    .map(|perk| perk.name().to_string())
+                    .collect()
+            ),
+            list(
+                player
+                    .effects
+                    .iter()
+                    .map(|effect| effect.to_string())
+                    .collect()
+            ),
+            player.gold,
+            self.valuables_worth(),
+            self.karma,
+            self.reputation
+        );
+        output
+            + &self.describe_diseases()
+            + &self.describe_level()
+            + &self.describe_attribute_points()
+    }
+}
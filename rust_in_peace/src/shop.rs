@@ -0,0 +1,156 @@
+//! Shopping at traders. What a trader charges depends on the player's
+//! reputation and on how well they haggled, and a trader restocks what it
+//! keeps in stock every so often, so what the player buys up comes back. Good
+//! prices only go for buying, or the player could sell back what they bought
+//! for more than they paid.
+use super::{Attribute, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+// Percent off a trader's prices for every point of reputation, and the most
+// reputation can take off or put on
+const REPUTATION_DISCOUNT: i64 = 2;
+const MAX_REPUTATION_DISCOUNT: i64 = 30;
+
+// What a haggling check has to reach, the percent off a trader gives when the
+// player haggles well, and the percent it puts on when they haggle badly
+const HAGGLE_DIFFICULTY: i64 = 12;
+const HAGGLE_DISCOUNT: i64 = 15;
+const HAGGLE_PENALTY: i64 = 10;
+
+// Turns between a trader's restocks
+const RESTOCK_TURNS: u64 = 30;
+
+/// An item a trader keeps in stock, and how many of it a restock brings it back up to
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stock {
+    pub item: usize,
+    pub quantity: u64,
+}
+
+impl World {
+    /// Returns the percent a trader takes off its prices for the player, or
+    /// puts on them if it is negative
+    pub fn trade_discount(&self, trader: usize) -> i64 {
+        (self.reputation * REPUTATION_DISCOUNT)
+            .clamp(-MAX_REPUTATION_DISCOUNT, MAX_REPUTATION_DISCOUNT)
+            + self.objects[trader].haggle.unwrap_or(0)
+    }
+
+    /// Returns what a trader charges the player for something with a price,
+    /// which is always at least a gold coin
+    pub fn trade_price(&self, trader: usize, price: u64) -> u64 {
+        let percent = (100 - self.trade_discount(trader)).max(0) as u64;
+        (price * percent / 100).max(1)
+    }
+
+    /// Returns what a trader pays the player for what it would otherwise pay,
+    /// which is less if it charges them extra and never more
+    pub(crate) fn trade_offer(&self, trader: usize, paid: u64) -> u64 {
+        let percent = (100 + self.trade_discount(trader).min(0)).max(0) as u64;
+        paid * percent / 100
+    }
+
+    /// Player looks over what a trader where they are has for sale
+    pub fn do_shop(&self) -> String {
+        let Some(trader) = self.trader_here() else {
+            return "There is nobody here to trade with.\n".to_string();
+        };
+        let name = &self.objects[trader].label[0];
+        let prices = match self.trade_discount(trader) {
+            0 => String::new(),
+            discount if discount > 0 => {
+                format!("The {} gives you {}% off.\n", name, discount)
+            }
+            discount => format!("The {} charges you {}% extra.\n", name, -discount),
+        };
+        self.list_stock(trader) + &prices
+    }
+
+    /// Player haggles with a trader where they are, which gets them better
+    /// prices if it goes well and worse ones if it doesn't, until it restocks
+    pub fn do_haggle(&mut self) -> String {
+        let Some(trader) = self.trader_here() else {
+            return "There is nobody here to haggle with.\n".to_string();
+        };
+        let name = self.objects[trader].label[0].clone();
+        if self.objects[trader].haggle.is_some() {
+            return format!(
+                "The {} won't haggle with you again until it restocks.\n",
+                name
+            );
+        }
+        let check = self.skill_check(Attribute::Intellect, self.eloquence(), HAGGLE_DIFFICULTY);
+        let note = self.roll_note(&check);
+        match check.passed() {
+            true => {
+                self.objects[trader].haggle = Some(HAGGLE_DISCOUNT);
+                format!(
+                    "The {} grudgingly knocks {}% off its prices{}.\n",
+                    name, HAGGLE_DISCOUNT, note
+                )
+            }
+            false => {
+                self.objects[trader].haggle = Some(-HAGGLE_PENALTY);
+                format!(
+                    "The {} takes offence and puts {}% on its prices{}.\n",
+                    name, HAGGLE_PENALTY, note
+                )
+            }
+        }
+    }
+
+    /// Brings what a trader keeps in stock back up to how many of it the
+    /// trader should have. Something sold out comes back as a fresh copy of
+    /// the item.
+    fn restock(&mut self, trader: usize) {
+        for stock in self.objects[trader].restock.clone() {
+            let label = &self.objects[stock.item].label[0];
+            let held = (0..self.objects.len()).find(|index| {
+                self.objects[*index].location == Some(trader)
+                    && &self.objects[*index].label[0] == label
+            });
+            match held {
+                Some(index) if self.count(index) < stock.quantity => {
+                    self.objects[index].quantity = Some(stock.quantity);
+                }
+                Some(_) => {}
+                None => {
+                    let mut fresh = self.objects[stock.item].clone();
+                    fresh.location = Some(trader);
+                    fresh.price = fresh.price.or(fresh.value);
+                    fresh.lit = false;
+                    if fresh.quantity.is_some() || stock.quantity > 1 {
+                        fresh.quantity = Some(stock.quantity);
+                    }
+                    self.place_copy(fresh);
+                }
+            }
+        }
+    }
+
+    /// Restocks every trader every so often, letting the player know if they
+    /// are there to see it. Traders are willing to haggle again afterwards.
+    pub(crate) fn run_restock(&mut self) -> String {
+        if !self.turn.is_multiple_of(RESTOCK_TURNS) {
+            return String::new();
+        }
+        let mut output = String::new();
+        for trader in 0..self.objects.len() {
+            if !self.objects[trader].trader {
+                continue;
+            }
+            self.objects[trader].haggle = None;
+            if self.objects[trader].restock.is_empty() {
+                continue;
+            }
+            self.restock(trader);
+            if self.objects[trader].location == self.objects[LOC_PLAYER].location {
+                output += &format!(
+                    "\nThe {} restocks its wares.\n",
+                    self.objects[trader].label[0]
+                );
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,92 @@
+//! Shrines that take an offering and answer it with a blessing or, now and
+//! then, a curse.
+use super::{Command, StatusEffect, World, LOC_PLAYER};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Everything that makes an object a shrine
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Shrine {
+    /// Gold it takes to make an offering
+    pub cost: u64,
+    pub blessings: Vec<StatusEffect>,
+    #[serde(default)]
+    pub curses: Vec<StatusEffect>,
+    /// One in this many offerings brings a curse instead of a blessing
+    #[serde(default)]
+    pub curse_odds: u32,
+}
+
+impl World {
+    /// Player makes an offering of gold or an item at a shrine where they stand
+    pub fn do_offer(&mut self, noun: &String) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let shrine = (0..self.objects.len()).find(|index| {
+            self.objects[*index].shrine.is_some() && self.objects[*index].location == player_loc
+        });
+        let Some(shrine) = shrine else {
+            return "There is no shrine here to make an offering at.\n".to_string();
+        };
+        let cost = self.objects[shrine].shrine.as_ref().map_or(0, |s| s.cost);
+
+        let offering = if noun.to_lowercase() == "gold" {
+            if self.objects[LOC_PLAYER].gold < cost {
+                return format!(
+                    "The {} asks for {} gold.\n",
+                    self.objects[shrine].label[0], cost
+                );
+            }
+            self.objects[LOC_PLAYER].gold -= cost;
+            format!("{} gold", cost)
+        } else {
+            let (output, obj_opt) =
+                self.get_possession(Some(LOC_PLAYER), Command::Offer(noun.clone()), noun);
+            let Some(item) = obj_opt else {
+                return output;
+            };
+            if let Some(refusal) = self.keep_key_item(item) {
+                return refusal;
+            }
+            self.use_up(item);
+            format!("the {}", self.objects[item].label[0])
+        };
+
+        self.answer_offering(shrine, offering)
+    }
+
+    /// Rolls what a shrine gives back for an offering
+    fn answer_offering(&mut self, shrine: usize, offering: String) -> String {
+        let Some(details) = self.objects[shrine].shrine.clone() else {
+            return String::new();
+        };
+        let mut rng = self.rng();
+        let cursed = details.curse_odds > 0
+            && !details.curses.is_empty()
+            && rng.gen_ratio(1, details.curse_odds);
+        let name = self.objects[shrine].label[0].clone();
+
+        let (effect, message) = if cursed {
+            (
+                details.curses.choose(&mut rng).cloned(),
+                "It grows cold. You are cursed",
+            )
+        } else {
+            (
+                details.blessings.choose(&mut rng).cloned(),
+                "A warm light surrounds you. You are blessed",
+            )
+        };
+        match effect {
+            Some(effect) => {
+                let output = format!(
+                    "You offer {} at the {}. {}: {}.\n",
+                    offering, name, message, effect
+                );
+                self.add_effect(LOC_PLAYER, effect);
+                output
+            }
+            None => format!("You offer {} at the {}. Nothing happens.\n", offering, name),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Items that come in stacks, such as a handful of apples. A stack is one
+//! object with a quantity, shown on one line, and part of it can be picked up
+//! or dropped while the rest stays where it is. Stacks of the same item that
+//! end up in the same place are merged. Splitting a stack makes a copy of its
+//! object, and copies that have been merged away make room for the next ones,
+//! so picking up and dropping stacks doesn't grow the world.
+use super::{Distance, Object, World, LOC_PLAYER};
+
+impl World {
+    /// Returns how many items an object stands for
+    pub fn count(&self, index: usize) -> u64 {
+        self.objects[index].quantity.unwrap_or(1)
+    }
+
+    /// Names an object, with how many there are if it is a stack of more than one
+    pub fn stack_name(&self, index: usize) -> String {
+        match self.count(index) {
+            1 => self.objects[index].label[0].clone(),
+            count => format!("{} (x{})", self.objects[index].label[0], count),
+        }
+    }
+
+    /// Describes an object, with how many there are if it is a stack of more than one
+    pub(crate) fn stack_description(&self, index: usize) -> String {
+        match self.count(index) {
+            1 => self.objects[index].description.clone(),
+            count => format!("{} (x{})", self.objects[index].description, count),
+        }
+    }
+
+    /// Returns a stack lying where the player is that goes by a name, which is
+    /// the one they mean to pick up when they carry a stack of the same item
+    pub(crate) fn stack_here(&self, noun: &str) -> Option<usize> {
+        self.matching_objects(noun, Some(LOC_PLAYER), Distance::Here)
+            .into_iter()
+            .find(|index| {
+                self.objects[*index].quantity.is_some()
+                    && self.get_distance(Some(LOC_PLAYER), Some(*index)) == Distance::Here
+            })
+    }
+
+    /// Checks how many of an object the player asked for, taking all of it if
+    /// they didn't say
+    pub(crate) fn count_wanted(&self, index: usize, wanted: Option<u64>) -> Result<u64, String> {
+        let count = self.count(index);
+        match wanted {
+            None => Ok(count),
+            Some(0) => Err("You can't move none of something.\n".to_string()),
+            Some(wanted) if wanted > count => Err(format!(
+                "There is only {} of the {}.\n",
+                count, self.objects[index].label[0]
+            )),
+            Some(wanted) => Ok(wanted),
+        }
+    }
+
+    /// Moves some of a stack to a new location, leaving the rest where it is
+    pub(crate) fn move_some(&mut self, index: usize, count: u64, to: Option<usize>) -> String {
+        if count >= self.count(index) {
+            return self.move_object(Some(index), to);
+        }
+
+        let mut part = self.objects[index].clone();
+        part.quantity = Some(count);
+        self.objects[index].quantity = Some(self.count(index) - count);
+        let part = self.place_copy(part);
+        self.move_object(Some(part), to)
+    }
+
+    /// Puts a copy of an object into the world, in the place of a copy that
+    /// is gone if there is one, and returns where it went
+    pub(crate) fn place_copy(&mut self, mut copy: Object) -> usize {
+        copy.copy = true;
+        let gear = [self.riding, self.wearing, self.wielding, self.trinket];
+        let spare = (0..self.objects.len()).find(|index| {
+            self.objects[*index].copy
+                && self.objects[*index].location.is_none()
+                && !gear.contains(&Some(*index))
+        });
+        match spare {
+            Some(index) => {
+                self.objects[index] = copy;
+                index
+            }
+            None => {
+                self.objects.push(copy);
+                self.objects.len() - 1
+            }
+        }
+    }
+
+    /// Adds one more of a stacking item to a place, as a fresh copy of it
+    /// that joins any stack of it already there
+    pub(crate) fn add_one(&mut self, item: usize, to: Option<usize>) {
+        let mut fresh = self.objects[item].clone();
+        fresh.location = to;
+        fresh.quantity = Some(1);
+        let fresh = self.place_copy(fresh);
+        self.merge_stack(fresh);
+    }
+
+    /// Merges a stack into another stack of the same item in the same place
+    pub(crate) fn merge_stack(&mut self, index: usize) {
+        if self.objects[index].quantity.is_none() {
+            return;
+        }
+        let label = &self.objects[index].label[0];
+        let location = self.objects[index].location;
+        let other = (0..self.objects.len()).find(|other| {
+            *other != index
+                && self.objects[*other].quantity.is_some()
+                && self.objects[*other].location == location
+                && &self.objects[*other].label[0] == label
+        });
+        if let Some(other) = other {
+            self.objects[other].quantity = Some(self.count(other) + self.count(index));
+            self.objects[index].location = None;
+        }
+    }
+
+    /// Returns what the player carries that goes by an item's name, which takes
+    /// in any copies split off from it
+    pub(crate) fn carried_like(&self, item: usize) -> Vec<usize> {
+        let label = &self.objects[item].label[0];
+        (0..self.objects.len())
+            .filter(|index| {
+                self.objects[*index].location == Some(LOC_PLAYER)
+                    && &self.objects[*index].label[0] == label
+            })
+            .collect()
+    }
+
+    /// Uses up one of a stack, or the whole object if it doesn't stack
+    pub(crate) fn use_up(&mut self, index: usize) {
+        match self.objects[index].quantity {
+            Some(count) if count > 1 => self.objects[index].quantity = Some(count - 1),
+            _ => self.objects[index].location = None,
+        }
+    }
+}
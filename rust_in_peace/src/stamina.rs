@@ -0,0 +1,88 @@
+//! Stamina, the breath the player spends on strenuous things like swinging a
+//! weapon, running from a fight or walking from place to place. A winded
+//! player strikes weakly, can't run and has to stop and catch their breath,
+//! which gives a fight a pace of its own beyond trading blows until someone
+//! drops. A player without a stamina pool never tires.
+use super::{World, LOC_PLAYER};
+
+// Stamina the player starts with and recovers up to
+const PLAYER_STAMINA: u64 = 50;
+
+// Stamina spent on every attack
+pub(crate) const ATTACK_COST: u64 = 5;
+
+// Stamina spent on trying to run from a fight
+pub(crate) const FLEE_COST: u64 = 10;
+
+// Stamina spent on walking from one location to the next
+pub(crate) const TRAVEL_COST: u64 = 2;
+
+// Stamina recovered by bracing for a blow in a fight
+pub(crate) const DEFEND_RECOVERY: u64 = 5;
+
+// Stamina recovered by waiting a turn
+const WAIT_RECOVERY: u64 = 15;
+
+impl World {
+    /// Returns the most stamina the player can have
+    pub fn max_stamina(&self) -> u64 {
+        PLAYER_STAMINA
+    }
+
+    /// Returns true if the player has the stamina for something costing this
+    /// much, which they always do without a stamina pool
+    pub fn has_stamina(&self, cost: u64) -> bool {
+        self.objects[LOC_PLAYER]
+            .stamina
+            .is_none_or(|stamina| stamina >= cost)
+    }
+
+    /// Returns true if a fighter is out of breath and strikes weakly
+    pub(crate) fn winded(&self, fighter: usize) -> bool {
+        fighter == LOC_PLAYER && self.objects[LOC_PLAYER].stamina == Some(0)
+    }
+
+    /// Spends the player's stamina, as much of it as they have. Returns what
+    /// the player sees if it leaves them out of breath.
+    pub(crate) fn spend_stamina(&mut self, cost: u64) -> String {
+        let Some(stamina) = self.objects[LOC_PLAYER].stamina else {
+            return String::new();
+        };
+        let left = stamina.saturating_sub(cost);
+        self.objects[LOC_PLAYER].stamina = Some(left);
+        match stamina > 0 && left == 0 {
+            true => {
+                "\nYou are out of breath. Your blows land weakly until you catch it.".to_string()
+            }
+            false => String::new(),
+        }
+    }
+
+    /// Gives the player back some stamina, never past the most they can have
+    pub(crate) fn recover_stamina(&mut self, amount: u64) {
+        let most = self.max_stamina();
+        let player = &mut self.objects[LOC_PLAYER];
+        player.stamina = player.stamina.map(|stamina| (stamina + amount).min(most));
+    }
+
+    /// Returns why the player is too tired to walk on, if they are. Riding
+    /// costs them nothing.
+    pub(crate) fn travel_refusal(&self) -> Option<String> {
+        match self.riding.is_some() || self.has_stamina(TRAVEL_COST) {
+            true => None,
+            false => Some("You are too tired to go on. Try <wait> or <rest>.\n".to_string()),
+        }
+    }
+
+    /// Player waits a turn, catching their breath
+    pub fn do_wait(&mut self) -> String {
+        if self.objects[LOC_PLAYER].stamina.is_none() {
+            return "You wait a while.\n".to_string();
+        }
+        self.recover_stamina(WAIT_RECOVERY);
+        format!(
+            "You wait a while and catch your breath. Your stamina: {}\n",
+            self.objects[LOC_PLAYER].stamina.unwrap_or(0)
+        )
+    }
+}
@@ -0,0 +1,118 @@
+//! How much noise the player's gear makes, and what that costs them when
+//! trying to go unnoticed.
+use super::{Attribute, Skill, World, LOC_PLAYER};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// A d20 plus the player's noise at or above this lets an enemy ambush them
+const AMBUSH_ROLL: u64 = 20;
+// An agility check, hindered by the player's noise, at or above this lets them
+// sneak away
+const SNEAK_ROLL: i64 = 8;
+
+/// How bulky a piece of gear is
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WeightClass {
+    #[default]
+    Light,
+    Medium,
+    Heavy,
+}
+
+impl WeightClass {
+    /// Returns the noise gear of this class makes when carried
+    pub fn noise(&self) -> u64 {
+        match self {
+            WeightClass::Light => 0,
+            WeightClass::Medium => 1,
+            WeightClass::Heavy => 3,
+        }
+    }
+}
+
+impl World {
+    /// Returns the noise made by everything the player carries
+    pub fn noise(&self) -> u64 {
+        self.objects
+            .iter()
+            .filter(|object| object.location == Some(LOC_PLAYER))
+            .map(|object| object.weight_class.noise())
+            .sum()
+    }
+
+    /// Describes the player's noise in words
+    pub fn noise_level(&self) -> &'static str {
+        match self.noise() {
+            0 => "quiet",
+            1..=2 => "noticeable",
+            _ => "loud",
+        }
+    }
+
+    /// Player tries to slip out of an enemy's sight, which is harder in noisy gear
+    pub(crate) fn try_sneak(&mut self, enemy: usize) -> String {
+        let check = self.skill_check(
+            Attribute::Agility,
+            2 * self.skill(Skill::Stealth) as i64 + self.light_step() - self.noise() as i64,
+            SNEAK_ROLL,
+        );
+        let note = self.roll_note(&check);
+        if check.passed() {
+            let threat = self.threat(enemy, LOC_PLAYER);
+            self.objects[enemy].threat.insert(LOC_PLAYER, threat / 2);
+            format!(
+                "You slip out of the {}'s sight.{}",
+                self.objects[enemy].label[0], note
+            )
+        } else {
+            format!(
+                "Your gear rattles and the {} keeps its eyes on you.{}",
+                self.objects[enemy].label[0], note
+            )
+        }
+    }
+
+    /// Gives enemies at a location the chance to jump a noisy player walking in
+    pub(crate) fn ambush(&mut self, location: usize) -> String {
+        let mut output = String::new();
+        let mut rng = self.rng();
+
+        for index in 0..self.objects.len() {
+            let object = &self.objects[index];
+            let attack = match object.attack {
+                Some(attack) if attack > 0 => attack,
+                _ => continue,
+            };
+            if !object.enemy
+                || object.calmed
+                || object.location != Some(location)
+                || object.health.unwrap_or(0) == 0
+                || rng.gen_range(1..=20) + self.noise()
+                    < AMBUSH_ROLL + 2 * self.skill(Skill::Stealth)
+            {
+                continue;
+            }
+            // A sleeping enemy that hears the player only wakes up
+            if object.asleep {
+                self.objects[index].asleep = false;
+                output += &format!(
+                    "\nThe {} stirs and wakes up.\n",
+                    self.objects[index].label[0]
+                );
+                continue;
+            }
+            let damage = World::adjust_damage(
+                rng.gen_range(1..=attack),
+                self.attack_bonus(index) - self.defense_bonus(LOC_PLAYER),
+            );
+            let health = self.objects[LOC_PLAYER].health.unwrap_or(0);
+            self.objects[LOC_PLAYER].health = Some(health.saturating_sub(damage));
+            output += &format!(
+                "\nThe {} hears you coming and ambushes you!\nYour health: {}\n",
+                self.objects[index].label[0],
+                self.objects[LOC_PLAYER].health.unwrap_or(0)
+            );
+        }
+        output
+    }
+}
@@ -0,0 +1,67 @@
+//! The main storyline. A world file can split its story into chapters, each
+//! with a goal for the player and a condition that completes it, and the world
+//! moves on to the next chapter once the condition holds. Chapters make their
+//! changes when they are completed, so descriptions and dialogue can follow
+//! the story along. Alongside flags the story can keep count of things in
+//! variables, which changes add to and conditions compare against.
+use super::{Change, Condition, World};
+use serde::{Deserialize, Serialize};
+
+/// A part of the main storyline
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Chapter {
+    pub name: String,
+    /// What the player has to do, shown when the chapter begins and in the journal
+    pub goal: String,
+    /// What has to hold for the chapter to be completed
+    pub complete: Condition,
+    /// Made to the world when the chapter is completed
+    #[serde(default)]
+    pub changes: Vec<Change>,
+}
+
+impl World {
+    /// Returns the value of a story variable, which is 0 until it is changed
+    pub fn variable(&self, name: &str) -> i64 {
+        self.variables.get(name).copied().unwrap_or(0)
+    }
+
+    /// Describes the chapter the player is in and its goal
+    pub fn describe_chapter(&self) -> String {
+        match self.chapters.get(self.chapter) {
+            Some(chapter) => format!(
+                "Chapter {}: {}\n{}\n",
+                self.chapter + 1,
+                chapter.name,
+                chapter.goal
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// Completes the chapters whose conditions hold and begins the next ones
+    pub(crate) fn run_chapters(&mut self) -> String {
+        let mut output = String::new();
+        while let Some(chapter) = self.chapters.get(self.chapter) {
+            if !self.condition_met(&chapter.complete) {
+                break;
+            }
+            let chapter = chapter.clone();
+            output += &format!(
+                "\nChapter {} complete: {}.\n",
+                self.chapter + 1,
+                chapter.name
+            );
+            self.set_flag(format!("chapter:{}", self.chapter + 1));
+            for change in chapter.changes {
+                self.apply_change(change);
+            }
+            self.chapter += 1;
+            output += &match self.describe_chapter().as_str() {
+                "" => "\nThe story is over, but the world goes on.\n".to_string(),
+                next => format!("\n{}", next),
+            };
+        }
+        output
+    }
+}
@@ -0,0 +1,99 @@
+//! Guessing what the player meant when they mistype a command or a name. Near
+//! misses are fixed on the spot, anything further off is offered as a question.
+use super::help::verbs;
+use super::{parse, Command, Distance, World, LOC_PLAYER};
+
+// A guess this close is taken without asking
+const AUTO_ACCEPT_DISTANCE: usize = 1;
+// Words shorter than this are too easily mistaken for others to fix on their own
+const MIN_AUTO_ACCEPT_LENGTH: usize = 4;
+
+/// Counts the letters that have to be added, removed, changed or swapped with
+/// their neighbour to turn one word into the other
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            rows[i][j] = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                rows[i][j] = rows[i][j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Picks the candidate closest to a word. Returns it along with whether it is
+/// close enough, and clear enough of the others, to be taken without asking.
+/// Returns None if nothing is close enough to suggest.
+fn guess<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<(String, bool)> {
+    let word = word.to_lowercase();
+    let mut scored: Vec<(usize, String)> = candidates
+        .filter(|candidate| !candidate.is_empty())
+        .map(|candidate| candidate.to_lowercase())
+        .map(|candidate| (edit_distance(&word, &candidate), candidate))
+        .collect();
+    scored.sort();
+    scored.dedup();
+
+    let (distance, best) = scored.first()?.clone();
+    // Allow one mistake for every three letters typed
+    if distance == 0 || distance * 3 > word.chars().count() {
+        return None;
+    }
+    let unique = scored.get(1).is_none_or(|(next, _)| *next > distance);
+    let sure = unique
+        && distance <= AUTO_ACCEPT_DISTANCE
+        && word.chars().count() >= MIN_AUTO_ACCEPT_LENGTH;
+    Some((best, sure))
+}
+
+impl World {
+    /// Fixes a command whose verb is one letter off a known one, such as
+    /// "atack troll". Returns None if there is nothing certain to fix.
+    pub(crate) fn correct_verb(&self, command: &Command) -> Option<Command> {
+        let Command::Unknown(input) = command else {
+            return None;
+        };
+        let (verb, rest) = input.split_once(' ').unwrap_or((input, ""));
+        match self.guess_verb(verb)? {
+            (verb, true) => Some(parse(format!("{} {}", verb, rest))),
+            (_, false) => None,
+        }
+    }
+
+    /// Suggests what a command the parser didn't know might have meant
+    pub(crate) fn suggest_verb(&self, input: &str) -> Option<String> {
+        let (verb, _) = input.split_once(' ').unwrap_or((input, ""));
+        let (verb, _) = self.guess_verb(verb)?;
+        Some(format!("Did you mean '{}'?\n", verb))
+    }
+
+    /// Guesses the object a name that matches nothing was meant to be
+    pub(crate) fn guess_object(&self, noun: &str) -> Option<(String, bool)> {
+        // Only what the player can see from where they are, so a typo gives
+        // nothing away about the rest of the world
+        let labels = (0..self.objects.len())
+            .filter(|index| {
+                !self.objects[*index].hidden
+                    && self.get_distance(Some(LOC_PLAYER), Some(*index)) <= Distance::OverThere
+            })
+            .flat_map(|index| self.objects[index].label.iter().map(String::as_str));
+        guess(noun, labels)
+    }
+
+    fn guess_verb(&self, verb: &str) -> Option<(String, bool)> {
+        let synonyms = self.synonyms.keys().chain(self.aliases.keys());
+        guess(verb, verbs().chain(synonyms.map(String::as_str)))
+    }
+}
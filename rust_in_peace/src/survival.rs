@@ -0,0 +1,127 @@
+//! Hunger and thirst, for players who want their journey to be a struggle to
+//! get through as well as a fight. With survival turned on the player's food
+//! and water run down as the turns go by, and running low weakens them until
+//! they eat or drink something. With it off, nobody ever gets hungry.
+use super::{Attribute, World};
+
+// How much food and water the player can have in them
+const MAX_METER: u64 = 100;
+
+// Turns it takes for the player to get one point hungrier. They get a point
+// thirstier every turn.
+const HUNGER_TURNS: u64 = 2;
+
+// At or below this much food or water left, the player is weakened
+const LOW_METER: u64 = 25;
+
+// What running low takes off the attribute it weakens, and running out entirely
+const LOW_PENALTY: i64 = 2;
+const EMPTY_PENALTY: i64 = 4;
+
+impl World {
+    /// Returns how much food the player has left in them
+    pub fn food(&self) -> u64 {
+        MAX_METER.saturating_sub(self.hunger)
+    }
+
+    /// Returns how much water the player has left in them
+    pub fn water(&self) -> u64 {
+        MAX_METER.saturating_sub(self.thirst)
+    }
+
+    /// Returns what hunger or thirst takes off an attribute's bonus. Hunger
+    /// saps the player's strength and thirst their agility.
+    pub(crate) fn survival_penalty(&self, attribute: Attribute) -> i64 {
+        let meter = match attribute {
+            Attribute::Strength => self.food(),
+            Attribute::Agility => self.water(),
+            Attribute::Intellect => return 0,
+        };
+        match meter {
+            _ if !self.survival => 0,
+            0 => EMPTY_PENALTY,
+            meter if meter <= LOW_METER => LOW_PENALTY,
+            _ => 0,
+        }
+    }
+
+    /// Makes the player hungrier and thirstier as a turn goes by, warning them
+    /// when they run low or out
+    pub(crate) fn run_survival(&mut self) -> String {
+        if !self.survival {
+            return String::new();
+        }
+        let (food, water) = (self.food(), self.water());
+        if self.turn.is_multiple_of(HUNGER_TURNS) {
+            self.hunger = (self.hunger + 1).min(MAX_METER);
+        }
+        self.thirst = (self.thirst + 1).min(MAX_METER);
+
+        let mut output = String::new();
+        if food > LOW_METER && self.food() <= LOW_METER {
+            output += "\nYou are getting hungry.\n";
+        } else if food > 0 && self.food() == 0 {
+            output += "\nYou are starving.\n";
+        }
+        if water > LOW_METER && self.water() <= LOW_METER {
+            output += "\nYou are getting thirsty.\n";
+        } else if water > 0 && self.water() == 0 {
+            output += "\nYou are parched.\n";
+        }
+        output
+    }
+
+    /// Returns true if eating or drinking the object would do the player some
+    /// good against hunger or thirst
+    pub(crate) fn relieves_survival(&self, object: usize) -> bool {
+        let object = &self.objects[object];
+        self.survival && (object.food > 0 && self.hunger > 0 || object.water > 0 && self.thirst > 0)
+    }
+
+    /// Feeds the player what the object holds in food and water
+    pub(crate) fn eat_and_drink(&mut self, object: usize) -> String {
+        if !self.relieves_survival(object) {
+            return String::new();
+        }
+        let (food, water) = (self.objects[object].food, self.objects[object].water);
+        let mut output = String::new();
+        if food > 0 && self.hunger > 0 {
+            self.hunger = self.hunger.saturating_sub(food);
+            output += "You feel less hungry.\n";
+        }
+        if water > 0 && self.thirst > 0 {
+            self.thirst = self.thirst.saturating_sub(water);
+            output += "You feel less thirsty.\n";
+        }
+        output
+    }
+
+    /// Describes how much food and water the player has left, for the
+    /// character sheet
+    pub(crate) fn describe_survival(&self) -> String {
+        match self.survival {
+            true => format!(
+                "food: {}/{}, water: {}/{}\n",
+                self.food(),
+                MAX_METER,
+                self.water(),
+                MAX_METER
+            ),
+            false => String::new(),
+        }
+    }
+
+    /// Player turns hunger and thirst on or off
+    pub fn do_survival(&mut self, noun: &str) -> String {
+        self.survival = match noun {
+            "on" => true,
+            "off" => false,
+            "" => !self.survival,
+            _ => return "Try <survival on> or <survival off>.\n".to_string(),
+        };
+        match self.survival {
+            true => "You will need to eat and drink to keep your strength up.\n".to_string(),
+            false => "You no longer need to eat or drink.\n".to_string(),
+        }
+    }
+}
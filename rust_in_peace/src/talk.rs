@@ -0,0 +1,91 @@
+//! Talking to the people and creatures of the world. Most only have a few words
+//! to share, said in turn each time they are talked to, but some enemies can
+//! be talked out of a fight, and talking to one that won't listen is enough to
+//! start it. Others hear the player out and can be persuaded or intimidated.
+use super::{World, LOC_PLAYER};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// What someone says when the player talks to them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Talk {
+    pub text: String,
+    /// More lines said one after another each time the player talks again,
+    /// starting over from the text once they run out
+    #[serde(default)]
+    pub lines: Vec<String>,
+    /// How many times the player has been talked to, to know which line comes next
+    #[serde(default)]
+    pub said: usize,
+    /// Chance in percent that an enemy is talked out of fighting the player
+    #[serde(default)]
+    pub calm_chance: u64,
+    /// What an enemy says once it has been talked out of fighting
+    #[serde(default)]
+    pub calmed_text: Option<String>,
+    /// What a persuasion roll has to reach to send an enemy away peacefully
+    #[serde(default)]
+    pub persuade: Option<i64>,
+    /// What an intimidation roll has to reach to scare an enemy off
+    #[serde(default)]
+    pub intimidate: Option<i64>,
+}
+
+impl World {
+    /// Player talks to someone they can see. An enemy may be talked out of
+    /// fighting, or attack the player if it isn't.
+    pub fn do_talk(&mut self, noun: &String) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+        if index == LOC_PLAYER {
+            return "You mutter to yourself.\n".to_string();
+        }
+        if self.objects[index].enemy && self.objects[index].health.unwrap_or(0) == 0 {
+            return format!("The {} is in no state to talk.\n", name);
+        }
+        let rewards = self.reward_quests(index);
+        if !self.objects[index].dialogue.is_empty() && !self.objects[index].enemy {
+            return rewards + &self.say_node(index, 0);
+        }
+        if !rewards.is_empty() {
+            return rewards;
+        }
+        let Some(talk) = self.objects[index].talk.clone() else {
+            return format!("The {} has nothing to say to you.\n", name);
+        };
+
+        let approaches = self.describe_approaches(index);
+        if self.objects[index].enemy && !approaches.is_empty() {
+            return format!("The {} says: \"{}\"\n", name, talk.text) + &approaches;
+        }
+        if !self.objects[index].enemy || talk.calm_chance == 0 {
+            let line = match talk.said % (talk.lines.len() + 1) {
+                0 => &talk.text,
+                line => &talk.lines[line - 1],
+            };
+            if let Some(talk) = self.objects[index].talk.as_mut() {
+                talk.said += 1;
+            }
+            return format!("The {} says: \"{}\"\n", name, line);
+        }
+        let calmed_text = talk.calmed_text.unwrap_or(talk.text.clone());
+        if self.objects[index].calmed {
+            return format!("The {} says: \"{}\"\n", name, calmed_text);
+        }
+
+        let output = format!("The {} says: \"{}\"\n", name, talk.text);
+        if self.rng().gen_range(1..=100) <= talk.calm_chance {
+            self.objects[index].calmed = true;
+            output
+                + &format!(
+                    "You talk the {} down. \"{}\"\nThe {} will leave you be.\n",
+                    name, calmed_text, name
+                )
+        } else {
+            output + &format!("The {} has heard enough!\n", name) + &self.start_combat(index)
+        }
+    }
+}
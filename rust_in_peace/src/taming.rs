@@ -0,0 +1,92 @@
+//! Taming wild animals instead of fighting them. An enemy that can be tamed
+//! has a favourite food, and once the player has fed it enough of it the
+//! animal stops being an enemy and joins them as a companion.
+use super::{AmbiguousOption, Companion, Distance, World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+
+/// How an animal can be won over with food
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Taming {
+    /// The item the animal eats
+    pub food: usize,
+    /// How many it has to be fed before it follows the player
+    pub needed: u64,
+    #[serde(default)]
+    pub fed: u64,
+    /// How the animal is described once it is tame
+    #[serde(default)]
+    pub tamed_description: Option<String>,
+}
+
+impl World {
+    /// Player feeds an animal something they carry, which tames it once it
+    /// has had enough. With no food named, the animal gets its favourite.
+    pub fn do_feed(&mut self, noun: &String, food: Option<&String>) -> String {
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+        if self.objects[index].location != self.objects[LOC_PLAYER].location {
+            return format!("The {} is too far away to feed.\n", name);
+        }
+        let Some(taming) = self.objects[index].taming.clone() else {
+            return format!("The {} won't eat out of your hand.\n", name);
+        };
+        if self.objects[index].health.unwrap_or(0) == 0 {
+            return format!("The {} is in no state to eat.\n", name);
+        }
+
+        // Stacks of the favourite food split off when picked up, so any of it will do
+        let favourite = self.objects[taming.food].label[0].clone();
+        let wanted = food.unwrap_or(&favourite);
+        let food = match self.object_index(wanted, Some(LOC_PLAYER), Distance::Held) {
+            AmbiguousOption::Some(food) => food,
+            _ if food.is_none() => {
+                return format!(
+                    "You have nothing the {} wants to eat. It is fond of the {}.\n",
+                    name, favourite
+                )
+            }
+            _ => return format!("You are not holding any {}.\n", wanted),
+        };
+        let food_name = self.objects[food].label[0].clone();
+        if food_name != favourite {
+            return format!("The {} sniffs the {} and turns away.\n", name, food_name);
+        }
+
+        self.use_up(food);
+        self.objects[index].asleep = false;
+        let fed = taming.fed + 1;
+        if fed < taming.needed {
+            if let Some(taming) = self.objects[index].taming.as_mut() {
+                taming.fed = fed;
+            }
+            return format!(
+                "The {} gulps down the {} and eyes you for more. ({}/{})\n",
+                name, food_name, fed, taming.needed
+            );
+        }
+
+        let object = &mut self.objects[index];
+        if let Some(description) = taming.tamed_description {
+            object.description = description;
+        }
+        object.taming = None;
+        object.enemy = false;
+        object.npc = true;
+        object.pursuing = false;
+        object.roams.clear();
+        object.threat.clear();
+        object.companion = Some(Companion {
+            joined: true,
+            home: object.location,
+            ..object.companion.clone().unwrap_or_default()
+        });
+        self.set_flag(format!("tamed:{}", name.to_lowercase()));
+        format!(
+            "The {} gulps down the {} and nuzzles your hand. It follows you now.\n",
+            name, food_name
+        )
+    }
+}
@@ -0,0 +1,149 @@
+//! Throwing things at enemies. Anything the player carries can be thrown to
+//! soften an enemy up before closing in, and it lands where the enemy stands
+//! so it can be picked up again afterwards.
+use super::{Command, DamageResult, DamageType, Outcome, World, LOC_PLAYER};
+
+// Damage a thrown item deals for every point of its weight, on top of its attack
+const DAMAGE_PER_WEIGHT: u64 = 1;
+
+impl World {
+    /// Returns the damage an item deals when it is thrown. Weapons hit for
+    /// their attack and heavy things hurt more, but anything does at least one point.
+    pub fn throw_damage(&self, index: usize) -> u64 {
+        let item = &self.objects[index];
+        (item.attack.unwrap_or(0) + item.weight * DAMAGE_PER_WEIGHT).max(1)
+    }
+
+    /// Finds the enemy the player throws at, or explains why they can't. In a
+    /// fight the enemy they are attacking is the one when they don't say.
+    fn throw_target(&self, target: Option<&String>) -> Result<usize, String> {
+        let Some(target) = target else {
+            return match &self.combat {
+                Some(combat) => Ok(combat.enemy),
+                None => Err("Throw it at what? Try <throw <item> at <enemy>>.\n".to_string()),
+            };
+        };
+        let (output, obj_opt) = self.object_visible(target);
+        let Some(index) = obj_opt else {
+            return Err(output);
+        };
+        let name = &self.objects[index].label[0];
+        if !self.objects[index].enemy {
+            return Err(format!(
+                "There is no point throwing things at the {}.\n",
+                name
+            ));
+        }
+        if self.objects[index].health.unwrap_or(0) == 0 {
+            return Err(format!("The {} is already down.\n", name));
+        }
+        if self.objects[index].location != self.objects[LOC_PLAYER].location {
+            return Err(format!("The {} is too far away to hit.\n", name));
+        }
+        if self.combat.is_some() && !self.fighting_enemies().contains(&index) {
+            return Err(format!("You are not fighting the {}.\n", name));
+        }
+        if self.combat.is_none() && self.fumble_in_dark() {
+            return Err(format!(
+                "It is too dark to see the {} well enough to hit it.\n",
+                name
+            ));
+        }
+        Ok(index)
+    }
+
+    /// Player throws something they carry at an enemy next to them. The item
+    /// lands where the enemy stands whether it hits or not. Outside a fight the
+    /// throw starts one, and in a fight it takes the player's turn.
+    pub fn do_throw(&mut self, item: &str, target: Option<&String>) -> String {
+        let enemy = match self.throw_target(target) {
+            Ok(enemy) => enemy,
+            Err(output) => return output,
+        };
+        let command = Command::Throw {
+            item: item.to_string(),
+            target: target.cloned(),
+        };
+        let (output, obj_opt) = self.get_possession(Some(LOC_PLAYER), command, &item.to_string());
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        if let Some(refusal) = self.keep_key_item(index) {
+            return refusal;
+        }
+
+        // Things that aren't weapons thump into their target
+        let kind = match self.objects[index].attack {
+            Some(_) => self.objects[index].damage_type(),
+            None => DamageType::Blunt,
+        };
+        let resisted = self.objects[enemy].resists.contains(&kind);
+        let vulnerable = self.objects[enemy].weak_to.contains(&kind);
+        let outcome = self.roll_attack(LOC_PLAYER, None, enemy);
+        let mut damage = outcome.damage(self.throw_damage(index));
+        if resisted {
+            damage /= 2;
+        }
+        if vulnerable {
+            damage = damage.saturating_mul(3) / 2;
+        }
+        let health = self.objects[enemy]
+            .health
+            .unwrap_or(0)
+            .saturating_sub(damage);
+        self.objects[enemy].health = Some(health);
+        self.log_blow(
+            self.objects[LOC_PLAYER].label[0].clone(),
+            outcome.verb(),
+            enemy,
+            damage,
+        );
+        let result = DamageResult {
+            outcome,
+            kind,
+            resisted,
+            vulnerable,
+            damage,
+            health,
+        };
+
+        let item_name = self.objects[index].label[0].clone();
+        let name = self.objects[enemy].label[0].clone();
+        if self.count(index) == 1 {
+            self.put_away(index);
+        }
+        self.move_some(index, 1, self.objects[LOC_PLAYER].location);
+
+        let mut output = if outcome == Outcome::Miss {
+            format!(
+                "You throw the {} at the {} but miss. It lands nearby.",
+                item_name, name
+            )
+        } else {
+            format!(
+                "{}You hit the {} with the {}.{}\nEnemy health: {}",
+                result.critical_text(),
+                name,
+                item_name,
+                result.effect_text(),
+                health
+            )
+        };
+        if health > 0 {
+            self.add_threat(enemy, LOC_PLAYER, damage);
+            return match self.combat {
+                Some(_) => output + &self.enemies_turn(),
+                None => output + "\n" + &self.start_combat(enemy),
+            };
+        }
+
+        self.objects[enemy].knocked_out =
+            kind == DamageType::Blunt && self.objects[enemy].capturable;
+        self.record_defeat(enemy);
+        output += &self.defeat(enemy);
+        match self.combat {
+            Some(_) => output + &self.next_enemy(),
+            None => output,
+        }
+    }
+}
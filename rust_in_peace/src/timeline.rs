@@ -0,0 +1,65 @@
+//! A record of what happened in the world and when, so that a quest or
+//! reaction that misfired can be traced back. Viewing it is a debug feature.
+use super::World;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+// Entries older than this many are forgotten
+const TIMELINE_LIMIT: usize = 100;
+
+/// Something that happened in the world, stamped with the turn it happened on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    pub turn: u64,
+    pub text: String,
+}
+
+/// Lists the last few of a record's entries, one to a line: as many as the
+/// noun asks for, or ten
+pub(crate) fn last_entries<T: Display>(entries: &[T], noun: &str) -> String {
+    let count = noun.trim().parse::<usize>().unwrap_or(10);
+    let start = entries.len().saturating_sub(count);
+    entries[start..]
+        .iter()
+        .map(|entry| format!("{}\n", entry))
+        .collect()
+}
+
+impl World {
+    /// Writes something that happened into the timeline
+    pub fn record(&mut self, text: String) {
+        self.timeline.push(TimelineEntry {
+            turn: self.turn,
+            text,
+        });
+        if self.timeline.len() > TIMELINE_LIMIT {
+            self.timeline.remove(0);
+        }
+    }
+
+    /// Sets a flag, noting it in the timeline the first time
+    pub fn set_flag(&mut self, flag: String) {
+        if !self.flags.contains(&flag) {
+            self.record(format!("flag set: {}", flag));
+            self.flags.insert(flag);
+        }
+    }
+
+    /// Shows the last few timeline entries, or writes them all to a file
+    #[cfg(feature = "debug")]
+    pub fn do_timeline(&self, noun: &str) -> String {
+        let lines: Vec<String> = self
+            .timeline
+            .iter()
+            .map(|entry| format!("[turn {}] {}", entry.turn, entry.text))
+            .collect();
+
+        if let Some(path) = noun.strip_prefix("dump ") {
+            return match std::fs::write(path.trim(), lines.join("\n") + "\n") {
+                Ok(()) => format!("Wrote {} entries to {}.\n", lines.len(), path.trim()),
+                Err(error) => format!("Could not write {}: {}\n", path.trim(), error),
+            };
+        }
+        last_entries(&lines, noun)
+    }
+}
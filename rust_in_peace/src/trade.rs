@@ -0,0 +1,167 @@
+//! Spending gold on things that are for sale, and selling things to traders.
+//! A trader keeps its stock on itself, pays for what the player brings out of
+//! its own purse and puts it up for sale again.
+use super::{split_count, AmbiguousOption, Command, Distance, World, LOC_PLAYER};
+
+// Share of an item's value in percent a trader pays for it
+const SELL_SHARE: u64 = 50;
+
+impl World {
+    /// Finds a trader at the player's location
+    pub(crate) fn trader_here(&self) -> Option<usize> {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        (0..self.objects.len()).find(|index| {
+            self.objects[*index].trader && self.objects[*index].location == player_loc
+        })
+    }
+
+    /// Lists what a trader has for sale and what it costs
+    pub(crate) fn list_stock(&self, trader: usize) -> String {
+        let stock: String = (0..self.objects.len())
+            .filter(|index| self.is_containing(Some(trader), Some(*index)))
+            .filter_map(|index| {
+                let price = self.trade_price(trader, self.objects[index].price?);
+                Some(format!("    {} ({} gold)\n", self.stack_name(index), price))
+            })
+            .collect();
+        match stock.is_empty() {
+            true => format!(
+                "The {} has nothing left to sell.\n",
+                self.objects[trader].label[0]
+            ),
+            false => format!("The {} sells:\n", self.objects[trader].label[0]) + &stock,
+        }
+    }
+
+    /// Player buys something for sale at their location, or from a trader here
+    pub fn do_buy(&mut self, noun: &String) -> String {
+        if let Some(trader) = self.trader_here() {
+            match self.object_index(noun, Some(trader), Distance::Held) {
+                AmbiguousOption::Some(index) => return self.buy_from(trader, index),
+                AmbiguousOption::Ambiguous => {
+                    return self.offer_choice(noun, Some(trader), Distance::Held)
+                }
+                AmbiguousOption::None => {}
+            }
+        }
+        let (output, obj_opt) = self.object_visible(noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        let name = self.objects[index].label[0].clone();
+
+        match self.objects[index].price {
+            None => format!("The {} is not for sale.\n", name),
+            Some(_) if self.get_distance(Some(LOC_PLAYER), Some(index)) != Distance::Here => {
+                format!("You don't see any '{}' here.\n", noun)
+            }
+            Some(price) if self.objects[LOC_PLAYER].gold < price => format!(
+                "You can't afford the {}. It costs {} gold and you have {}.\n",
+                name, price, self.objects[LOC_PLAYER].gold
+            ),
+            Some(_) if self.objects[index].item && self.too_heavy(index, 1).is_some() => {
+                format!("You would have nowhere to put the {}. ", name)
+                    + &self.too_heavy(index, 1).unwrap_or_default()
+            }
+            Some(price) => {
+                self.objects[LOC_PLAYER].gold -= price;
+                self.objects[index].price = None;
+                self.objects[index].value.get_or_insert(price);
+                if self.objects[index].item {
+                    self.objects[index].location = Some(LOC_PLAYER);
+                }
+                format!(
+                    "You buy the {} for {} gold. You have {} gold left.\n",
+                    name, price, self.objects[LOC_PLAYER].gold
+                ) + &self.load_warning()
+            }
+        }
+    }
+
+    /// Player buys one of something a trader has in stock, paying the trader
+    fn buy_from(&mut self, trader: usize, index: usize) -> String {
+        let name = self.objects[index].label[0].clone();
+        let Some(listed) = self.objects[index].price else {
+            return format!(
+                "The {} won't part with the {}.\n",
+                self.objects[trader].label[0], name
+            );
+        };
+        let price = self.trade_price(trader, listed);
+        if self.objects[LOC_PLAYER].gold < price {
+            return format!(
+                "You can't afford the {}. It costs {} gold and you have {}.\n",
+                name, price, self.objects[LOC_PLAYER].gold
+            );
+        }
+        if let Some(refusal) = self.too_heavy(index, 1) {
+            return format!("You would have nowhere to put the {}. ", name) + &refusal;
+        }
+
+        self.objects[LOC_PLAYER].gold -= price;
+        self.objects[trader].gold += price;
+        self.objects[index].value.get_or_insert(listed);
+        // The one bought is no longer for sale, but the rest of a stack still is
+        self.objects[index].price = None;
+        self.move_some(index, 1, Some(LOC_PLAYER));
+        if self.objects[index].location == Some(trader) {
+            self.objects[index].price = Some(listed);
+        }
+        format!(
+            "You buy the {} from the {} for {} gold. You have {} gold left.\n",
+            name, self.objects[trader].label[0], price, self.objects[LOC_PLAYER].gold
+        ) + &self.load_warning()
+    }
+
+    /// Player sells something they carry to a trader at their location, for a
+    /// share of what it is worth
+    pub fn do_sell(&mut self, noun: &str) -> String {
+        let (wanted, item) = split_count(noun);
+        let Some(trader) = self.trader_here() else {
+            return format!("There is nobody here to buy the {}.\n", item);
+        };
+        let (output, obj_opt) =
+            self.get_possession(Some(LOC_PLAYER), Command::Sell(noun.to_string()), &item);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+        if let Some(refusal) = self.keep_key_item(index) {
+            return refusal;
+        }
+        let name = self.objects[index].label[0].clone();
+        let trader_name = self.objects[trader].label[0].clone();
+        let Some(value) = self.objects[index].value.or(self.objects[index].price) else {
+            return format!("The {} has no use for the {}.\n", trader_name, name);
+        };
+        if (0..self.objects.len()).any(|other| self.objects[other].location == Some(index)) {
+            return format!("Empty the {} before you sell it.\n", name);
+        }
+        let count = match self.count_wanted(index, wanted) {
+            Ok(count) => count,
+            Err(refusal) => return refusal,
+        };
+        let paid = self.trade_offer(trader, value * SELL_SHARE / 100 * count);
+        if self.objects[trader].gold < paid {
+            return format!(
+                "The {} can't afford the {}. It offers {} gold but only has {}.\n",
+                trader_name, name, paid, self.objects[trader].gold
+            );
+        }
+
+        self.objects[trader].gold -= paid;
+        self.objects[LOC_PLAYER].gold += paid;
+        if count == self.count(index) {
+            self.put_away(index);
+        }
+        // What is sold goes up for sale again at its full value
+        self.objects[index].price = Some(value);
+        self.move_some(index, count, Some(trader));
+        if self.objects[index].location == Some(LOC_PLAYER) {
+            self.objects[index].price = None;
+        }
+        format!(
+            "You sell the {} to the {} for {} gold. You have {} gold now.\n",
+            name, trader_name, paid, self.objects[LOC_PLAYER].gold
+        )
+    }
+}
@@ -0,0 +1,121 @@
+//! Trainers who teach the player skills for gold, each rank costing more than
+//! the last and taking some time to learn.
+use super::{World, LOC_PLAYER};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// The highest rank a skill can be trained to
+const MAX_RANK: u64 = 3;
+
+/// Something the player can get better at
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Skill {
+    /// Adds to the damage the player deals
+    Swordsmanship,
+    /// Takes off the damage the player receives
+    Toughness,
+    /// Helps the player sneak and avoid ambushes
+    Stealth,
+    /// Helps the player notice hidden things
+    Perception,
+}
+
+impl Skill {
+    const ALL: [Skill; 4] = [
+        Skill::Swordsmanship,
+        Skill::Toughness,
+        Skill::Stealth,
+        Skill::Perception,
+    ];
+
+    /// Finds a skill by name
+    pub fn named(name: &str) -> Option<Skill> {
+        Skill::ALL
+            .into_iter()
+            .find(|skill| skill.to_string() == name.to_lowercase())
+    }
+}
+
+impl fmt::Display for Skill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// A skill a trainer teaches, with the cost of its first rank
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lesson {
+    pub skill: Skill,
+    pub cost: u64,
+    /// Turns each rank takes to learn
+    pub turns: u64,
+}
+
+impl World {
+    /// Returns the player's rank in a skill
+    pub fn skill(&self, skill: Skill) -> u64 {
+        self.skills.get(&skill).copied().unwrap_or(0)
+    }
+
+    /// Returns what the next rank of a lesson costs
+    pub fn lesson_cost(&self, lesson: &Lesson) -> u64 {
+        lesson.cost * (self.skill(lesson.skill) + 1)
+    }
+
+    /// Player pays a trainer where they stand to learn the next rank of a skill
+    pub fn do_train(&mut self, noun: &str) -> String {
+        let player_loc = self.objects[LOC_PLAYER].location;
+        let trainer = (0..self.objects.len()).find(|index| {
+            !self.objects[*index].teaches.is_empty() && self.objects[*index].location == player_loc
+        });
+        let Some(trainer) = trainer else {
+            return "There is nobody here to train you.\n".to_string();
+        };
+        let name = self.objects[trainer].label[0].clone();
+        let lesson = Skill::named(noun).and_then(|skill| {
+            self.objects[trainer]
+                .teaches
+                .iter()
+                .find(|lesson| lesson.skill == skill)
+                .cloned()
+        });
+
+        let Some(lesson) = lesson else {
+            let offers = self.objects[trainer]
+                .teaches
+                .iter()
+                .map(|lesson| format!("{} ({} gold)", lesson.skill, self.lesson_cost(lesson)))
+                .collect::<Vec<String>>()
+                .join(", ");
+            return format!("The {} can teach you: {}.\n", name, offers);
+        };
+        let rank = self.skill(lesson.skill);
+        let cost = self.lesson_cost(&lesson);
+
+        if rank >= MAX_RANK {
+            format!(
+                "The {} has nothing more to teach you about {}.\n",
+                name, lesson.skill
+            )
+        } else if self.objects[LOC_PLAYER].gold < cost {
+            format!(
+                "The next lesson in {} costs {} gold and you have {}.\n",
+                lesson.skill, cost, self.objects[LOC_PLAYER].gold
+            )
+        } else {
+            self.objects[LOC_PLAYER].gold -= cost;
+            self.skills.insert(lesson.skill, rank + 1);
+            let mut output = format!(
+                "You spend {} turns training with the {}. Your {} is now rank {}.\n",
+                lesson.turns,
+                name,
+                lesson.skill,
+                rank + 1
+            );
+            for _ in 1..lesson.turns {
+                output += &self.tick();
+            }
+            output
+        }
+    }
+}
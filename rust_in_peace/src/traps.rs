@@ -0,0 +1,68 @@
+//! Snares and pits the player crafts and leaves for roaming enemies, which
+//! pay off at the start of the next fight with whatever got caught.
+use super::{Command, World, LOC_PLAYER};
+
+impl World {
+    /// Player sets a trap they carry where they are standing
+    pub fn do_place(&mut self, noun: &String) -> String {
+        let (output, obj_opt) =
+            self.get_possession(Some(LOC_PLAYER), Command::Place(noun.clone()), noun);
+        let Some(index) = obj_opt else {
+            return output;
+        };
+
+        if self.objects[index].trap.is_none() {
+            format!("The {} is not a trap.\n", self.objects[index].label[0])
+        } else {
+            // A set trap is staked down, so it can't be picked straight back up
+            self.objects[index].location = self.objects[LOC_PLAYER].location;
+            self.objects[index].item = false;
+            format!(
+                "You set the {} here. Anything that wanders in will get a nasty surprise.\n",
+                self.objects[index].label[0]
+            )
+        }
+    }
+
+    /// Catches a roaming enemy in a trap set where it just arrived
+    pub fn spring_traps(&mut self, enemy: usize) -> String {
+        let location = self.objects[enemy].location;
+        let trap = (0..self.objects.len()).find(|index| {
+            self.objects[*index].trap.is_some() && self.objects[*index].location == location
+        });
+        let Some(trap) = trap else {
+            return String::new();
+        };
+
+        self.objects[trap].location = None;
+        self.objects[enemy].snared_by = Some(trap);
+        if location == self.objects[LOC_PLAYER].location {
+            format!(
+                "\nThe {} steps right into your {}!\n",
+                self.objects[enemy].label[0], self.objects[trap].label[0]
+            )
+        } else {
+            format!(
+                "\nYou hear something caught in your {} in the {}.\n",
+                self.objects[trap].label[0],
+                self.objects[location.unwrap_or_default()].label[0]
+            )
+        }
+    }
+
+    /// Deals the damage of the trap an enemy is caught in as the fight starts
+    pub fn trap_damage(&mut self, enemy: usize) -> String {
+        let Some(trap) = self.objects[enemy].snared_by.take() else {
+            return String::new();
+        };
+        let damage = self.objects[trap].trap.unwrap_or(0);
+        let health = self.objects[enemy].health.unwrap_or(0);
+
+        // A trap softens an enemy up but never finishes it off
+        self.objects[enemy].health = Some(health.saturating_sub(damage).max(1));
+        format!(
+            "\nThe {} is still caught in your {} and takes {} damage.\n",
+            self.objects[enemy].label[0], self.objects[trap].label[0], damage
+        )
+    }
+}
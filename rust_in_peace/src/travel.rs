@@ -0,0 +1,214 @@
+//! Getting around more than one passage at a time: routes through the passage
+//! graph and fast travel back to places the player has already been.
+use super::{World, LOC_PLAYER};
+use std::collections::{BTreeMap, VecDeque};
+
+impl World {
+    /// Returns true if the player could go through the passage right now,
+    /// without having to climb
+    pub fn passage_open(&self, passage: usize) -> bool {
+        let object = &self.objects[passage];
+        self.passage_refusal(passage).is_none()
+            && object.barricaded == 0
+            && object.climb.is_none()
+            && object
+                .locked_by
+                .is_none_or(|key| self.is_containing(Some(LOC_PLAYER), Some(key)))
+            && object
+                .destination
+                .is_some_and(|to| self.mount_refusal(to).is_none())
+    }
+
+    /// Finds the shortest chain of open passages between two locations, only
+    /// passing through locations the filter allows
+    pub fn route(
+        &self,
+        from: usize,
+        to: usize,
+        allowed: impl Fn(usize) -> bool,
+    ) -> Option<Vec<usize>> {
+        let mut came_by: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(location) = queue.pop_front() {
+            if location == to {
+                let mut passages = Vec::new();
+                let mut at = to;
+                while at != from {
+                    let passage = came_by[&at];
+                    passages.push(passage);
+                    at = self.objects[passage].location.unwrap_or(from);
+                }
+                passages.reverse();
+                return Some(passages);
+            }
+            for passage in self.exits(location) {
+                let Some(next) = self.objects[passage].destination else {
+                    continue;
+                };
+                if next == from || came_by.contains_key(&next) || !allowed(next) {
+                    continue;
+                }
+                if self.passage_open(passage) {
+                    came_by.insert(next, passage);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Finds a location by name
+    pub fn location_named(&self, noun: &str) -> Option<usize> {
+        self.locations().into_iter().find(|location| {
+            self.objects[*location]
+                .label
+                .iter()
+                .any(|label| label.to_lowercase() == noun.to_lowercase())
+        })
+    }
+
+    /// Living enemies waiting at the locations a route passes through
+    fn enemies_along(&self, route: &[usize]) -> Vec<usize> {
+        let stops: Vec<Option<usize>> = route
+            .iter()
+            .map(|passage| self.objects[*passage].destination)
+            .collect();
+        (0..self.objects.len())
+            .filter(|index| {
+                let object = &self.objects[*index];
+                object.enemy
+                    && object.health.unwrap_or(0) > 0
+                    && !object.captured
+                    && stops.contains(&object.location)
+            })
+            .collect()
+    }
+
+    /// Player travels straight to a location they have been to before,
+    /// asking first if there are enemies on the way
+    pub fn do_travel(&mut self, noun: &String) -> String {
+        let Some(to) = self.location_named(noun) else {
+            return format!("There is no place called '{}'.\n", noun);
+        };
+        let name = self.objects[to].label[0].clone();
+        let from = self.objects[LOC_PLAYER].location.unwrap_or_default();
+
+        if from == to {
+            return format!("You are already in the {}.\n", name);
+        }
+        if !self.visited.contains(&to) {
+            return format!("You haven't been to the {} yet.\n", name);
+        }
+        let Some(route) = self.route(from, to, |location| self.visited.contains(&location)) else {
+            return format!("You can't find a way to the {} from here.\n", name);
+        };
+
+        let enemies = self.enemies_along(&route);
+        if enemies.is_empty() {
+            return self.follow_route(route, false);
+        }
+        let dangers = enemies
+            .iter()
+            .map(|enemy| {
+                format!(
+                    "the {} in the {}",
+                    self.objects[*enemy].label[0],
+                    self.objects[self.objects[*enemy].location.unwrap_or_default()].label[0]
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        self.confirm_travel = Some(to);
+        format!(
+            "There is danger on the way to the {}: {}. Travel anyway? (yes/no)\n",
+            name, dangers
+        )
+    }
+
+    /// Player answers whether to carry on with a dangerous journey
+    pub fn do_confirm(&mut self, travel: Option<usize>, answer: bool) -> String {
+        match travel {
+            None => "There is nothing to answer.\n".to_string(),
+            Some(_) if !answer => "You stay where you are.\n".to_string(),
+            Some(to) => {
+                let from = self.objects[LOC_PLAYER].location.unwrap_or_default();
+                match self.route(from, to, |location| self.visited.contains(&location)) {
+                    Some(route) => self.follow_route(route, false),
+                    None => format!(
+                        "You can't find a way to the {} from here.\n",
+                        self.objects[to].label[0]
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Player goes back to the location they came from before this one
+    pub fn do_back(&mut self) -> String {
+        let Some(previous) = self.history.pop() else {
+            return "You haven't been anywhere else yet.\n".to_string();
+        };
+        let from = self.objects[LOC_PLAYER].location.unwrap_or_default();
+        let passage = self
+            .exits(from)
+            .into_iter()
+            .find(|passage| self.objects[*passage].destination == Some(previous));
+
+        match passage {
+            Some(passage) => {
+                let output = self.go_through(passage);
+                // Going back retraces the player's steps instead of adding to them
+                if self.objects[LOC_PLAYER].location == Some(previous) {
+                    self.history.pop();
+                } else {
+                    self.history.push(previous);
+                }
+                output
+            }
+            None => {
+                self.history.push(previous);
+                "You can't go back the way you came.\n".to_string()
+            }
+        }
+    }
+
+    /// Player walks to a location further away than the next passage
+    pub(crate) fn walk_to(&mut self, noun: &str) -> Option<String> {
+        let to = self.location_named(noun)?;
+        let from = self.objects[LOC_PLAYER].location?;
+        Some(match self.route(from, to, |_| true) {
+            Some(route) => self.follow_route(route, true),
+            None => format!(
+                "You can't find a way to the {} from here.\n",
+                self.objects[to].label[0]
+            ),
+        })
+    }
+
+    /// Walks the player along a route, letting time pass at each location on the
+    /// way and only describing where they end up. Walking stops early at the
+    /// first location with an enemy in it if asked to.
+    fn follow_route(&mut self, route: Vec<usize>, stop_at_enemies: bool) -> String {
+        let mut output = String::new();
+        let Some((last, rest)) = route.split_last() else {
+            return output;
+        };
+
+        for passage in rest {
+            let to = self.objects[*passage].destination.unwrap_or_default();
+            if let Some(refusal) = self.travel_refusal() {
+                return output + &refusal;
+            }
+            if stop_at_enemies && !self.enemies_along(&[*passage]).is_empty() {
+                return output + &self.move_player(to) + "You stop, the way on isn't safe.\n";
+            }
+            self.step(to);
+            output += &format!("You pass through the {}.\n", self.objects[to].label[0]);
+            for _ in 0..self.travel_turns() {
+                output += &self.tick();
+            }
+        }
+        output + &self.move_player(self.objects[*last].destination.unwrap_or_default())
+    }
+}
@@ -0,0 +1,469 @@
+//! Checks run on a world after it has been deserialized.
+//! Problems are collected as diagnostics so a bad world file is reported up front
+//! instead of panicking on a bad index in the middle of a game.
+use super::{parse, Change, Command, Condition, World, LOC_PLAYER};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+
+/// How serious a validation problem is
+#[derive(PartialOrd, Ord, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A single problem found in the world data
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn error(message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+        }
+    }
+
+    pub(crate) fn warning(message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+impl World {
+    /// Runs every check on the world and returns the problems found
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let count = self.objects.len();
+
+        for (index, object) in self.objects.iter().enumerate() {
+            if object.label.is_empty() || object.label[0].is_empty() {
+                diagnostics.push(Diagnostic::error(format!("object {} has no name", index)));
+            }
+        }
+        // Every later check prints names, so stop here if some are missing
+        if !diagnostics.is_empty() {
+            return diagnostics;
+        }
+
+        match self.objects.get(LOC_PLAYER) {
+            Some(player) if player.label[0] == "Player" => {
+                if player.location.map(|loc| loc >= count).unwrap_or(true) {
+                    diagnostics.push(Diagnostic::error(
+                        "the player does not start in a valid location".to_string(),
+                    ));
+                }
+            }
+            _ => diagnostics.push(Diagnostic::error(format!(
+                "object {} must be the player",
+                LOC_PLAYER
+            ))),
+        }
+
+        for (index, object) in self.objects.iter().enumerate() {
+            if let Some(destination) = object.destination {
+                if destination >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "passage '{}' ({}) leads to nonexistent location {}",
+                        object.label[0], index, destination
+                    )));
+                } else if self.objects[destination].item || self.objects[destination].enemy {
+                    diagnostics.push(Diagnostic::warning(format!(
+                        "passage '{}' ({}) leads to '{}', which is not a location",
+                        object.label[0], index, self.objects[destination].label[0]
+                    )));
+                }
+            }
+            if let Some(location) = object.location {
+                if location >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) is placed in nonexistent location {}",
+                        object.label[0], index, location
+                    )));
+                }
+            }
+            if let Some(key) = object.locked_by {
+                if key >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "passage '{}' ({}) is locked by nonexistent key {}",
+                        object.label[0], index, key
+                    )));
+                } else if !self.objects[key].item {
+                    diagnostics.push(Diagnostic::warning(format!(
+                        "passage '{}' ({}) is locked by '{}', which can't be picked up",
+                        object.label[0], index, self.objects[key].label[0]
+                    )));
+                }
+            }
+            if let Some(Condition::EnemyDead(target) | Condition::ItemHeld(target)) =
+                &object.requires
+            {
+                if *target >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "passage '{}' ({}) requires nonexistent object {}",
+                        object.label[0], index, target
+                    )));
+                }
+            }
+            if let Some(food) = object.taming.as_ref().map(|taming| taming.food) {
+                if food >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) is tamed with nonexistent food {}",
+                        object.label[0], index, food
+                    )));
+                }
+            }
+            for stop in object.schedule.iter().filter(|stop| stop.location >= count) {
+                diagnostics.push(Diagnostic::error(format!(
+                    "'{}' ({}) is scheduled into nonexistent location {}",
+                    object.label[0], index, stop.location
+                )));
+            }
+            for stock in &object.restock {
+                if !self.objects.get(stock.item).is_some_and(|item| item.item) {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) restocks {}, which is not an item",
+                        object.label[0], index, stock.item
+                    )));
+                }
+            }
+            for material in object.tiers.iter().flat_map(|tier| &tier.needs) {
+                if !self
+                    .objects
+                    .get(material.item)
+                    .is_some_and(|item| item.item)
+                {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) is forged with {}, which is not an item",
+                        object.label[0], index, material.item
+                    )));
+                }
+            }
+            for location in object.roams.iter().filter(|location| **location >= count) {
+                diagnostics.push(Diagnostic::error(format!(
+                    "'{}' ({}) roams into nonexistent location {}",
+                    object.label[0], index, location
+                )));
+            }
+            if let Some(home) = object
+                .companion
+                .as_ref()
+                .and_then(|companion| companion.home)
+                .filter(|home| *home >= count)
+            {
+                diagnostics.push(Diagnostic::error(format!(
+                    "companion '{}' ({}) goes home to nonexistent location {}",
+                    object.label[0], index, home
+                )));
+            }
+            for phase in object.phases.iter() {
+                for ally in phase.summon.iter().filter(|ally| **ally >= count) {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) summons nonexistent enemy {}",
+                        object.label[0], index, ally
+                    )));
+                }
+                if let Some(to) = phase.retreat.filter(|to| *to >= count) {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) retreats to nonexistent location {}",
+                        object.label[0], index, to
+                    )));
+                }
+            }
+            for item in object.loot.iter().filter_map(|drop| drop.item) {
+                if item >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) drops nonexistent item {}",
+                        object.label[0], index, item
+                    )));
+                } else if self.objects[item].location.is_some() {
+                    diagnostics.push(Diagnostic::warning(format!(
+                        "'{}' ({}) drops '{}', which is already placed in the world",
+                        object.label[0], index, self.objects[item].label[0]
+                    )));
+                }
+            }
+            if object.enemy && object.health.is_none() {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "enemy '{}' ({}) has no health",
+                    object.label[0], index
+                )));
+            }
+            if let Some(roll) = object.stat_roll.filter(|roll| roll.min > roll.max) {
+                diagnostics.push(Diagnostic::error(format!(
+                    "'{}' ({}) rolls its stats between {} and {}, which is no range",
+                    object.label[0], index, roll.min, roll.max
+                )));
+            }
+            if object.npc && object.enemy {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "'{}' ({}) is both an npc and an enemy, so it fights like any other enemy",
+                    object.label[0], index
+                )));
+            }
+            if let Some(durability) = object
+                .durability
+                .filter(|durability| durability.current > durability.max)
+            {
+                diagnostics.push(Diagnostic::error(format!(
+                    "'{}' ({}) has {} durability but can only have {}",
+                    object.label[0], index, durability.current, durability.max
+                )));
+            }
+            if object.armor.is_some() && !object.item {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "armor '{}' ({}) is not an item and can never be worn",
+                    object.label[0], index
+                )));
+            }
+            let responses = object.dialogue.iter().flat_map(|node| &node.responses);
+            for response in responses {
+                if let Some(next) = response.next.filter(|next| *next >= object.dialogue.len()) {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "'{}' ({}) answers \"{}\" with nonexistent dialogue node {}",
+                        object.label[0], index, response.text, next
+                    )));
+                }
+                if let Some(Condition::EnemyDead(target) | Condition::ItemHeld(target)) =
+                    &response.requires
+                {
+                    if *target >= count {
+                        diagnostics.push(Diagnostic::error(format!(
+                            "'{}' ({}) answer \"{}\" requires nonexistent object {}",
+                            object.label[0], index, response.text, target
+                        )));
+                    }
+                }
+            }
+        }
+
+        let changes =
+            self.reactions
+                .iter()
+                .enumerate()
+                .flat_map(|(index, reaction)| {
+                    reaction.changes.iter().map(move |c| ("reaction", index, c))
+                })
+                .chain(self.events.iter().enumerate().flat_map(|(index, event)| {
+                    event.changes.iter().map(move |c| ("event", index, c))
+                }))
+                .chain(self.objects.iter().enumerate().flat_map(|(index, object)| {
+                    object
+                        .dialogue
+                        .iter()
+                        .flat_map(|node| &node.responses)
+                        .flat_map(|response| &response.changes)
+                        .map(move |c| ("dialogue of object", index, c))
+                }))
+                .chain(
+                    self.chapters
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(index, chapter)| {
+                            chapter.changes.iter().map(move |c| ("chapter", index, c))
+                        }),
+                );
+        for (kind, index, change) in changes {
+            let target = match change {
+                Change::StartQuest(quest) if *quest >= self.quests.len() => {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "{} {} starts nonexistent quest {}",
+                        kind, index, quest
+                    )));
+                    continue;
+                }
+                Change::Move { object, .. }
+                | Change::Describe { object, .. }
+                | Change::Summon(object) => *object,
+                Change::Flag(_)
+                | Change::Gold(_)
+                | Change::Health(_)
+                | Change::Journal(_)
+                | Change::StartQuest(_)
+                | Change::Variable { .. } => continue,
+            };
+            if target >= count {
+                diagnostics.push(Diagnostic::error(format!(
+                    "{} {} changes nonexistent object {}",
+                    kind, index, target
+                )));
+            }
+        }
+
+        for (index, recipe) in self.recipes.iter().enumerate() {
+            for object in std::iter::once(&recipe.makes).chain(&recipe.needs) {
+                if *object >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "recipe {} uses nonexistent object {}",
+                        index, object
+                    )));
+                }
+            }
+        }
+
+        for (index, brew) in self.brews.iter().enumerate() {
+            for object in std::iter::once(&brew.makes).chain(&brew.needs) {
+                if *object >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "brew {} uses nonexistent object {}",
+                        index, object
+                    )));
+                }
+            }
+        }
+
+        for (index, quest) in self.quests.iter().enumerate() {
+            let objective = match &quest.objective {
+                Condition::EnemyDead(target) | Condition::ItemHeld(target) => Some(*target),
+                _ => None,
+            };
+            for object in std::iter::once(&quest.giver)
+                .chain(&quest.items)
+                .chain(objective.as_ref())
+            {
+                if *object >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "quest {} uses nonexistent object {}",
+                        index, object
+                    )));
+                }
+            }
+            for item in quest
+                .items
+                .iter()
+                .filter(|item| **item < count && self.objects[**item].location.is_some())
+            {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "quest {} rewards '{}', which is already placed in the world",
+                    index, self.objects[*item].label[0]
+                )));
+            }
+        }
+
+        for (index, chapter) in self.chapters.iter().enumerate() {
+            if let Condition::EnemyDead(target) | Condition::ItemHeld(target) = &chapter.complete {
+                if *target >= count {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "chapter {} is completed by nonexistent object {}",
+                        index, target
+                    )));
+                }
+            }
+        }
+        for class in &self.classes {
+            if class.item.is_some_and(|item| item >= count) {
+                diagnostics.push(Diagnostic::error(format!(
+                    "class '{}' starts with nonexistent object {}",
+                    class.name,
+                    class.item.unwrap_or(0)
+                )));
+            }
+        }
+        for encounter in &self.encounters {
+            if !self
+                .objects
+                .get(*encounter)
+                .is_some_and(|object| object.enemy)
+            {
+                diagnostics.push(Diagnostic::error(format!(
+                    "encounter {} is not an enemy",
+                    encounter
+                )));
+            }
+        }
+        if let Some(class) = self.objects.get(LOC_PLAYER).and_then(|player| player.class) {
+            if class >= self.classes.len() {
+                diagnostics.push(Diagnostic::error(format!(
+                    "the player is of class {}, but there are only {}",
+                    class,
+                    self.classes.len()
+                )));
+            }
+        }
+        if self.chapter > self.chapters.len() {
+            diagnostics.push(Diagnostic::error(format!(
+                "the story is at chapter {}, but there are only {}",
+                self.chapter,
+                self.chapters.len()
+            )));
+        }
+
+        for (phrase, verb) in self.synonyms.iter() {
+            if matches!(parse(verb.clone()), Command::Unknown(_)) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "synonym '{}' stands for '{}', which is not a command",
+                    phrase, verb
+                )));
+            }
+        }
+
+        let reachable = self.reachable_locations();
+        for location in self.locations() {
+            if !reachable.contains(&location) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "location '{}' ({}) cannot be reached from the start",
+                    self.objects[location].label[0], location
+                )));
+            }
+        }
+
+        let mut names: HashMap<String, usize> = HashMap::new();
+        for (index, object) in self.objects.iter().enumerate() {
+            if object.destination.is_some()
+                || !(object.item || object.enemy || object.location.is_none())
+            {
+                continue;
+            }
+            let name = object.label[0].to_lowercase();
+            if let Some(first) = names.get(&name) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "'{}' is used by both object {} and object {}",
+                    object.label[0], first, index
+                )));
+            } else {
+                names.insert(name, index);
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Walks the passages from the player's starting location
+    pub(crate) fn reachable_locations(&self) -> BTreeSet<usize> {
+        let mut reachable = BTreeSet::new();
+        let start = self
+            .objects
+            .get(LOC_PLAYER)
+            .and_then(|player| player.location)
+            .filter(|location| *location < self.objects.len());
+        let mut queue: VecDeque<usize> = start.into_iter().collect();
+
+        while let Some(location) = queue.pop_front() {
+            if !reachable.insert(location) {
+                continue;
+            }
+            for passage in self.exits(location) {
+                if let Some(to) = self.objects[passage].destination {
+                    if to < self.objects.len() {
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+        reachable
+    }
+}
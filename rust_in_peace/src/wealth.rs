@@ -0,0 +1,40 @@
+//! The player's wealth: the gold in their purse and the valuables they carry.
+//! Gold is counted rather than carried, so a pile of it the player picks up
+//! goes straight into their purse. Valuables are only good for selling.
+use super::{World, LOC_PLAYER};
+
+impl World {
+    /// Returns what the valuables the player carries are worth, at full value
+    pub fn valuables_worth(&self) -> u64 {
+        self.objects
+            .iter()
+            .filter(|object| object.treasure && object.location == Some(LOC_PLAYER))
+            .map(|object| object.value.unwrap_or(0) * object.quantity.unwrap_or(1))
+            .sum()
+    }
+
+    /// Empties a pile of gold the player has picked up into their purse
+    pub(crate) fn pocket_gold(&mut self, index: usize) -> String {
+        let pile = &self.objects[index];
+        if !pile.item || pile.gold == 0 || pile.location != Some(LOC_PLAYER) {
+            return String::new();
+        }
+        let gold = pile.gold;
+        self.objects[index].gold = 0;
+        self.objects[index].location = None;
+        self.objects[LOC_PLAYER].gold += gold;
+        format!(
+            "You count {} gold. You have {} gold now.\n",
+            gold, self.objects[LOC_PLAYER].gold
+        )
+    }
+
+    /// Describes the player's gold and valuables, for the inventory
+    pub(crate) fn describe_wealth(&self) -> String {
+        let gold = format!("You have {} gold.\n", self.objects[LOC_PLAYER].gold);
+        match self.valuables_worth() {
+            0 => gold,
+            worth => gold + &format!("Your valuables are worth {} gold.\n", worth),
+        }
+    }
+}
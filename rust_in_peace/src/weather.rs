@@ -0,0 +1,124 @@
+//! The weather over the outdoor locations, which turns every so often. Rain
+//! puts out the open flames of torches the player has lit, and snow piles up
+//! where it can, tiring the player out as they wade through it. Under a roof
+//! the player doesn't notice the weather at all.
+use super::{World, LOC_PLAYER};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Turns the weather holds before it may turn
+const WEATHER_TURNS: u64 = 20;
+
+// Out of this many, the odds of each kind of weather coming next: clear skies
+// the most, snow the least
+const WEATHER_ODDS: u32 = 6;
+const CLEAR_ODDS: u32 = 3;
+const RAIN_ODDS: u32 = 2;
+
+// Stamina wading through snowdrifts costs the player every turn
+const SNOW_COST: u64 = 1;
+
+/// What the sky is doing
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Snow,
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Weather::Clear => write!(f, "clear"),
+            Weather::Rain => write!(f, "rain"),
+            Weather::Snow => write!(f, "snow"),
+        }
+    }
+}
+
+impl World {
+    /// Returns true if the player is somewhere the weather reaches them
+    pub fn is_outdoors(&self) -> bool {
+        self.objects[LOC_PLAYER]
+            .location
+            .is_some_and(|location| self.objects[location].outdoors)
+    }
+
+    /// Returns the line the weather adds to the description of where the
+    /// player is, if it is outdoors
+    pub(crate) fn describe_weather(&self) -> String {
+        if !self.is_outdoors() {
+            return String::new();
+        }
+        match self.weather {
+            Weather::Clear => String::new(),
+            Weather::Rain => " Rain is falling steadily.\n".to_string(),
+            Weather::Snow => " Snow is falling, settling on everything.\n".to_string(),
+        }
+    }
+
+    /// Rolls what the weather does next
+    fn next_weather(&self) -> Weather {
+        match self.rng().gen_range(0..WEATHER_ODDS) {
+            roll if roll < CLEAR_ODDS => Weather::Clear,
+            roll if roll < CLEAR_ODDS + RAIN_ODDS => Weather::Rain,
+            _ => Weather::Snow,
+        }
+    }
+
+    /// Puts out the lit flames the player carries or has left where they are
+    fn douse_flames(&mut self) -> String {
+        let location = self.objects[LOC_PLAYER].location;
+        let mut output = String::new();
+        for index in 0..self.objects.len() {
+            let object = &self.objects[index];
+            if object.flame
+                && object.lit
+                && (object.location == Some(LOC_PLAYER) || object.location == location)
+            {
+                self.objects[index].lit = false;
+                output += &format!(
+                    "\nThe rain puts out the {}.\n",
+                    self.objects[index].label[0]
+                );
+            }
+        }
+        output
+    }
+
+    /// Turns the weather every so often, and lets it do what it does to a
+    /// player out in it
+    pub(crate) fn run_weather(&mut self) -> String {
+        let mut output = String::new();
+        if self.turn.is_multiple_of(WEATHER_TURNS) {
+            let weather = self.next_weather();
+            if weather != self.weather && self.is_outdoors() {
+                output += match weather {
+                    Weather::Clear => "\nThe sky clears.\n",
+                    Weather::Rain => "\nIt starts to rain.\n",
+                    Weather::Snow => "\nIt starts to snow.\n",
+                };
+            }
+            self.weather = weather;
+        }
+        if !self.is_outdoors() {
+            return output;
+        }
+        match self.weather {
+            Weather::Clear => {}
+            Weather::Rain => output += &self.douse_flames(),
+            Weather::Snow => {
+                let location = self.objects[LOC_PLAYER].location.unwrap();
+                if self.objects[location].snowdrifts {
+                    let winded = self.spend_stamina(SNOW_COST);
+                    if !winded.is_empty() {
+                        output += &(winded + "\n");
+                    }
+                }
+            }
+        }
+        output
+    }
+}
@@ -4,6 +4,28 @@ use game_lib::*;
 mod tests {
     use super::*;
 
+    /// Returns the index of the object that goes by a label
+    fn index_of(world: &World, label: &str) -> usize {
+        world
+            .objects
+            .iter()
+            .position(|object| object.label[0] == label)
+            .unwrap()
+    }
+
+    /// Returns the bundled world with its gear at the stats the world file
+    /// gives it, for tests that count on exact damage or armor
+    fn unrolled_world() -> World {
+        let mut world = World::new();
+        let file = World::parse_file("game_file.ron").unwrap();
+        for (object, unrolled) in world.objects.iter_mut().zip(file.objects) {
+            object.attack = unrolled.attack;
+            object.armor = unrolled.armor;
+            object.quality = unrolled.quality;
+        }
+        world
+    }
+
     #[test]
     fn test_game_over() {
         // Create a world instance
@@ -12,6 +34,7 @@ mod tests {
         // Scenario 1: Player's health is 0
         world.objects[LOC_PLAYER].health = Some(0);
         assert!(world.game_over());
+        assert_eq!(world.win_message(), None);
 
         // Scenario 2: Player's health is not 0, but all enemies' health is 0
         world.objects[LOC_PLAYER].health = Some(100);
@@ -19,6 +42,10 @@ mod tests {
         world.objects[LOC_TROLL].health = Some(0);
         world.objects[LOC_BANDITS].health = Some(0);
         assert!(world.game_over());
+        assert_eq!(
+            world.win_message(),
+            Some("You have defeated all enemies! You win!\n")
+        );
 
         // Scenario 3: Neither player's health is 0 nor all enemies' health is 0
         world.objects[LOC_PLAYER].health = Some(100);
@@ -53,7 +80,7 @@ mod tests {
 
         // Test case 1: Look without specifying a noun
         let result = world.do_look("");
-        let expected = " You are in the Forest\n Look out for tree people..\n\nYou see:\nA path to the north leading out of the forest leading to an old Tavern\n";
+        let expected = " You are in the Forest\n Look out for tree people..\n\nYou see:\nA path to the north leading out of the forest leading to an old Tavern\nA hermit in a moss-covered cloak, picking through the undergrowth for herbs.\n";
         assert_eq!(result, expected);
 
         // Test case 2: Look with an invalid noun
@@ -94,6 +121,7 @@ mod tests {
             health: Some(10),
             attack: None,
             consumable: true,
+            ..Default::default()
         });
 
         // Test consuming an object
@@ -124,6 +152,7 @@ mod tests {
             health: None,
             attack: Some(20),
             consumable: false,
+            ..Default::default()
         });
 
         // Test dropping an object
@@ -155,12 +184,4007 @@ mod tests {
     fn test_display_locations() {
         let mut world = World::default();
 
-        // Set up the initial world state
-        world.objects[LOC_PLAYER].location = Some(LOC_PLAYER);
-
-        // Test the display of available locations
-        let expected_result = "Available locations:\n0: Forest\n1: Dungeons\n2: Cave\n3: Tavern\n4: Village\n5: Stronghold\n";
+        // Only the starting location has been explored
+        let expected_result = "Explored locations:\n0: Forest\n    North: ???\n";
         let result = world.display_locations();
         assert_eq!(result, expected_result);
+
+        // Exits to places the player has been are named
+        world.update_state(&parse("n".to_string()));
+        let result = world.display_locations();
+        assert!(result.contains("0: Forest\n    North: Tavern\n"));
+        assert!(result.contains("3: Tavern\n    South: Forest\n    East: ???\n    West: ???\n"));
+        assert!(!result.contains("Stronghold"));
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut world = World::new();
+
+        // The built-in world has no problems
+        assert!(world.validate().is_empty());
+
+        // A passage leading nowhere is an error
+        world.objects.push(Object {
+            label: vec!["Up".to_string()],
+            description: "A rope ladder".to_string(),
+            location: Some(LOC_FOREST),
+            destination: Some(999),
+            ..Default::default()
+        });
+        let diagnostics = world.validate();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+
+        // Missing player is reported instead of panicking
+        world.objects.truncate(LOC_PLAYER);
+        let diagnostics = world.validate();
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("must be the player")));
+    }
+
+    #[test]
+    fn test_validate_exit_code() {
+        let validate = |file: &str| {
+            std::process::Command::new(env!("CARGO_BIN_EXE_main"))
+                .args(["--validate", file])
+                .current_dir(env!("CARGO_MANIFEST_DIR"))
+                .output()
+                .unwrap()
+        };
+
+        // The bundled world passes
+        let output = validate("game_file.ron");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("0 error(s)"));
+
+        // A passage leading nowhere fails it
+        let path = std::env::temp_dir().join(format!("rip_broken_{}.ron", std::process::id()));
+        let world = std::fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("game_file.ron"),
+        )
+        .unwrap();
+        std::fs::write(
+            &path,
+            world.replacen("destination:Some(3)", "destination:Some(999)", 1),
+        )
+        .unwrap();
+        let output = validate(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("error"));
+
+        // So does a file that isn't there
+        assert_eq!(validate("no_such_world.ron").status.code(), Some(1));
+    }
+
+    #[test]
+    fn test_exits() {
+        let mut world = World::new();
+
+        // The Tavern leads south, east, west and up, but its northern wall is no way out
+        let exits = world.exits(LOC_TAVERN);
+        let destinations: Vec<Option<usize>> = exits
+            .iter()
+            .map(|exit| world.objects[*exit].destination)
+            .collect();
+        assert_eq!(
+            destinations,
+            [
+                Some(LOC_FOREST),
+                Some(LOC_DUNGEONS),
+                Some(LOC_VILLAGE),
+                Some(index_of(&world, "Your Room"))
+            ]
+        );
+        assert!(exits.iter().all(|exit| world.objects[*exit].is_passage()));
+        assert!(world
+            .objects
+            .iter()
+            .filter(|object| object.location == Some(LOC_TAVERN))
+            .filter(|object| object.label[0] == "North")
+            .all(|object| !object.is_passage()));
+
+        // Locations and items aren't passages
+        assert!(!world.objects[LOC_TAVERN].is_passage());
+        assert!(!world.objects[index_of(&world, "Sword")].is_passage());
+
+        // A passage taken out of the world no longer leads anywhere
+        world.objects[exits[0]].location = None;
+        assert!(!world.objects[exits[0]].is_passage());
+        assert_eq!(world.exits(LOC_TAVERN), exits[1..]);
+    }
+
+    #[test]
+    fn test_capture_and_turn_in() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+
+        // A troll that is still standing cannot be captured
+        let result = world.do_capture(&"troll".to_string());
+        assert_eq!(result, "The Troll is still standing. Defeat it first.\n");
+
+        // Knocked out with a blunt weapon, it can be tied up
+        world.objects[LOC_TROLL].health = Some(0);
+        world.objects[LOC_TROLL].knocked_out = true;
+        let result = world.do_capture(&"troll".to_string());
+        assert_eq!(
+            result,
+            "You tie up the Troll. It will follow you wherever you go.\n"
+        );
+        assert!(world.objects[LOC_TROLL].captured);
+        assert_eq!(world.objects[LOC_TROLL].location, Some(LOC_DUNGEONS));
+        assert!(!world.do_inventory().contains("Troll"));
+        assert_eq!(world.karma, 1);
+
+        // A captive with enough persuasion behind the questions gives up what it knows
+        world.karma = 20;
+        let result = world.do_interrogate(&"troll".to_string());
+        assert!(result.starts_with("The Troll talks:"));
+        assert!(world.flags.contains("stronghold_stash"));
+        assert_eq!(world.journal.len(), 1);
+
+        // The bounty can only be claimed where the notice board is
+        let result = world.do_turn_in(&"troll".to_string());
+        assert_eq!(result, "There is nobody here to take your captive.\n");
+
+        // The captive is led along to the Tavern
+        world.events.clear();
+        world.update_state(&parse("go west".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+        assert_eq!(world.objects[LOC_TROLL].location, Some(LOC_TAVERN));
+        let result = world.do_turn_in(&"troll".to_string());
+        assert_eq!(
+            result,
+            "You claim the bounty on the Troll. Your reputation is now 10.\n"
+        );
+        assert_eq!(world.objects[LOC_TROLL].location, None);
+    }
+
+    #[test]
+    fn test_many_locations() {
+        let mut world = World::new();
+        world.objects.clear();
+        world.quests.clear();
+        world.classes.clear();
+        world.encounters.clear();
+        world.recipes.clear();
+        world.brews.clear();
+
+        // Twenty rooms in a row, with the player in the first one
+        let room = |index: usize| Object {
+            label: vec![format!("Room {}", index)],
+            description: format!("Room number {}", index),
+            ..Default::default()
+        };
+        let rooms: Vec<usize> = (0..=20).filter(|index| *index != LOC_PLAYER).collect();
+        for index in 0..=20 {
+            if index == LOC_PLAYER {
+                world.objects.push(Object {
+                    label: vec!["Player".to_string()],
+                    description: "You".to_string(),
+                    location: Some(rooms[0]),
+                    health: Some(100),
+                    ..Default::default()
+                });
+            } else {
+                world.objects.push(room(index));
+            }
+        }
+        for pair in rooms.windows(2) {
+            world.objects.push(Object {
+                label: vec!["Onward".to_string()],
+                description: "A door leading onward".to_string(),
+                location: Some(pair[0]),
+                destination: Some(pair[1]),
+                ..Default::default()
+            });
+            world.objects.push(Object {
+                label: vec!["Back".to_string()],
+                description: "A door leading back".to_string(),
+                location: Some(pair[1]),
+                destination: Some(pair[0]),
+                ..Default::default()
+            });
+        }
+        assert!(world.validate().is_empty());
+        assert_eq!(world.locations().len(), rooms.len());
+
+        // Walk all the way to the last room
+        for _ in 1..rooms.len() {
+            assert!(world.do_go(&"onward".to_string()).starts_with("OK."));
+        }
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(20));
+        assert!(world.display_locations().contains("20: Room 20\n"));
+
+        // Only enemies that are not optional have to be beaten
+        world.objects.push(Object {
+            label: vec!["Rat".to_string()],
+            description: "A rat".to_string(),
+            location: Some(20),
+            enemy: true,
+            health: Some(10),
+            optional: true,
+            ..Default::default()
+        });
+        world.objects.push(Object {
+            label: vec!["Ogre".to_string()],
+            description: "An ogre".to_string(),
+            location: Some(20),
+            enemy: true,
+            health: Some(100),
+            ..Default::default()
+        });
+        assert!(!world.game_over());
+        let ogre = world.objects.len() - 1;
+        world.objects[ogre].health = Some(0);
+        assert!(world.game_over());
+    }
+
+    #[test]
+    fn test_reactions() {
+        let mut world = World::new();
+        let villagers = index_of(&world, "Villagers");
+
+        // Nothing happens until the bandits are defeated
+        world.tick();
+        assert_eq!(world.objects[villagers].location, None);
+
+        world.flags.insert("defeated:bandits".to_string());
+        let output: String = (0..5).map(|_| world.tick()).collect();
+        assert!(output.contains("the bandit patrols stop coming"));
+        assert!(output.contains("the villagers have started returning home"));
+        assert_eq!(world.objects[villagers].location, Some(LOC_VILLAGE));
+        assert!(world.flags.contains("village_restored"));
+
+        // Reactions only fire once
+        assert_eq!(world.tick(), "");
+    }
+
+    #[test]
+    fn test_companion_approval() {
+        let mut world = World::new();
+        world.objects.push(Object {
+            label: vec!["Hermit".to_string()],
+            description: "An old hermit".to_string(),
+            location: Some(LOC_FOREST),
+            health: Some(50),
+            companion: Some(Companion {
+                joined: true,
+                banter: vec![Banter {
+                    line: "These woods were greener once.".to_string(),
+                    location: Some(LOC_FOREST),
+                    ..Default::default()
+                }],
+                opinions: vec![
+                    Opinion {
+                        flag: "spared:troll".to_string(),
+                        change: 5,
+                        ..Default::default()
+                    },
+                    Opinion {
+                        flag: "killed:bear".to_string(),
+                        change: -10,
+                        ..Default::default()
+                    },
+                ],
+                quest_at: Some(5),
+                quest_flag: Some("quest:hermit".to_string()),
+                leave_below: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let hermit = world.objects.len() - 1;
+        assert_eq!(world.party(), vec![LOC_PLAYER, hermit]);
+
+        // Sparing the troll wins the hermit over and unlocks a favour
+        world.flags.insert("spared:troll".to_string());
+        let output = world.tick();
+        assert!(output.contains("Hermit approves."));
+        assert!(world.flags.contains("quest:hermit"));
+
+        // Banter comes every few turns
+        let output: String = (0..4).map(|_| world.tick()).collect();
+        assert!(output.contains("These woods were greener once."));
+
+        // Killing the bear is too much for them
+        world.flags.insert("killed:bear".to_string());
+        let output = world.tick();
+        assert!(output.contains("Hermit has had enough of you and leaves."));
+        assert_eq!(world.party(), vec![LOC_PLAYER]);
+    }
+
+    #[test]
+    fn test_parse_directions() {
+        assert!(matches!(parse("n".to_string()), Command::Go(noun) if noun == "north"));
+        assert!(matches!(parse("go w".to_string()), Command::Go(noun) if noun == "west"));
+        assert!(matches!(parse("South".to_string()), Command::Go(noun) if noun == "south"));
+        assert!(matches!(parse("go tavern".to_string()), Command::Go(noun) if noun == "tavern"));
+
+        // The abbreviation leads through the passage out of the current location
+        let mut world = World::new();
+        let command = parse("n".to_string());
+        world.update_state(&command);
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+    }
+
+    #[test]
+    fn test_buy_and_ride_horse() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+
+        // The horse has to be paid for before it can be ridden
+        let result = world.update_state(&parse("ride horse".to_string()));
+        assert!(result.starts_with("The Horse is not yours. It costs 15 gold"));
+        let result = world.update_state(&parse("buy horse".to_string()));
+        assert!(result.starts_with("You buy the Horse for 15 gold. You have 5 gold left."));
+        world.objects[LOC_PLAYER].strength = Some(10);
+        assert_eq!(world.capacity(), Some(30));
+        let result = world.update_state(&parse("ride horse".to_string()));
+        assert!(result.starts_with("You climb onto the Horse."));
+
+        // The horse carries some of the load
+        assert_eq!(world.capacity(), Some(50));
+
+        // Riding takes one turn per location instead of two
+        let turn = world.turn;
+        world.update_state(&parse("e".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+        assert_eq!(world.turn, turn + 1);
+
+        // The horse can't go into the Dungeons
+        let result = world.update_state(&parse("e".to_string()));
+        assert!(result.starts_with("You can't ride the Horse into the Dungeons."));
+        world.update_state(&parse("dismount".to_string()));
+        assert_eq!(world.capacity(), Some(30));
+        world.update_state(&parse("e".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_DUNGEONS));
+    }
+
+    #[test]
+    fn test_noise() {
+        let mut world = World::new();
+        assert_eq!(world.noise(), 0);
+        assert_eq!(world.noise_level(), "quiet");
+
+        // Heavier gear makes more noise
+        for object in world.objects.iter_mut() {
+            if object.label[0] == "Sword" || object.label[0] == "Spear" {
+                object.location = Some(LOC_PLAYER);
+            }
+        }
+        assert_eq!(world.noise(), 2);
+        assert!(world.do_inventory().ends_with("Your gear is noticeable.\n"));
+    }
+
+    #[test]
+    fn test_locked_stronghold() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        let key = index_of(&world, "Iron Key");
+
+        // The way into the Stronghold stays shut without the Troll's key
+        let result = world.update_state(&parse("n".to_string()));
+        assert!(result.starts_with("The way north is locked. You need the Iron Key."));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_VILLAGE));
+
+        // Killing the Troll leaves the key where it fell
+        world.objects[LOC_TROLL].health = Some(0);
+        let result = world.drop_carried(LOC_TROLL);
+        assert_eq!(result, "The Troll drops the Iron Key.\n");
+        assert_eq!(
+            world.objects[key].location,
+            world.objects[LOC_TROLL].location
+        );
+
+        world.objects[key].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("unlock north".to_string()));
+        assert!(result.starts_with("You unlock the way north with the Iron Key."));
+        let result = world.update_state(&parse("unlock n".to_string()));
+        assert!(result.starts_with("The way north is not locked."));
+        world.update_state(&parse("n".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_STRONGHOLD));
+    }
+
+    #[test]
+    fn test_craft_and_spring_trap() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        let rope = index_of(&world, "Rope");
+        let snare = index_of(&world, "Snare");
+
+        let result = world.update_state(&parse("craft snare".to_string()));
+        assert!(result.starts_with("To make the Snare you need: Rope."));
+        world.update_state(&parse("get rope".to_string()));
+        let result = world.update_state(&parse("craft snare".to_string()));
+        assert!(result.starts_with("You make the Snare from the Rope."));
+        assert_eq!(world.objects[rope].location, None);
+        assert_eq!(world.objects[snare].location, Some(LOC_PLAYER));
+
+        // Set the snare in the Dungeons and let the Bear wander into it
+        world.objects[LOC_BEAR].location = None;
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        let result = world.update_state(&parse("place snare".to_string()));
+        assert!(result.starts_with("You set the Snare here."));
+        let result = world.update_state(&parse("get snare".to_string()));
+        assert_eq!(result, "You cannot get that!!\n");
+        assert_eq!(world.objects[snare].location, Some(LOC_DUNGEONS));
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_BEAR].location = Some(LOC_DUNGEONS);
+        let result = world.spring_traps(LOC_BEAR);
+        assert!(result.contains("caught in your Snare in the Dungeons"));
+        assert_eq!(world.objects[snare].location, None);
+
+        // The trap hurts the Bear as soon as the fight starts
+        let result = world.trap_damage(LOC_BEAR);
+        assert!(result.contains("takes 20 damage"));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(80));
+        assert_eq!(world.trap_damage(LOC_BEAR), "");
+    }
+
+    #[test]
+    fn test_barricade() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_BEAR].location = Some(LOC_DUNGEONS);
+        world.objects[LOC_BEAR].pursuing = true;
+
+        let result = world.update_state(&parse("barricade east".to_string()));
+        assert!(result.starts_with("You have nothing to barricade the way east with."));
+        assert_eq!(world.objects[LOC_BEAR].location, Some(LOC_TAVERN));
+
+        // With the planks the Bear can't follow through the barricade
+        world.objects[LOC_BEAR].location = Some(LOC_DUNGEONS);
+        world.update_state(&parse("get planks".to_string()));
+        world.objects[LOC_BEAR].location = Some(LOC_DUNGEONS);
+        let result = world.update_state(&parse("barricade e".to_string()));
+        assert!(result.starts_with("You barricade the way east with the Planks."));
+        assert!(result.contains("battering at the barricade to the east"));
+        assert_eq!(world.objects[LOC_BEAR].location, Some(LOC_DUNGEONS));
+
+        // The Bear eventually breaks through and follows the player in
+        for _ in 0..3 {
+            world.update_state(&parse("look".to_string()));
+        }
+        assert_eq!(world.objects[LOC_BEAR].location, Some(LOC_TAVERN));
+    }
+
+    #[test]
+    fn test_conditional_passages() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        world.objects[LOC_BEAR].location = None;
+
+        // The Troll guards the way to the Cave
+        let result = world.update_state(&parse("n".to_string()));
+        assert!(result.starts_with("The Troll stands in front of the passage north."));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_DUNGEONS));
+        world.objects[LOC_TROLL].health = Some(0);
+        world.update_state(&parse("n".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_CAVE));
+
+        // The cliff below the Stronghold only goes one way
+        world.objects[LOC_PLAYER].location = Some(LOC_STRONGHOLD);
+        world.objects[LOC_BANDITS].health = Some(0);
+        world.update_state(&parse("d".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_FOREST));
+        let result = world.update_state(&parse("u".to_string()));
+        assert!(!result.starts_with("OK."));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_FOREST));
+
+        assert!(world.condition_met(&Condition::EnemyDead(LOC_TROLL)));
+        assert!(!world.condition_met(&Condition::Flag("village_restored".to_string())));
+    }
+
+    #[test]
+    fn test_fast_travel() {
+        let mut world = World::new();
+
+        let result = world.update_state(&parse("travel village".to_string()));
+        assert!(result.starts_with("You haven't been to the Village yet."));
+        world.update_state(&parse("n".to_string()));
+        world.update_state(&parse("w".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_VILLAGE));
+
+        // Enemies on the way need confirming before setting off
+        world.objects[LOC_TROLL].location = Some(LOC_TAVERN);
+        let result = world.update_state(&parse("travel forest".to_string()));
+        assert!(result.contains("the Troll in the Tavern. Travel anyway?"));
+        let result = world.update_state(&parse("no".to_string()));
+        assert!(result.starts_with("You stay where you are."));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_VILLAGE));
+        world.update_state(&parse("travel forest".to_string()));
+        let turn = world.turn;
+        let result = world.update_state(&parse("yes".to_string()));
+        assert!(result.contains("You are in the Forest"));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_FOREST));
+        assert_eq!(world.turn, turn + 4);
+        let result = world.update_state(&parse("yes".to_string()));
+        assert!(result.starts_with("There is nothing to answer."));
+    }
+
+    #[test]
+    fn test_shrine() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        world.objects[LOC_BEAR].location = None;
+        let shrine = world
+            .objects
+            .iter()
+            .position(|object| object.shrine.is_some())
+            .unwrap();
+        if let Some(details) = world.objects[shrine].shrine.as_mut() {
+            details.curses.clear();
+        }
+
+        let result = world.update_state(&parse("offer gold".to_string()));
+        assert!(result.starts_with("You offer 5 gold at the Shrine. A warm light surrounds you."));
+        assert_eq!(world.objects[LOC_PLAYER].gold, 15);
+        assert_eq!(world.objects[LOC_PLAYER].effects.len(), 1);
+        assert!(world.attack_bonus(LOC_PLAYER) + world.defense_bonus(LOC_PLAYER) > 0);
+
+        // Blessings only last so long
+        for _ in 0..10 {
+            world.tick();
+        }
+        assert!(world.objects[LOC_PLAYER].effects.is_empty());
+        assert_eq!(World::adjust_damage(10, -15), 0);
+    }
+
+    #[test]
+    fn test_karma_events() {
+        let mut world = World::new();
+        let assassin = index_of(&world, "Assassin");
+        let chance = |world: &World, text: &str| {
+            let event = world.events.iter().find(|e| e.text.contains(text)).unwrap();
+            world.event_weight(event)
+        };
+        assert_eq!(chance(&world, "hooded figure"), 0);
+
+        // Good deeds make grateful villagers more likely
+        world.karma = 3;
+        assert!(chance(&world, "grateful farmer") > 1);
+        assert_eq!(chance(&world, "hooded figure"), 0);
+
+        // Ruthless players attract assassins
+        world.karma = -2;
+        assert_eq!(chance(&world, "grateful farmer"), 0);
+        assert!(chance(&world, "hooded figure") > 0);
+        let index = world.events.iter().position(|e| e.once).unwrap();
+        let result = world.fire_event(index);
+        assert!(result.contains("Someone has paid for your death!"));
+        assert_eq!(
+            world.objects[assassin].location,
+            world.objects[LOC_PLAYER].location
+        );
+        assert_eq!(world.event_weight(&world.events[index]), 0);
+    }
+
+    #[test]
+    fn test_go_far() {
+        let mut world = World::new();
+
+        // The Village is two passages away from the Forest
+        let result = world.update_state(&parse("go village".to_string()));
+        assert!(result.starts_with("You pass through the Tavern.\n"));
+        assert!(result.contains("You are in the Village"));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_VILLAGE));
+
+        // Walking stops where an enemy is in the way
+        world.objects[LOC_TROLL].location = Some(LOC_TAVERN);
+        let result = world.update_state(&parse("go dungeons".to_string()));
+        assert!(result.contains("You stop, the way on isn't safe."));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+
+        // The Stronghold's gate is locked and its cliff can't be climbed
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        let result = world.update_state(&parse("go stronghold".to_string()));
+        assert!(result.starts_with("You can't find a way to the Stronghold from here."));
+    }
+
+    #[test]
+    fn test_loan() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        let collector = world
+            .objects
+            .iter()
+            .position(|object| object.collector)
+            .unwrap();
+
+        let result = world.update_state(&parse("borrow 100".to_string()));
+        assert!(result.contains("will lend you anything up to 50 gold"));
+        let result = world.update_state(&parse("borrow 20".to_string()));
+        assert!(result.starts_with("The Moneylender hands you 20 gold."));
+        assert_eq!(world.objects[LOC_PLAYER].gold, 40);
+
+        // Interest piles up and the debt collector comes once the loan is overdue
+        let due = world.loan.as_ref().unwrap().due;
+        while world.turn < due {
+            world.tick();
+        }
+        assert!(world.loan.as_ref().unwrap().owed > 20);
+        assert_eq!(
+            world.objects[collector].location,
+            world.objects[LOC_PLAYER].location
+        );
+
+        // Paying up sends the collector away
+        world.objects[LOC_PLAYER].gold = 100;
+        let result = world.update_state(&parse("repay".to_string()));
+        assert!(result.contains("Your debt is settled."));
+        assert_eq!(world.loan, None);
+        assert_eq!(world.objects[collector].location, None);
+    }
+
+    #[test]
+    fn test_home() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_PLAYER].gold = 50;
+        world.objects[LOC_PLAYER].health = Some(40);
+
+        // The room upstairs needs its key, which is for sale
+        let result = world.update_state(&parse("u".to_string()));
+        assert!(result.starts_with("The way up is locked. You need the Room Key."));
+        world.update_state(&parse("buy room key".to_string()));
+        world.update_state(&parse("buy rug".to_string()));
+        world.update_state(&parse("u".to_string()));
+        let room = world.objects[LOC_PLAYER].location.unwrap();
+        assert!(world.objects[room].home);
+
+        // Decor only helps once it is put up at home
+        assert_eq!(world.defense_bonus(LOC_PLAYER), 0);
+        world.update_state(&parse("drop rug".to_string()));
+        assert_eq!(world.defense_bonus(LOC_PLAYER), 1);
+
+        let result = world.update_state(&parse("rest".to_string()));
+        assert!(result.starts_with("You sleep in your own bed"));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(100));
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        let result = world.update_state(&parse("rest".to_string()));
+        assert!(result.starts_with("You are already fully rested."));
+    }
+
+    #[test]
+    fn test_back() {
+        let mut world = World::new();
+        let result = world.update_state(&parse("back".to_string()));
+        assert!(result.starts_with("You haven't been anywhere else yet."));
+
+        world.update_state(&parse("n".to_string()));
+        world.update_state(&parse("e".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_DUNGEONS));
+        world.update_state(&parse("go back".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+        world.update_state(&parse("back".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_FOREST));
+
+        // There is no going back up the cliff
+        world.objects[LOC_PLAYER].location = Some(LOC_STRONGHOLD);
+        world.objects[LOC_BANDITS].location = None;
+        world.update_state(&parse("d".to_string()));
+        let result = world.update_state(&parse("back".to_string()));
+        assert!(result.starts_with("You can't go back the way you came."));
+    }
+
+    #[test]
+    fn test_dark_cave() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        assert!(world.is_dark(LOC_CAVE));
+        let result = world.do_look("");
+        assert!(result.ends_with("It is too dark to see what is around you.\n"));
+
+        let result = world.update_state(&parse("light torch".to_string()));
+        assert!(result.starts_with("You don't see any 'torch' here."));
+        let torch = index_of(&world, "Torch");
+        world.objects[torch].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("light torch".to_string()));
+        assert!(result.starts_with("You light the Torch."));
+        assert!(!world.is_dark(LOC_CAVE));
+        assert!(world.do_look("").contains("A mossy shrine"));
+
+        // The light goes where the player goes
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        assert!(world.is_dark(LOC_CAVE));
+    }
+
+    #[test]
+    fn test_training() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_PLAYER].gold = 35;
+
+        let result = world.update_state(&parse("train".to_string()));
+        assert!(result.starts_with(
+            "The Soldier can teach you: swordsmanship (10 gold), toughness (8 gold)."
+        ));
+
+        // Each rank costs more than the last
+        let turn = world.turn;
+        let result = world.update_state(&parse("train swordsmanship".to_string()));
+        assert!(result.contains("Your swordsmanship is now rank 1."));
+        assert_eq!(world.turn, turn + 3);
+        assert_eq!(world.attack_bonus(LOC_PLAYER), 3);
+        world.update_state(&parse("train swordsmanship".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].gold, 5);
+        assert_eq!(world.skill(Skill::Swordsmanship), 2);
+        let result = world.update_state(&parse("train swordsmanship".to_string()));
+        assert!(result.starts_with("The next lesson in swordsmanship costs 30 gold"));
+
+        // Stealth is taught by the Wizard in the Village
+        let result = world.update_state(&parse("train stealth".to_string()));
+        assert!(result.starts_with("The Soldier can teach you:"));
+    }
+
+    #[test]
+    fn test_secret_passage() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        world.objects[LOC_BEAR].location = None;
+        let crack = world
+            .objects
+            .iter()
+            .position(|object| object.hidden)
+            .unwrap();
+        assert_eq!(world.exits(LOC_CAVE).len(), 1);
+        let result = world.update_state(&parse("search".to_string()));
+        assert!(result.starts_with("It is too dark to search here."));
+        let result = world.update_state(&parse("u".to_string()));
+        assert!(!result.starts_with("OK."));
+
+        // The most perceptive player finds the way out sooner or later
+        world.objects[LOC_CAVE].dark = false;
+        world.skills.insert(Skill::Perception, 3);
+        while world.objects[crack].hidden {
+            world.update_state(&parse("search".to_string()));
+        }
+        assert_eq!(world.exits(LOC_CAVE).len(), 2);
+        // Strong enough to make the climb every time
+        world.objects[LOC_PLAYER].strength = Some(30);
+        world.update_state(&parse("u".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_FOREST));
+    }
+
+    #[test]
+    fn test_search_hidden_items() {
+        let mut world = World::new();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        world.objects[LOC_PLAYER].health = Some(50);
+        let herbs = index_of(&world, "Herbs");
+        assert!(world
+            .update_state(&parse("get herbs".to_string()))
+            .starts_with("Invalid command!!"));
+
+        // Hidden items can be picked up once they are found
+        world.skills.insert(Skill::Perception, 3);
+        while world.objects[herbs].hidden {
+            world.update_state(&parse("search".to_string()));
+        }
+        world.update_state(&parse("eat herbs".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(65));
+    }
+
+    #[test]
+    fn test_timeline() {
+        let mut world = World::new();
+        world.events.clear();
+        world.update_state(&parse("n".to_string()));
+        world.set_flag("defeated:troll".to_string());
+        world.set_flag("defeated:troll".to_string());
+        for _ in 0..3 {
+            world.tick();
+        }
+
+        let texts: Vec<&str> = world.timeline.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "player entered the Tavern",
+                "flag set: defeated:troll",
+                "reaction 0 fired after defeated:troll"
+            ]
+        );
+        assert!(world.timeline[0].turn < world.timeline[2].turn);
+
+        #[cfg(feature = "debug")]
+        {
+            let result = world.update_state(&parse("timeline 1".to_string()));
+            assert!(result.starts_with("[turn "));
+            assert!(result.contains("] reaction 0 fired after defeated:troll\n"));
+
+            // The whole timeline can be dumped to a file, keeping the path's case
+            let dir = std::env::temp_dir().join(format!("rip_Timeline_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("Run.txt");
+            let result = world.update_state(&parse(format!("timeline dump {}", path.display())));
+            assert_eq!(result, format!("Wrote 3 entries to {}.\n", path.display()));
+            let dumped = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(dumped.lines().count(), 3);
+            assert!(dumped.starts_with("[turn "));
+            assert!(dumped.ends_with("] reaction 0 fired after defeated:troll\n"));
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_combat_rounds() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_TROLL].attack = Some(0);
+        world.objects[LOC_BEAR].location = None;
+        // Nimble enough to always get away
+        world.objects[LOC_PLAYER].dodge = 100;
+
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert_eq!(result, "You are not fighting anything.\n");
+
+        let result = world.update_state(&parse("attack troll".to_string()));
+        assert!(result.contains("You are attacking the Troll."));
+        assert_eq!(
+            world.combat,
+            Some(CombatState {
+                enemy: LOC_TROLL,
+                enemies: vec![LOC_TROLL],
+                surprise: false,
+                defending: false
+            })
+        );
+
+        // Only fighting commands work until the fight is over
+        let location = world.objects[LOC_PLAYER].location;
+        let result = world.update_state(&parse("go tavern".to_string()));
+        assert!(result.contains("Hint:"));
+        assert_eq!(world.objects[LOC_PLAYER].location, location);
+        assert!(world
+            .update_state(&parse("inventory".to_string()))
+            .contains("A rusty sword."));
+
+        let result = world.update_state(&parse("run".to_string()));
+        assert_eq!(result, "You ran away from the Troll.\n");
+        assert_eq!(world.combat, None);
+
+        world.update_state(&parse("attack troll".to_string()));
+        let mut result = String::new();
+        while world.combat.is_some() {
+            result = world.update_state(&parse("use sword".to_string()));
+        }
+        assert!(result.contains("You killed the Troll."));
+        assert_eq!(world.objects[LOC_TROLL].health, Some(0));
+    }
+
+    #[test]
+    fn test_autosave_replay() {
+        let path = std::env::temp_dir().join(format!("rip_autosave_{}.ron", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut world = World::new();
+        let mut save = Autosave::create(path, &world).unwrap();
+
+        // Enough commands to roll over to a new checkpoint and journal a few more
+        for _ in 0..30 {
+            for input in ["n", "search", "s"] {
+                let command = parse(input.to_string());
+                world.update_state(&command);
+                save.record(&command, &world).unwrap();
+            }
+        }
+
+        let (loaded, save) = Autosave::load(path).unwrap();
+        assert_eq!(
+            ron::to_string(&loaded).unwrap(),
+            ron::to_string(&world).unwrap()
+        );
+        save.remove().unwrap();
+        assert!(!Autosave::exists(path));
+    }
+
+    #[test]
+    fn test_autosave_crash_between_checkpoint_and_journal() {
+        let path = std::env::temp_dir().join(format!("rip_crash_{}.ron", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut world = World::new();
+        let mut save = Autosave::create(path, &world).unwrap();
+        for input in ["n", "search", "s"] {
+            let command = parse(input.to_string());
+            world.update_state(&command);
+            save.record(&command, &world).unwrap();
+        }
+
+        // A crash before the new checkpoint lands keeps the old one and its journal
+        std::fs::write(format!("{}.tmp", path.trim_end_matches(".ron")), "(").unwrap();
+        let (loaded, _) = Autosave::load(path).unwrap();
+        assert_eq!(
+            ron::to_string(&loaded).unwrap(),
+            ron::to_string(&world).unwrap()
+        );
+
+        // A crash after it lands but before its journal is started doesn't
+        // replay the old journal on top of it
+        std::fs::write(path, ron::to_string(&world).unwrap()).unwrap();
+        let (loaded, save) = Autosave::load(path).unwrap();
+        assert_eq!(
+            ron::to_string(&loaded).unwrap(),
+            ron::to_string(&world).unwrap()
+        );
+        save.remove().unwrap();
+        std::fs::remove_file(format!("{}.tmp", path.trim_end_matches(".ron"))).unwrap();
+    }
+
+    #[test]
+    fn test_armor() {
+        let mut world = unrolled_world();
+        let leather = index_of(&world, "Leather Armor");
+        let chainmail = index_of(&world, "Chainmail");
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+
+        let result = world.do_wear(&"leather armor".to_string());
+        assert_eq!(result, "You are not holding any Leather Armor.\n");
+        world.objects[leather].location = Some(LOC_PLAYER);
+        world.objects[chainmail].location = Some(LOC_PLAYER);
+        let before = world.defense_bonus(LOC_PLAYER);
+
+        world.update_state(&parse("wear leather armor".to_string()));
+        assert_eq!(world.worn_armor(), Some(leather));
+        assert_eq!(world.defense_bonus(LOC_PLAYER), before + 5);
+        assert!(world
+            .do_inventory()
+            .contains("You are wearing the Leather Armor."));
+
+        // Putting on other armor swaps it for the old one
+        let result = world.update_state(&parse("wear chainmail".to_string()));
+        assert!(result.starts_with("You take off the Leather Armor. You put on the Chainmail."));
+        assert_eq!(world.defense_bonus(LOC_PLAYER), before + 10);
+
+        world.update_state(&parse("take off".to_string()));
+        assert_eq!(world.wearing, None);
+        assert_eq!(world.defense_bonus(LOC_PLAYER), before);
+
+        // Armor left behind doesn't protect anyone
+        world.update_state(&parse("wear chainmail".to_string()));
+        world.do_drop(&"chainmail".to_string());
+        assert_eq!(world.wearing, None);
+        assert_eq!(world.defense_bonus(LOC_PLAYER), before);
+    }
+
+    #[test]
+    fn test_session_host() {
+        let host = SessionHost::new(2);
+        let first = host.open(None).unwrap();
+        let second = host.open(None).unwrap();
+        assert_eq!(host.metrics().sessions, 2);
+
+        // A command in one session leaves the other session alone
+        let reply = host
+            .submit(first, parse("n".to_string()))
+            .unwrap()
+            .recv()
+            .unwrap();
+        assert!(reply.output.contains("Tavern"));
+        assert!(!reply.game_over);
+        let reply = host
+            .submit(second, parse("look".to_string()))
+            .unwrap()
+            .recv()
+            .unwrap();
+        assert!(reply.output.starts_with(" You are in the Forest"));
+        assert!(host.metrics().turns >= 2);
+
+        assert!(host.close(first));
+        assert!(host.submit(first, parse("look".to_string())).is_none());
+        assert_eq!(host.metrics().sessions, 1);
+
+        // A full host turns new sessions away until one closes
+        let open: Vec<u64> = (1..MAX_SESSIONS)
+            .map(|_| host.open(None).unwrap())
+            .collect();
+        assert!(host.open(None).is_err());
+        host.close(open[0]);
+        assert!(host.open(None).is_ok());
+        #[cfg(target_os = "linux")]
+        assert!(host.metrics().memory_bytes > 0);
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut world = World::new();
+
+        // The bundled world has nothing to warn about
+        assert!(world.lint().is_empty());
+
+        // A lost item, a quest nobody can finish and a rambling description
+        world.objects.push(Object {
+            label: vec!["Amulet".to_string()],
+            description: "An amulet.".to_string(),
+            item: true,
+            ..Default::default()
+        });
+        world.objects.push(Object {
+            label: vec!["Pebble".to_string()],
+            description: "A pebble. ".repeat(10),
+            location: Some(LOC_FOREST),
+            item: true,
+            ..Default::default()
+        });
+        world.reactions.push(Reaction {
+            when: "defeated:dragon".to_string(),
+            delay: 0,
+            message: None,
+            changes: vec![],
+            armed_at: None,
+            fired: false,
+        });
+
+        let diagnostics = world.lint();
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == Severity::Warning));
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert!(messages.iter().any(|m| m.contains("'Amulet'")));
+        assert!(messages.iter().any(|m| m.contains("'Pebble'")));
+        assert!(messages.iter().any(|m| m.contains("'defeated:dragon'")));
+    }
+
+    #[test]
+    fn test_critical_and_dodge() {
+        let mut world = unrolled_world();
+        let sword = index_of(&world, "Sword");
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_TROLL].attack = Some(0);
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.update_state(&parse("attack troll".to_string()));
+
+        // A troll that dodges everything can't be touched
+        world.objects[LOC_TROLL].dodge = 100;
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.starts_with("You missed the Troll."));
+        assert_eq!(world.objects[LOC_TROLL].health, Some(100));
+
+        // A sure critical hit does double the sword's damage
+        world.objects[LOC_TROLL].dodge = 0;
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = 100;
+        assert_eq!(
+            world.roll_attack(LOC_PLAYER, Some(sword), LOC_TROLL),
+            Outcome::Critical
+        );
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.starts_with("A critical hit! You attacked the Troll."));
+        assert_eq!(world.objects[LOC_TROLL].health, Some(60));
+    }
+
+    #[test]
+    fn test_resolve_damage() {
+        let mut world = World::new();
+        let spear = index_of(&world, "Spear");
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+
+        // The troll's hide turns spear points, but the bear has no such luck
+        let result = world.resolve_damage(LOC_PLAYER, Some(spear), LOC_TROLL);
+        assert_eq!(result.outcome, Outcome::Hit);
+        assert_eq!(result.kind, DamageType::Pierce);
+        assert!(result.resisted);
+        assert!((9..=12).contains(&result.damage));
+        assert_eq!(result.health, 100 - result.damage);
+
+        world.objects[LOC_BEAR].health = Some(5);
+        let result = world.resolve_damage(LOC_PLAYER, Some(spear), LOC_BEAR);
+        assert!(result.vulnerable);
+        assert!((27..=37).contains(&result.damage));
+        assert_eq!(result.health, 0);
+        assert_eq!(world.objects[LOC_BEAR].health, Some(0));
+    }
+
+    #[test]
+    fn test_multi_enemy_combat() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        let lookout = index_of(&world, "Lookout");
+        let brute = index_of(&world, "Brute");
+        world.objects[LOC_PLAYER].location = Some(LOC_STRONGHOLD);
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[sword].location = Some(LOC_PLAYER);
+
+        // The whole gang joins in when the player picks a fight with one of them
+        let result = world.update_state(&parse("attack bandits".to_string()));
+        assert!(result.contains("The Lookout joins the fight!"));
+        assert!(result.contains("The Brute joins the fight!"));
+        assert_eq!(world.fighting_enemies(), vec![LOC_BANDITS, lookout, brute]);
+
+        // Each enemy gets a line in the round summary
+        let result = world.update_state(&parse("use sword on lookout".to_string()));
+        assert!(result.contains("You attacked the Lookout."));
+        assert!(result.contains("This round:"));
+        assert!(result.contains("- The Brute "));
+        assert_eq!(world.combat.as_ref().unwrap().enemy, lookout);
+
+        let result = world.update_state(&parse("use sword on tavern".to_string()));
+        assert!(result.starts_with("You don't see any 'tavern' here."));
+
+        // Knocking one down turns the player to the next
+        while world.objects[lookout].health != Some(0) {
+            world.update_state(&parse("use sword on lookout".to_string()));
+        }
+        assert_eq!(world.fighting_enemies(), vec![LOC_BANDITS, brute]);
+        assert_ne!(world.combat.as_ref().unwrap().enemy, lookout);
+
+        // The Bandits never let anyone run
+        let result = world.update_state(&parse("run".to_string()));
+        assert!(result.starts_with("The Bandits won't let you get away!"));
+        assert!(world.combat.is_some());
+    }
+
+    #[test]
+    fn test_flee() {
+        let mut world = World::new();
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_BEAR].location = None;
+        world.update_state(&parse("attack troll".to_string()));
+
+        // A troll that never misses never lets the player slip away
+        world.objects[LOC_PLAYER].dodge = -100;
+        world.objects[LOC_TROLL].accuracy = 100;
+        let result = world.update_state(&parse("run".to_string()));
+        assert!(result.starts_with("You try to run, but the Troll cuts you off!"));
+        assert!(result.contains("The Troll attacks"));
+        assert!(world.combat.is_some());
+
+        world.objects[LOC_PLAYER].dodge = 100;
+        world.objects[LOC_TROLL].accuracy = 0;
+        let result = world.update_state(&parse("run".to_string()));
+        assert_eq!(result, "You ran away from the Troll.\n");
+    }
+
+    #[test]
+    fn test_initiative_and_surprise() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_BEAR].specials.clear();
+
+        // The sleeping Bear doesn't get to hit back in the opening round
+        world.objects[LOC_CAVE].dark = false;
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        assert!(world.do_look("").contains("A bear (enemy) (asleep)"));
+        let result = world.update_state(&parse("attack bear".to_string()));
+        assert!(result.contains("You catch the Bear by surprise"));
+        assert!(!world.objects[LOC_BEAR].asleep);
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.contains("still reeling from the surprise"));
+        assert!(!result.contains("The Bear attacks"));
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.contains("The Bear attacks"));
+        world.combat = None;
+
+        // A quick enemy strikes before the player can act
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_TROLL].initiative = 100;
+        let result = world.update_state(&parse("attack troll".to_string()));
+        assert!(result.contains("The Troll is quicker and strikes first!"));
+    }
+
+    #[test]
+    fn test_special_moves() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.events.clear();
+
+        // The Bear's maul leaves the player bleeding every turn after
+        world.objects[LOC_CAVE].dark = false;
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        world.objects[LOC_BEAR].asleep = false;
+        world.objects[LOC_BEAR].initiative = -100;
+        world.objects[LOC_BEAR].accuracy = 100;
+        world.objects[LOC_BEAR].specials[0].chance = 100;
+        world.update_state(&parse("attack bear".to_string()));
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.contains("The Bear rears up and rakes with its claws!"));
+        assert!(result.contains("You are bleeding."));
+        assert!(result.contains("You lose 3 health."));
+        assert!(world.objects[LOC_PLAYER]
+            .effects
+            .iter()
+            .any(|effect| effect.name == "Bleeding"));
+        world.combat = None;
+        world.objects[LOC_BEAR].location = None;
+
+        // The Troll heals a little every round
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_TROLL].health = Some(50);
+        world.objects[LOC_TROLL].initiative = -100;
+        world.update_state(&parse("attack troll".to_string()));
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.contains("The Troll's wounds knit themselves closed.\nTroll health:"));
+
+        // but never past the health it started with
+        world.objects[LOC_TROLL].max_health = Some(50);
+        world.objects[LOC_TROLL].health = Some(50);
+        world.objects[LOC_PLAYER].accuracy = -100;
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(!result.contains("knit themselves closed"));
+        assert_eq!(world.objects[LOC_TROLL].health, Some(50));
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.combat = None;
+
+        // The Bandits' volley is shared out between the whole party
+        world.objects[LOC_PLAYER].location = Some(LOC_STRONGHOLD);
+        world.objects[LOC_BANDITS].specials[0].chance = 100;
+        world.objects[LOC_BANDITS].accuracy = 100;
+        world.objects[LOC_BANDITS].critical = -100;
+        world.update_state(&parse("attack bandits".to_string()));
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.contains("The Bandits loose a volley of arrows!\nYou got hit for"));
+
+        // The summary gives what the arrows dealt after armor, not the volley's 30
+        let dealt = result
+            .split("You got hit for ")
+            .nth(1)
+            .and_then(|rest| rest.split('\n').next())
+            .unwrap();
+        assert!(result.contains(&format!(
+            "- The Bandits hit the party for {} in all.",
+            dealt
+        )));
+
+        // and each member of the party can dodge their share
+        world.objects[LOC_BANDITS].accuracy = -100;
+        let result = world.update_state(&parse("use sword".to_string()));
+        assert!(result.contains("The Bandits loose a volley of arrows!\nYou dodged the attack"));
+        assert!(result.contains("- The Bandits missed the whole party."));
+    }
+
+    #[test]
+    fn test_defend() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].health = Some(1000);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_TROLL].min_attack = Some(20);
+        world.objects[LOC_TROLL].accuracy = 100;
+        world.objects[LOC_TROLL].critical = -100;
+        world.objects[LOC_TROLL].initiative = -100;
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.update_state(&parse("attack troll".to_string()));
+
+        // Defending halves the blows and builds up a counterattack
+        let result = world.update_state(&parse("defend".to_string()));
+        assert!(result.contains("Your next attack gets +5."));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(990));
+        let result = world.update_state(&parse("block".to_string()));
+        assert!(result.contains("Your next attack gets +10."));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(980));
+
+        // The next attack gets the bonus, and the player takes full damage again
+        let sword_attack = world.objects[sword].attack.unwrap();
+        world.update_state(&parse("use sword".to_string()));
+        assert_eq!(
+            world.objects[LOC_TROLL].health,
+            Some(100 - sword_attack - 10)
+        );
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(960));
+    }
+
+    #[test]
+    fn test_on_hit_effects() {
+        let mut world = World::new();
+        let dagger = index_of(&world, "Poisoned Dagger");
+        world.objects[dagger].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_TROLL].attack = Some(0);
+        world.objects[LOC_TROLL].dodge = -100;
+        world.objects[LOC_TROLL].resists.clear();
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.update_state(&parse("attack troll".to_string()));
+
+        // Every hit with the dagger adds another dose of poison
+        let result = world.update_state(&parse("use dagger".to_string()));
+        assert!(result.contains("The Troll suffers Poison (-2 health per turn, 3 turns)."));
+        assert!(result.contains("The Troll loses 2 health."));
+        assert_eq!(world.objects[LOC_TROLL].health, Some(90));
+        world.update_state(&parse("use dagger".to_string()));
+        assert_eq!(world.objects[LOC_TROLL].health, Some(78));
+
+        // The poison can finish the fight on its own
+        world.objects[LOC_TROLL].health = Some(3);
+        let result = world.update_state(&parse("defend".to_string()));
+        assert!(result.contains("You killed the Troll."));
+        assert!(world.combat.is_none());
+        assert!(world.flags.contains("killed:troll"));
+
+        // An enemy poisoned elsewhere isn't one the player saw die
+        world.objects[LOC_BEAR].location = Some(LOC_TAVERN);
+        world.objects[LOC_BEAR].health = Some(1);
+        world.stack_effect(LOC_BEAR, StatusEffect::poisoned(2, 3));
+        let result = world.update_state(&parse("wait".to_string()));
+        assert!(!result.contains("You killed the Bear."));
+        assert!(result.contains("Somewhere out of sight, the Bear succumbs."));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(0));
+    }
+
+    #[test]
+    fn test_combat_log() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_TROLL].min_attack = Some(20);
+        world.objects[LOC_TROLL].accuracy = 100;
+        world.objects[LOC_TROLL].critical = -100;
+        world.objects[LOC_TROLL].initiative = -100;
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        assert_eq!(
+            world.update_state(&parse("log".to_string())),
+            "You haven't been in a fight yet.\n"
+        );
+
+        // Both sides of the round end up in the log, even in the middle of the fight
+        world.update_state(&parse("attack troll".to_string()));
+        world.update_state(&parse("use sword".to_string()));
+        let troll_health = world.objects[LOC_TROLL].health.unwrap();
+        let sword_attack = world.objects[sword].attack.unwrap();
+        let result = world.update_state(&parse("log".to_string()));
+        let turn = world.turn;
+        assert_eq!(
+            result,
+            format!(
+                "[turn {}] Player hit Troll for {} ({} health left)\n\
+                 [turn {}] Troll hit Player for 20 (80 health left)\n",
+                turn - 1,
+                sword_attack,
+                troll_health,
+                turn - 1
+            )
+        );
+        assert!(world.combat.is_some());
+
+        // The log is saved with the rest of the world
+        let loaded: World = ron::from_str(&ron::to_string(&world).unwrap()).unwrap();
+        assert_eq!(loaded.combat_log, world.combat_log);
+        assert_eq!(world.do_log("1").lines().count(), 1);
+    }
+
+    #[test]
+    fn test_boss_phases() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        let thug = index_of(&world, "Thug");
+        world.objects[thug].specials.clear();
+        for guard in [index_of(&world, "Lookout"), index_of(&world, "Brute")] {
+            world.objects[guard].location = None;
+        }
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].location = Some(LOC_STRONGHOLD);
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_BANDITS].specials.clear();
+        world.objects[LOC_BANDITS].dodge = -100;
+        world.objects[LOC_BANDITS].health = Some(60);
+        world.events.clear();
+        world.update_state(&parse("attack bandits".to_string()));
+
+        // Wounding the Bandits brings in reinforcements and a new attack
+        let result = world.update_state(&parse("use sword on bandits".to_string()));
+        assert!(result.contains("more of the gang come running!"));
+        assert!(result.contains("The Thug joins the fight!"));
+        assert_eq!(world.fighting_enemies(), vec![LOC_BANDITS, thug]);
+        assert_eq!(world.objects[LOC_BANDITS].specials.len(), 1);
+        assert_eq!(world.objects[LOC_BANDITS].phase, 1);
+
+        // A phase is only entered once
+        world.objects[LOC_BANDITS].health = Some(50);
+        let result = world.update_state(&parse("use sword on bandits".to_string()));
+        assert!(!result.contains("come running"));
+
+        // Badly hurt, they run off and leave the Thug to finish the fight
+        world.objects[LOC_BANDITS].health = Some(30);
+        let result = world.update_state(&parse("use sword on bandits".to_string()));
+        assert!(result.contains("flee into the forest!"));
+        assert_eq!(world.objects[LOC_BANDITS].location, Some(LOC_FOREST));
+        assert_eq!(world.fighting_enemies(), vec![thug]);
+        assert!(world.combat.is_some());
+    }
+
+    #[test]
+    fn test_unarmed_combat() {
+        let mut world = unrolled_world();
+        let sword = index_of(&world, "Sword");
+        world.objects[LOC_CAVE].dark = false;
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_BEAR].asleep = false;
+        world.objects[LOC_BEAR].attack = Some(0);
+        world.objects[LOC_BEAR].specials.clear();
+        world.objects[LOC_BEAR].weak_to.clear();
+        world.events.clear();
+        world.update_state(&parse("attack bear".to_string()));
+
+        // Without a weapon the player still has their fists
+        let result = world.update_state(&parse("use fists".to_string()));
+        assert!(result.starts_with("You attacked the Bear with your bare fists."));
+        let health = world.objects[LOC_BEAR].health.unwrap();
+        assert!((96..=99).contains(&health));
+
+        // Attacking again in the middle of a fight carries on with the best weapon
+        let result = world.update_state(&parse("attack".to_string()));
+        assert!(result.contains("with your bare fists"));
+        let health = world.objects[LOC_BEAR].health.unwrap();
+        world.objects[sword].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("attack bear".to_string()));
+        assert!(result.starts_with("You attacked the Bear."));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(health - 20));
+    }
+
+    #[test]
+    fn test_verb_synonyms() {
+        for input in ["take sword", "grab sword", "pick up sword"] {
+            assert!(matches!(parse(input.to_string()), Command::Get(noun) if noun == "sword"));
+        }
+        assert!(matches!(parse("take off".to_string()), Command::TakeOff));
+        assert!(matches!(parse("x troll".to_string()), Command::Look(noun) if noun == "troll"));
+        assert!(
+            matches!(parse("examine troll".to_string()), Command::Look(noun) if noun == "troll")
+        );
+        for input in ["fight troll", "hit troll", "kill troll"] {
+            assert!(
+                matches!(parse(input.to_string()), Command::Attack { target, .. } if target == "troll")
+            );
+        }
+        assert!(matches!(parse("walk n".to_string()), Command::Go(noun) if noun == "north"));
+        assert!(matches!(parse("move tavern".to_string()), Command::Go(noun) if noun == "tavern"));
+
+        // Worlds can add their own on top
+        let mut world = World::new();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        let inventory = world.do_inventory();
+        assert_eq!(world.update_state(&parse("i".to_string())), inventory);
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        let result = world.update_state(&parse("slay troll".to_string()));
+        assert!(result.contains("You are attacking the Troll."));
+
+        world
+            .synonyms
+            .insert("dance".to_string(), "boogie".to_string());
+        assert!(world
+            .validate()
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("synonym 'dance'")));
+    }
+
+    #[test]
+    fn test_prepositional_commands() {
+        assert!(matches!(
+            parse("attack troll with rusty sword".to_string()),
+            Command::Attack { target, weapon: Some(weapon) }
+                if target == "troll" && weapon == "rusty sword"
+        ));
+        assert!(matches!(
+            parse("hit troll using bow".to_string()),
+            Command::Attack { weapon: Some(weapon), .. } if weapon == "bow"
+        ));
+        assert!(matches!(
+            parse("use bow at lookout".to_string()),
+            Command::Use { item, target: Some(target) } if item == "bow" && target == "lookout"
+        ));
+        assert!(matches!(
+            parse("use sword".to_string()),
+            Command::Use { target: None, .. }
+        ));
+
+        let mut world = unrolled_world();
+        let sword = index_of(&world, "Sword");
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_TROLL].initiative = -100;
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+
+        // Something that isn't a weapon doesn't start the fight
+        let apple = index_of(&world, "Apple");
+        world.objects[apple].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("attack troll with apple".to_string()));
+        assert!(result.starts_with("That is not a weapon!!"));
+        assert!(world.combat.is_none());
+
+        // The fight starts and the first blow lands in one command
+        let result = world.update_state(&parse("attack troll with sword".to_string()));
+        assert!(result.contains("You are attacking the Troll."));
+        assert!(result.contains("You attacked the Troll."));
+        assert_eq!(world.objects[LOC_TROLL].health, Some(80));
+        assert!(world.combat.is_some());
+    }
+
+    #[test]
+    fn test_get_and_drop_all() {
+        let mut world = World::new();
+        let bow = index_of(&world, "Bow");
+        let rope = index_of(&world, "Rope");
+        let apple = index_of(&world, "Apple");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+
+        // Everything lying around is picked up, one line each, except the food
+        let result = world.update_state(&parse("get all".to_string()));
+        assert!(result.contains("You pick up the Bow.\n"));
+        assert!(result.contains("You pick up the Rope.\n"));
+        assert!(!result.contains("Apple"));
+        assert_eq!(world.objects[bow].location, Some(LOC_PLAYER));
+        assert_eq!(world.objects[apple].location, Some(LOC_TAVERN));
+        let result = world.update_state(&parse("get all".to_string()));
+        assert_eq!(result, "There is nothing here you can pick up.\n");
+
+        let result = world.update_state(&parse("drop all".to_string()));
+        assert!(result.contains("You have dropped Bow.\n"));
+        assert!(result.contains("You have dropped Rope.\n"));
+        assert_eq!(world.objects[rope].location, Some(LOC_TAVERN));
+        assert_eq!(
+            world.update_state(&parse("drop all".to_string())),
+            "You currently do not have anything in your inventory.\n"
+        );
+    }
+
+    #[test]
+    fn test_pronoun_it() {
+        let mut world = World::new();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        assert_eq!(
+            world.update_state(&parse("get it".to_string())),
+            "I don't know what you mean by 'it'.\n"
+        );
+
+        // "it" is whatever the player last mentioned
+        world.update_state(&parse("get bow".to_string()));
+        assert_eq!(
+            world.update_state(&parse("look it".to_string())),
+            "A bow.\n"
+        );
+        let result = world.update_state(&parse("drop it".to_string()));
+        assert_eq!(result, "You have dropped Bow.\n");
+        let result = world.update_state(&parse("get it".to_string()));
+        assert_eq!(result, "You pick up the Bow.\n");
+
+        world.update_state(&parse("look rope".to_string()));
+        let result = world.update_state(&parse("get it".to_string()));
+        assert_eq!(result, "You pick up the Rope.\n");
+    }
+
+    #[test]
+    fn test_fuzzy_matching() {
+        assert_eq!(edit_distance("sword", "sword"), 0);
+        assert_eq!(edit_distance("swrod", "sword"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+
+        let mut world = World::new();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+
+        // One letter off a long enough word is fixed without asking
+        let result = world.update_state(&parse("get rpoe".to_string()));
+        assert_eq!(result, "You pick up the Rope.\n");
+        let result = world.update_state(&parse("inventroy".to_string()));
+        assert!(result.contains("A coil of sturdy rope."));
+
+        // Anything less certain is only suggested
+        assert_eq!(
+            world.update_state(&parse("interogat".to_string())),
+            "Did you mean 'interrogate'?\n"
+        );
+        assert!(world
+            .update_state(&parse("zzzzzz".to_string()))
+            .starts_with("Invalid command!!\n"));
+        assert_eq!(
+            world.update_state(&parse("get zzzzzz".to_string())),
+            "Invalid command!!"
+        );
+
+        // Nothing out of sight is guessed at, so typos give nothing away
+        assert_eq!(
+            world.update_state(&parse("get trol".to_string())),
+            "Invalid command!!"
+        );
+        world.objects[LOC_TROLL].location = Some(LOC_TAVERN);
+        assert_ne!(
+            world.update_state(&parse("get trol".to_string())),
+            "Invalid command!!"
+        );
+    }
+
+    #[test]
+    fn test_disambiguation_menu() {
+        let mut world = unrolled_world();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        let rusty_sword = index_of(&world, "Sword");
+        world.objects.push(Object {
+            label: vec!["Sword".to_string()],
+            description: "An iron sword.".to_string(),
+            location: Some(LOC_PLAYER),
+            item: true,
+            ..Default::default()
+        });
+        let iron_sword = world.objects.len() - 1;
+
+        // A name that fits several objects lists them to pick from
+        let result = world.update_state(&parse("look sword".to_string()));
+        assert_eq!(
+            result,
+            "Which sword do you mean?\n1. Sword (Dungeons)\n2. Sword (held)\nType a number to choose.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("2".to_string())),
+            "An iron sword.\n"
+        );
+        // The answer only counts straight after the question
+        assert!(world
+            .update_state(&parse("2".to_string()))
+            .starts_with("Invalid command!!\n"));
+
+        world.update_state(&parse("look sword".to_string()));
+        assert_eq!(
+            world.update_state(&parse("1".to_string())),
+            "A rusty sword.\n"
+        );
+        let result = world.update_state(&parse("get sword".to_string()));
+        assert!(result.starts_with("Which sword do you mean?\n"));
+        world.update_state(&parse("1".to_string()));
+        let result = world.update_state(&parse("drop sword".to_string()));
+        assert!(result.contains("1. Sword (held)\n2. Sword (held)\n"));
+        world.update_state(&parse("2".to_string()));
+        assert_eq!(world.objects[iron_sword].location, Some(LOC_DUNGEONS));
+        assert_eq!(world.objects[rusty_sword].location, Some(LOC_PLAYER));
+    }
+
+    #[test]
+    fn test_again() {
+        let mut world = unrolled_world();
+        let sword = index_of(&world, "Sword");
+        world.objects[LOC_CAVE].dark = false;
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_BEAR].asleep = false;
+        world.objects[LOC_BEAR].attack = Some(0);
+        world.objects[LOC_BEAR].specials.clear();
+        world.objects[LOC_BEAR].weak_to.clear();
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.events.clear();
+        assert!(matches!(parse("g".to_string()), Command::Again));
+        assert_eq!(
+            world.update_state(&parse("again".to_string())),
+            "There is nothing to repeat yet.\n"
+        );
+
+        // Each "again" is another round of the fight with the same weapon
+        world.update_state(&parse("attack bear".to_string()));
+        world.update_state(&parse("use sword".to_string()));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(80));
+        let result = world.update_state(&parse("again".to_string()));
+        assert!(result.starts_with("You attacked the Bear."));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(60));
+
+        // Commands the game didn't understand aren't repeated
+        world.update_state(&parse("dance".to_string()));
+        world.update_state(&parse("g".to_string()));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(40));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let mut world = World::new();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        assert_eq!(
+            world.update_state(&parse("alias".to_string())),
+            "You haven't made any aliases yet.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("alias gb = get bow".to_string())),
+            "'gb' now means 'get bow'.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("gb".to_string())),
+            "You pick up the Bow.\n"
+        );
+
+        // Words after the alias go on the end of what it stands for
+        world.update_state(&parse("alias grab2 = get".to_string()));
+        assert_eq!(
+            world.update_state(&parse("grab2 rope".to_string())),
+            "You pick up the Rope.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("alias".to_string())),
+            "gb = get bow\ngrab2 = get\n"
+        );
+
+        assert_eq!(
+            world.update_state(&parse("alias look = inventory".to_string())),
+            "'look' is already a command.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("alias gb =".to_string())),
+            "Removed the alias 'gb'.\n"
+        );
+        assert!(world
+            .update_state(&parse("gb".to_string()))
+            .starts_with("Invalid command!!\n"));
+        assert_eq!(world.turn, 2);
+    }
+
+    #[test]
+    fn test_command_help() {
+        let mut world = World::new();
+        world.events.clear();
+        let result = world.update_state(&parse("help".to_string()));
+        assert!(result.starts_with("Available commands are\n"));
+        assert!(result.contains("\n        attack <enemy name> [with <weapon name>]\n"));
+
+        // Every command has its own entry, found by any word for it
+        let result = world.update_state(&parse("help attack".to_string()));
+        assert!(result.starts_with("attack <enemy name> [with <weapon name>]\n"));
+        assert!(result.contains("Examples: attack troll"));
+        assert_eq!(world.update_state(&parse("help hit".to_string())), result);
+        assert!(world
+            .update_state(&parse("help taunt".to_string()))
+            .contains("Only works during a fight."));
+        for input in [
+            "look",
+            "go",
+            "n",
+            "get",
+            "drop",
+            "capture",
+            "loot",
+            "turn in",
+            "interrogate",
+            "journal",
+            "log",
+            "alias",
+            "buy",
+            "shop",
+            "haggle",
+            "mount",
+            "dismount",
+            "wear",
+            "equip",
+            "unequip",
+            "take off",
+            "unlock",
+            "craft",
+            "combine",
+            "brew",
+            "upgrade",
+            "place",
+            "barricade",
+            "travel",
+            "yes",
+            "no",
+            "offer",
+            "borrow",
+            "repay",
+            "rest",
+            "wait",
+            "light",
+            "train",
+            "back",
+            "again",
+            "search",
+            "inventory",
+            "map",
+            "quit",
+            "help",
+            "taunt",
+            "sneak",
+            "defend",
+            "run",
+        ] {
+            let result = world.update_state(&parse(format!("help {}", input)));
+            assert!(result.contains("Examples: "), "no help for {}", input);
+        }
+        assert_eq!(
+            world.update_state(&parse("help dance".to_string())),
+            "There is no command called 'dance'.\n"
+        );
+        assert_eq!(world.turn, 0);
+    }
+
+    #[test]
+    fn test_grammar() {
+        let tokens = tokenize("get \"poisoned dagger\" now").unwrap();
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, ["get", "poisoned dagger", "now"]);
+        assert_eq!(tokens[1].span, 4..21);
+        assert!(tokens[1].quoted);
+
+        // Articles are left out, and quoted names are never split
+        assert!(
+            matches!(parse("get the sword".to_string()), Command::Get(noun) if noun == "sword")
+        );
+        assert!(matches!(
+            parse("attack the troll with a sword".to_string()),
+            Command::Attack { target, weapon } if target == "troll" && weapon.as_deref() == Some("sword")
+        ));
+        assert!(matches!(
+            parse("use \"sword with a name\" on bear".to_string()),
+            Command::Use { item, target } if item == "sword with a name" && target.as_deref() == Some("bear")
+        ));
+
+        // Mistakes are pointed out where they were made
+        let command = parse("get \"poisoned dagger".to_string());
+        let Command::Malformed(error) = &command else {
+            panic!("expected a malformed command");
+        };
+        assert_eq!(error.span, 4..20);
+        assert_eq!(
+            error.to_string(),
+            "get \"poisoned dagger\n    ^^^^^^^^^^^^^^^^ This quote is never closed.\n"
+        );
+
+        // Words in front of a name pick out the object described by them
+        let mut world = unrolled_world();
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        world.objects.push(Object {
+            label: vec!["Sword".to_string()],
+            description: "An iron sword.".to_string(),
+            location: Some(LOC_DUNGEONS),
+            item: true,
+            ..Default::default()
+        });
+        let iron_sword = world.objects.len() - 1;
+        assert_eq!(
+            world.update_state(&parse("get the iron sword".to_string())),
+            "You pick up the Sword.\n"
+        );
+        assert_eq!(world.objects[iron_sword].location, Some(LOC_PLAYER));
+        assert_eq!(
+            world.update_state(&parse("look rusty sword".to_string())),
+            "A rusty sword.\n"
+        );
+        assert_eq!(world.update_state(&command), error.to_string());
+        assert_eq!(world.turn, 2);
+    }
+
+    #[test]
+    fn test_talk() {
+        let mut world = World::new();
+        let lookout = index_of(&world, "Lookout");
+        let brute = index_of(&world, "Brute");
+        world.objects[LOC_BEAR].location = None;
+        world.objects[LOC_BANDITS].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        assert_eq!(
+            world.update_state(&parse("talk to soldier".to_string())),
+            "The Soldier says: \"Keep your guard up and your blade sharp. For a few coins I'll show you how.\"\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("speak rope".to_string())),
+            "The Rope has nothing to say to you.\n"
+        );
+
+        // An enemy talked down stays out of the fight
+        world.objects[LOC_PLAYER].location = Some(LOC_STRONGHOLD);
+        world.objects[lookout].talk.as_mut().unwrap().calm_chance = 100;
+        let result = world.update_state(&parse("talk to the lookout".to_string()));
+        assert!(result.contains("You talk the Lookout down."));
+        assert!(world.objects[lookout].calmed);
+        assert!(world
+            .update_state(&parse("look".to_string()))
+            .contains("(calm)"));
+        let result = world.update_state(&parse("talk brute".to_string()));
+        assert_eq!(result, "The Brute says: \"Brute smash.\"\n");
+        world.update_state(&parse("attack brute".to_string()));
+        assert_eq!(world.combat.as_ref().unwrap().enemies, vec![brute]);
+    }
+
+    #[test]
+    fn test_use_outside_combat() {
+        let mut world = World::new();
+        let key = index_of(&world, "Iron Key");
+        let torch = index_of(&world, "Torch");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.chapters.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        world.objects[LOC_PLAYER].health = Some(50);
+        world.objects[key].location = Some(LOC_PLAYER);
+        world.objects[torch].location = Some(LOC_PLAYER);
+
+        // Keys open the lock they fit here, or the one they are used on
+        assert_eq!(
+            world.update_state(&parse("use iron key".to_string())),
+            "You unlock the way north with the Iron Key.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("use iron key on north".to_string())),
+            "The way north is not locked.\n"
+        );
+
+        assert!(world
+            .update_state(&parse("use torch".to_string()))
+            .starts_with("You light the Torch."));
+        world.update_state(&parse("use potion".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(70));
+        assert_eq!(
+            world.update_state(&parse("use tracks".to_string())),
+            "You can't think of a way to use the Tracks here.\n"
+        );
+
+        // Weapons used on an enemy start a fight
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        world.update_state(&parse("get sword".to_string()));
+        assert_eq!(
+            world.update_state(&parse("use sword".to_string())),
+            "You are not fighting anything.\n"
+        );
+        world.update_state(&parse("use sword on troll".to_string()));
+        assert_eq!(world.combat.as_ref().unwrap().enemy, LOC_TROLL);
+    }
+
+    #[test]
+    fn test_equipment() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        let bones = index_of(&world, "Bones");
+        let charm = index_of(&world, "Lucky Charm");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_TROLL].initiative = -100;
+        world.objects[LOC_TROLL].attack = Some(0);
+        for item in [sword, bones, charm] {
+            world.objects[item].location = Some(LOC_PLAYER);
+        }
+
+        assert_eq!(
+            world.update_state(&parse("equip bones".to_string())),
+            "You equip the Bones as your weapon.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("equip lucky charm".to_string())),
+            "You equip the Lucky Charm as your trinket.\n"
+        );
+        assert_eq!(world.equipped(Slot::Trinket), Some(charm));
+        assert_eq!(
+            world.update_state(&parse("equip torch".to_string())),
+            "You can't equip the Torch.\n"
+        );
+        let result = world.update_state(&parse("inventory".to_string()));
+        assert!(result.contains("You are wielding the Bones.\n"));
+        assert!(result.contains("You have the Lucky Charm on as a trinket.\n"));
+
+        // The equipped weapon is used over the sword that hits harder
+        world.update_state(&parse("attack troll".to_string()));
+        world.update_state(&parse("attack".to_string()));
+        assert!(100 - world.objects[LOC_TROLL].health.unwrap() <= 5);
+        world.update_state(&parse("use".to_string()));
+        assert!(100 - world.objects[LOC_TROLL].health.unwrap() <= 10);
+
+        world.combat = None;
+        assert_eq!(
+            world.update_state(&parse("unequip weapon".to_string())),
+            "You put away the Bones.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("unequip weapon".to_string())),
+            "You don't have a weapon equipped.\n"
+        );
+        world.update_state(&parse("drop lucky charm".to_string()));
+        assert_eq!(world.trinket, None);
+    }
+
+    #[test]
+    fn test_carrying_capacity() {
+        let mut world = World::new();
+        let planks = index_of(&world, "Planks");
+        let chainmail = index_of(&world, "Chainmail");
+        let spear = index_of(&world, "Spear");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].strength = Some(10);
+        for item in [planks, chainmail, spear] {
+            world.objects[item].location = Some(LOC_FOREST);
+            world.objects[item].price = None;
+        }
+        assert_eq!(world.capacity(), Some(30));
+
+        let result = world.update_state(&parse("get planks".to_string()));
+        assert!(!result.contains("heavy"));
+        let result = world.update_state(&parse("get chainmail".to_string()));
+        assert!(result.ends_with("Your pack is getting heavy: 25/30 weight.\n"));
+        assert_eq!(
+            world.update_state(&parse("get spear".to_string())),
+            "The Spear is too heavy to carry as well. You are carrying 25/30 weight and it weighs 6.\n"
+        );
+        assert_eq!(world.objects[spear].location, Some(LOC_FOREST));
+        assert!(world
+            .update_state(&parse("inventory".to_string()))
+            .contains("You are carrying 25/30 weight.\n"));
+
+        // Without a strength there is no limit
+        world.objects[LOC_PLAYER].strength = None;
+        world.update_state(&parse("get spear".to_string()));
+        assert_eq!(world.objects[spear].location, Some(LOC_PLAYER));
+        assert_eq!(world.load(), 31);
+    }
+
+    #[test]
+    fn test_item_stacks() {
+        let mut world = World::new();
+        let apple = index_of(&world, "Apple");
+        let held = |world: &World| {
+            world
+                .objects
+                .iter()
+                .position(|object| {
+                    object.label[0] == "Apple" && object.location == Some(LOC_PLAYER)
+                })
+                .unwrap()
+        };
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        assert_eq!(world.count(apple), 5);
+
+        assert_eq!(
+            world.update_state(&parse("get 2 apples".to_string())),
+            "You pick up the Apple (x2).\n"
+        );
+        assert_eq!(world.count(apple), 3);
+        assert_eq!(world.count(held(&world)), 2);
+        assert!(world
+            .update_state(&parse("inventory".to_string()))
+            .contains("(x2)\n"));
+        assert_eq!(
+            world.update_state(&parse("get 9 apples".to_string())),
+            "There is only 3 of the Apple.\n"
+        );
+
+        // Picking up the rest joins the stack already carried
+        world.update_state(&parse("get apples".to_string()));
+        assert_eq!(world.count(held(&world)), 5);
+
+        // Splitting and merging the stack over and over doesn't grow the world
+        let objects = world.objects.len();
+        for _ in 0..50 {
+            world.update_state(&parse("drop 1 apple".to_string()));
+            world.update_state(&parse("get 1 apple".to_string()));
+        }
+        assert!(world.objects.len() <= objects + 1);
+        assert_eq!(world.count(held(&world)), 5);
+        assert_eq!(
+            world
+                .objects
+                .iter()
+                .filter(|object| object.location == Some(LOC_PLAYER))
+                .filter(|object| object.label[0] == "Apple")
+                .count(),
+            1
+        );
+
+        assert_eq!(
+            world.update_state(&parse("drop 4 apples".to_string())),
+            "You have dropped Apple (x4).\n"
+        );
+        assert_eq!(world.count(held(&world)), 1);
+        assert_eq!(
+            world.update_state(&parse("drop 0 apple".to_string())),
+            "You can't move none of something.\n"
+        );
+        world.update_state(&parse("drop apple".to_string()));
+        let on_floor: Vec<usize> = (0..world.objects.len())
+            .filter(|index| {
+                world.objects[*index].label[0] == "Apple"
+                    && world.objects[*index].location == Some(LOC_TAVERN)
+            })
+            .collect();
+        assert_eq!(on_floor.len(), 1);
+        assert_eq!(world.count(on_floor[0]), 5);
+    }
+
+    #[test]
+    fn test_containers() {
+        let mut world = World::new();
+        let chest = index_of(&world, "Chest");
+        let elixir = index_of(&world, "Elixir");
+        let bag = index_of(&world, "Bag");
+        let sword = index_of(&world, "Sword");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[chest].location = Some(LOC_FOREST);
+
+        assert_eq!(
+            world.update_state(&parse("get elixir from chest".to_string())),
+            "The Chest is closed. Try <open chest>.\n"
+        );
+        assert!(!world
+            .update_state(&parse("look".to_string()))
+            .contains("Elixir"));
+        assert_eq!(
+            world.update_state(&parse("open chest".to_string())),
+            format!(
+                "You open the Chest.\n    in the Chest: {}\n",
+                world.objects[elixir].description
+            )
+        );
+        assert_eq!(
+            world.update_state(&parse("open chest".to_string())),
+            "The Chest is already open.\n"
+        );
+        assert!(world
+            .update_state(&parse("look".to_string()))
+            .contains("    in the Chest: "));
+
+        assert_eq!(
+            world.update_state(&parse("get elixir from chest".to_string())),
+            "You got Elixir from Chest.\n"
+        );
+        assert_eq!(world.objects[elixir].location, Some(LOC_PLAYER));
+        assert_eq!(
+            world.update_state(&parse("put elixir".to_string())),
+            "Put it in what? Try <put <item> in <container>>.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("put elixir in chest".to_string())),
+            "You put Elixir in Chest.\n"
+        );
+        assert_eq!(world.objects[elixir].location, Some(chest));
+
+        // What is in a carried bag still weighs on the player
+        world.objects[bag].location = Some(LOC_PLAYER);
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.update_state(&parse("put sword into bag".to_string()));
+        assert_eq!(world.objects[sword].location, Some(bag));
+        assert_eq!(world.load(), 6);
+        assert!(world
+            .update_state(&parse("inventory".to_string()))
+            .contains("    in the Bag: "));
+        assert_eq!(
+            world.update_state(&parse("put bag in bag".to_string())),
+            "The Bag won't fit inside itself.\n"
+        );
+        // Even with the pack full, the sword is already being carried
+        world.objects[LOC_PLAYER].strength = Some(2);
+        assert_eq!(world.capacity(), Some(world.load()));
+        assert_eq!(
+            world.update_state(&parse("get sword from bag".to_string())),
+            "You got Sword from Bag.\nYour pack is getting heavy: 6/6 weight.\n"
+        );
+        assert_eq!(
+            world.update_state(&parse("open sword".to_string())),
+            "The Sword can't hold anything.\n"
+        );
+    }
+
+    #[test]
+    fn test_eat_and_drink() {
+        let mut world = World::new();
+        let apple = index_of(&world, "Apple");
+        let sword = index_of(&world, "Sword");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_PLAYER].health = Some(50);
+        world.objects[sword].location = Some(LOC_PLAYER);
+
+        // Picking food up keeps it for later
+        assert_eq!(
+            world.update_state(&parse("get 2 apples".to_string())),
+            "You pick up the Apple (x2).\n"
+        );
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(50));
+
+        // The apples carried are eaten before the ones on the table
+        assert_eq!(
+            world.update_state(&parse("eat apple".to_string())),
+            "You have consumed the item. Your health has increased to 60\n"
+        );
+        assert_eq!(world.count(apple), 3);
+        let held = (0..world.objects.len())
+            .find(|index| {
+                world.objects[*index].label[0] == "Apple"
+                    && world.objects[*index].location == Some(LOC_PLAYER)
+            })
+            .unwrap();
+        assert_eq!(world.count(held), 1);
+        world.update_state(&parse("consume apple".to_string()));
+        assert_eq!(world.objects[held].location, None);
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(70));
+
+        assert_eq!(
+            world.update_state(&parse("drink sword".to_string())),
+            "You can't eat or drink the Sword.\n"
+        );
+        assert!(
+            matches!(parse("drink potion".to_string()), Command::Eat(noun) if noun == "potion")
+        );
+    }
+
+    #[test]
+    fn test_gear_rarity() {
+        let mut world = unrolled_world();
+        let sword = index_of(&world, "Sword");
+        let dagger = index_of(&world, "Poisoned Dagger");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        assert_eq!(world.objects[sword].quality, None);
+        assert_eq!(world.objects[sword].attack, Some(20));
+
+        world.roll_gear();
+        let attack = world.objects[sword].attack.unwrap();
+        assert!((16..=24).contains(&attack));
+        let quality = match attack {
+            16..=18 => Quality::Rusty,
+            19..=21 => Quality::Standard,
+            _ => Quality::Fine,
+        };
+        assert_eq!(world.objects[sword].quality, Some(quality));
+        let name = match quality.adjective() {
+            Some(adjective) => format!("{} Sword (+{})", adjective, attack),
+            None => format!("Sword (+{})", attack),
+        };
+        assert_eq!(world.gear_name(sword), name);
+
+        // Gear is only rolled once
+        world.roll_gear();
+        assert_eq!(world.objects[sword].attack, Some(attack));
+
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[dagger].location = Some(LOC_PLAYER);
+        assert!(world
+            .update_state(&parse("inventory".to_string()))
+            .contains(&format!("A rusty sword. - {}\n", name)));
+        let dagger_attack = world.objects[dagger].attack.unwrap();
+        assert_eq!(
+            world.update_state(&parse("look dagger".to_string())),
+            format!(
+                "{}\n*Poisoned Dagger (+{})*, rare.\n",
+                world.objects[dagger].description, dagger_attack
+            )
+        );
+        if let Some(adjective) = quality.adjective() {
+            assert!(world
+                .update_state(&parse(format!("look {} sword", adjective)))
+                .starts_with("A rusty sword.\n"));
+        }
+
+        // Every new game comes with its gear already rolled, wherever its world is from
+        let loaded = World::read_from_file("game_file.ron").unwrap();
+        assert!(loaded.objects[sword].quality.is_some());
+        assert!(World::new().objects[sword].quality.is_some());
+        let host = SessionHost::new(1);
+        let id = host.open(None).unwrap();
+        let mut reply = String::new();
+        for input in ["n", "e", "look sword"] {
+            let command = parse(input.to_string());
+            reply = host.submit(id, command).unwrap().recv().unwrap().output;
+        }
+        assert!(reply.starts_with("A rusty sword.\n"));
+        assert!(reply.contains("Sword (+"));
+    }
+
+    #[test]
+    fn test_loot_drops() {
+        let mut world = World::new();
+        let sword = index_of(&world, "Sword");
+        let pelt = index_of(&world, "Bear Pelt");
+        let claw = index_of(&world, "Bear Claw");
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[LOC_BEAR].location = Some(LOC_FOREST);
+        world.objects[LOC_BEAR].asleep = false;
+        world.objects[LOC_BEAR].health = Some(1);
+        world.objects[LOC_BEAR].initiative = -100;
+        world.objects[LOC_BEAR].specials.clear();
+        // The claw is left to chance, so keep it out of this fight
+        world.objects[LOC_BEAR].loot[2].chance = 0;
+        let gold = world.objects[LOC_PLAYER].gold;
+        assert_eq!(world.objects[pelt].location, None);
+
+        let result = world.update_state(&parse("attack bear with sword".to_string()));
+        assert!(result.contains(
+            "You killed the Bear.\nYou find 10 gold on the Bear.\nThe Bear drops the Bear Pelt.\n"
+        ));
+        assert_eq!(world.objects[LOC_PLAYER].gold, gold + 10);
+        assert_eq!(world.objects[pelt].location, Some(LOC_FOREST));
+        assert_eq!(world.objects[claw].location, None);
+        world.update_state(&parse("get bear pelt".to_string()));
+        assert_eq!(world.objects[pelt].location, Some(LOC_PLAYER));
+    }
+
+    #[test]
+    fn test_buff_consumables() {
+        let mut world = World::new();
+        let quicksilver = index_of(&world, "Quicksilver Draught");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        world.objects[LOC_PLAYER].gold = 100;
+        let attack = world.attack_bonus(LOC_PLAYER);
+
+        // Buffs can be taken at full health
+        world.update_state(&parse("buy strength tonic".to_string()));
+        assert_eq!(
+            world.update_state(&parse("drink strength tonic".to_string())),
+            "You have consumed the item.\nYou are under Strength Tonic (+8 attack, 5 turns).\n"
+        );
+        assert_eq!(world.attack_bonus(LOC_PLAYER), attack + 8);
+
+        world.objects[quicksilver].price = None;
+        world.objects[quicksilver].location = Some(LOC_PLAYER);
+        world.update_state(&parse("drink quicksilver draught".to_string()));
+        assert_eq!(world.dodge_bonus(LOC_PLAYER), 20);
+
+        // Both wear off after their turns are up
+        for _ in 0..5 {
+            world.update_state(&parse("look".to_string()));
+        }
+        assert_eq!(world.attack_bonus(LOC_PLAYER), attack);
+        assert_eq!(world.dodge_bonus(LOC_PLAYER), 0);
+    }
+
+    #[test]
+    fn test_throwing() {
+        let mut world = World::new();
+        let bones = index_of(&world, "Bones");
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[bones].location = Some(LOC_PLAYER);
+        world.objects[LOC_BEAR].location = Some(LOC_FOREST);
+        world.objects[LOC_BEAR].asleep = false;
+        world.objects[LOC_BEAR].health = Some(50);
+        world.objects[LOC_BEAR].attack = Some(0);
+        world.objects[LOC_BEAR].initiative = -100;
+        world.objects[LOC_BEAR].specials.clear();
+        assert_eq!(world.throw_damage(bones), 7);
+
+        let result = world.update_state(&parse("throw bones".to_string()));
+        assert_eq!(result, "Throw it at what? Try <throw <item> at <enemy>>.\n");
+
+        // Throwing starts the fight and leaves the bones where the bear stands
+        let result = world.update_state(&parse("throw the bones at bear".to_string()));
+        assert!(result.contains("You hit the Bear with the Bones."));
+        assert!(result.contains("You are attacking the Bear."));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(43));
+        assert_eq!(world.objects[bones].location, Some(LOC_FOREST));
+        assert!(world.combat.is_some());
+
+        let result = world.update_state(&parse("throw bones".to_string()));
+        assert!(result.contains("You are not holding any bones."));
+
+        // In a fight the throw goes at the enemy being fought
+        world.combat = None;
+        world.update_state(&parse("get bones".to_string()));
+        world.update_state(&parse("attack bear".to_string()));
+        world.update_state(&parse("throw bones".to_string()));
+        assert_eq!(world.objects[LOC_BEAR].health, Some(36));
+        assert_eq!(world.objects[bones].location, Some(LOC_FOREST));
+    }
+
+    #[test]
+    fn test_key_items() {
+        let mut world = World::new();
+        let key = index_of(&world, "Iron Key");
+        let rope = index_of(&world, "Rope");
+        world.events.clear();
+        world.chapters.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        world.objects[key].location = Some(LOC_PLAYER);
+        world.objects[rope].location = Some(LOC_PLAYER);
+        assert!(world.objects[key].key_item);
+
+        let result = world.update_state(&parse("drop iron key".to_string()));
+        assert_eq!(
+            result,
+            "You can't part with the Iron Key. You will need it later.\n"
+        );
+        let result = world.update_state(&parse("eat iron key".to_string()));
+        assert!(result.contains("You can't part with the Iron Key."));
+        assert_eq!(world.objects[key].location, Some(LOC_PLAYER));
+
+        // Dropping everything leaves the key in the player's hands
+        let result = world.update_state(&parse("drop all".to_string()));
+        assert!(result.contains("You hold on to the Iron Key.\n"));
+        assert_eq!(world.objects[key].location, Some(LOC_PLAYER));
+        assert_eq!(world.objects[rope].location, Some(LOC_FOREST));
+
+        // It can't be left behind in a chest either
+        let chest = index_of(&world, "Chest");
+        world.objects[chest].location = Some(LOC_FOREST);
+        world.update_state(&parse("open chest".to_string()));
+        let result = world.update_state(&parse("put iron key in chest".to_string()));
+        assert_eq!(
+            result,
+            "You can't part with the Iron Key. You will need it later.\n"
+        );
+        assert_eq!(world.objects[key].location, Some(LOC_PLAYER));
+    }
+
+    #[test]
+    fn test_compare_weapons() {
+        let mut world = World::new();
+        let bow = index_of(&world, "Bow");
+        let spear = index_of(&world, "Spear");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        world.objects[bow].location = Some(LOC_PLAYER);
+        world.objects[spear].location = Some(LOC_PLAYER);
+        let rope = index_of(&world, "Rope");
+        world.objects[rope].location = Some(LOC_PLAYER);
+
+        let result = world.update_state(&parse("compare bow with spear".to_string()));
+        assert!(result.starts_with(&format!(
+            "{:<12}{:<24}{}\n",
+            "",
+            world.gear_name(bow),
+            world.gear_name(spear)
+        )));
+        assert!(result.contains(&format!("{:<12}{:<24}pierce\n", "Damage", "pierce")));
+        assert!(result.contains(&format!(
+            "{:<12}{:<24}{:+}\n",
+            "Accuracy",
+            format!("{:+}", world.objects[bow].accuracy),
+            world.objects[spear].accuracy
+        )));
+        assert_eq!(
+            result,
+            world.update_state(&parse("compare bow spear".to_string()))
+        );
+
+        // One weapon is compared with the one the player has ready
+        world.wielding = Some(spear);
+        let result = world.update_state(&parse("compare bow".to_string()));
+        assert!(result.starts_with(&format!(
+            "{:<12}{:<24}{}\n",
+            "",
+            world.gear_name(bow),
+            world.gear_name(spear)
+        )));
+        let result = world.update_state(&parse("compare spear".to_string()));
+        assert!(result.starts_with("You have no other weapon to compare the Spear with."));
+        let result = world.update_state(&parse("compare rope with bow".to_string()));
+        assert!(result.contains("not a weapon"));
+
+        // A weapon with a two-word name is one weapon, not two
+        let sword = index_of(&world, "Sword");
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[sword].quality = Some(Quality::Fine);
+        for name in ["rusty sword", "fine sword"] {
+            let result = world.update_state(&parse(format!("compare {}", name)));
+            assert!(result.starts_with(&format!(
+                "{:<12}{:<24}{}\n",
+                "",
+                world.gear_name(sword),
+                world.gear_name(spear)
+            )));
+        }
+    }
+
+    #[test]
+    fn test_repair_gear() {
+        let mut world = unrolled_world();
+        let sword = index_of(&world, "Sword");
+        let whetstone = index_of(&world, "Whetstone");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_PLAYER].gold = 10;
+        world.objects[sword].location = Some(LOC_PLAYER);
+        world.objects[sword].durability = Some(Durability {
+            current: 1,
+            max: 30,
+        });
+        world.objects[LOC_TROLL].location = Some(LOC_FOREST);
+        world.objects[LOC_TROLL].initiative = -100;
+        world.objects[LOC_TROLL].specials.clear();
+
+        // The blow that wears the sword out still lands in full
+        let result = world.update_state(&parse("attack troll with sword".to_string()));
+        assert!(result.contains("Your Sword has worn out. Try <repair sword>."));
+        assert!(world.objects[sword].is_worn_out());
+        assert_eq!(world.objects[sword].damage_range(), (10, 10));
+        world.combat = None;
+        world.objects[LOC_TROLL].location = None;
+        assert!(world
+            .update_state(&parse("look sword".to_string()))
+            .ends_with("Durability: 0/30 (worn out).\n"));
+
+        let result = world.update_state(&parse("repair sword".to_string()));
+        assert!(result.starts_with("There is nothing here to repair the Sword with."));
+
+        // The fireplace costs a coin for every point
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        let result = world.update_state(&parse("repair sword".to_string()));
+        assert_eq!(
+            result,
+            "Repairing the Sword costs 30 gold and you have 10.\n"
+        );
+
+        // A whetstone does it anywhere but is used up
+        world.objects[whetstone].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        let result = world.update_state(&parse("repair sword".to_string()));
+        assert!(result.ends_with("It is as good as new.\n"));
+        assert_eq!(
+            world.objects[sword].durability,
+            Some(Durability {
+                current: 30,
+                max: 30
+            })
+        );
+        assert_eq!(world.objects[whetstone].quantity, Some(2));
+        let result = world.update_state(&parse("repair sword".to_string()));
+        assert_eq!(result, "The Sword is already in perfect condition.\n");
+    }
+
+    #[test]
+    fn test_npc_dialogue() {
+        let mut world = World::new();
+        let hermit = index_of(&world, "Hermit");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        assert!(world.objects[hermit].npc);
+
+        // The hermit's lines come one after another, then start over
+        let talk = world.objects[hermit].talk.clone().unwrap();
+        let mut said = vec![talk.text.clone()];
+        said.extend(talk.lines.clone());
+        said.push(talk.text.clone());
+        for line in said {
+            assert_eq!(
+                world.update_state(&parse("talk to hermit".to_string())),
+                format!("The Hermit says: \"{}\"\n", line)
+            );
+        }
+
+        let result = world.update_state(&parse("attack hermit".to_string()));
+        assert_eq!(result, "The Hermit has done nothing to deserve that.\n");
+        assert!(world.combat.is_none());
+    }
+
+    #[test]
+    fn test_dialogue_tree() {
+        let mut world = World::new();
+        let key = index_of(&world, "Iron Key");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        let gold = world.objects[LOC_PLAYER].gold;
+
+        let result = world.update_state(&parse("talk to ghost".to_string()));
+        assert!(result.contains("1. Who are you?\n2. I'm going after the bandits.\n3. Goodbye.\n"));
+        assert!(result.ends_with("Type a number to answer.\n"));
+
+        // Answering gives the ghost's savings and a note in the journal
+        let result = world.update_state(&parse("2".to_string()));
+        assert!(result.starts_with("You say: \"I'm going after the bandits.\"\n"));
+        let result = world.update_state(&parse("1".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].gold, gold + 15);
+        assert!(world.flags.contains("ghost_favour"));
+        assert!(world
+            .journal
+            .last()
+            .unwrap()
+            .contains("asked you to drive the bandits out"));
+        // Back at the start, the favour isn't offered again
+        assert!(result.contains("1. Who are you?\n2. Goodbye.\n"));
+        world.update_state(&parse("2".to_string()));
+        assert!(world.conversation.is_none());
+
+        // Carrying the key opens up another answer
+        world.objects[key].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("talk ghost".to_string()));
+        assert!(result.contains("2. I have the key to their Stronghold.\n"));
+        let result = world.update_state(&parse("2".to_string()));
+        assert!(result.contains("So you took it off the Troll."));
+        assert!(world.conversation.is_none());
+        assert_eq!(world.objects[LOC_PLAYER].gold, gold + 15);
+    }
+
+    #[test]
+    fn test_quests() {
+        let mut world = World::new();
+        let ring = index_of(&world, "Ring");
+        world.objects[LOC_BEAR].location = None;
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        assert_eq!(world.quests[0].state, QuestState::NotStarted);
+
+        world.update_state(&parse("talk to ghost".to_string()));
+        world.update_state(&parse("2".to_string()));
+        world.update_state(&parse("1".to_string()));
+        assert_eq!(world.quests[0].state, QuestState::Accepted);
+        assert!(world
+            .update_state(&parse("journal".to_string()))
+            .ends_with("Quests:\n- Clear the bandits from the Stronghold (for the Ghost, open)\n"));
+
+        // Nothing is handed out until the objective is met
+        let result = world.update_state(&parse("talk to ghost".to_string()));
+        assert!(!result.contains("thanks you"));
+        world.flags.insert("defeated:bandits".to_string());
+        let gold = world.objects[LOC_PLAYER].gold;
+        let result = world.update_state(&parse("talk to ghost".to_string()));
+        assert!(result.starts_with(
+            "The Ghost thanks you for completing \"Clear the bandits from the Stronghold\".\nYou receive 50 gold, the Ring, 100 experience.\n"
+        ));
+        assert!(result.contains("The bandits are gone."));
+        assert_eq!(world.objects[LOC_PLAYER].gold, gold + 50);
+        assert_eq!(world.objects[ring].location, Some(LOC_PLAYER));
+        assert_eq!(world.quests[0].state, QuestState::Completed);
+        assert!(world
+            .update_state(&parse("journal".to_string()))
+            .ends_with("(for the Ghost, done)\nExperience: 100\n"));
+
+        // The reward is only handed out once
+        let result = world.update_state(&parse("talk to ghost".to_string()));
+        assert!(!result.contains("thanks you"));
+        assert_eq!(world.objects[LOC_PLAYER].gold, gold + 50);
+    }
+
+    #[test]
+    fn test_trader() {
+        let mut world = World::new();
+        let trader = index_of(&world, "Trader");
+        let knife = index_of(&world, "Throwing Knife");
+        let bow = index_of(&world, "Bow");
+        let key = index_of(&world, "Iron Key");
+        let rope = index_of(&world, "Rope");
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_PLAYER].gold = 10;
+
+        let result = world.update_state(&parse("look trader".to_string()));
+        assert!(result.contains("The Trader sells:\n    Throwing Knife (x3) (6 gold)\n"));
+
+        let result = world.update_state(&parse("buy throwing knife".to_string()));
+        assert_eq!(
+            result,
+            "You buy the Throwing Knife from the Trader for 6 gold. You have 4 gold left.\n"
+        );
+        assert_eq!(world.objects[trader].gold, 66);
+        assert_eq!(world.count(knife), 2);
+        assert_eq!(world.objects[knife].location, Some(trader));
+        assert_eq!(world.objects[knife].price, Some(6));
+        let result = world.update_state(&parse("buy throwing knife".to_string()));
+        assert_eq!(
+            result,
+            "You can't afford the Throwing Knife. It costs 6 gold and you have 4.\n"
+        );
+
+        // Selling pays half of what something is worth and puts it back on sale
+        world.objects[bow].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("sell bow".to_string()));
+        assert_eq!(
+            result,
+            "You sell the Bow to the Trader for 6 gold. You have 10 gold now.\n"
+        );
+        assert_eq!(world.objects[bow].location, Some(trader));
+        assert_eq!(world.objects[bow].price, Some(12));
+        assert_eq!(world.objects[trader].gold, 60);
+
+        world.objects[rope].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("sell rope".to_string()));
+        assert_eq!(result, "The Trader has no use for the Rope.\n");
+        world.objects[key].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("sell iron key".to_string()));
+        assert!(result.contains("You can't part with the Iron Key."));
+        world.objects[trader].gold = 0;
+        let result = world.update_state(&parse("sell throwing knife".to_string()));
+        assert!(result.contains("The Trader can't afford the Throwing Knife."));
+
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        let result = world.update_state(&parse("sell rope".to_string()));
+        assert_eq!(result, "There is nobody here to buy the rope.\n");
+    }
+
+    #[test]
+    fn test_recruit_companion() {
+        let mut world = World::new();
+        let hermit = index_of(&world, "Hermit");
+        world.events.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+
+        let result = world.update_state(&parse("dismiss hermit".to_string()));
+        assert_eq!(result, "The Hermit isn't travelling with you.\n");
+        let result = world.update_state(&parse("recruit hermit".to_string()));
+        assert_eq!(
+            result,
+            "The Hermit joins you. They will follow you wherever you go.\n"
+        );
+        assert_eq!(world.party(), vec![LOC_PLAYER, hermit]);
+
+        // The hermit follows the player and shows up when they look around
+        world.update_state(&parse("go north".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+        assert_eq!(world.objects[hermit].location, Some(LOC_TAVERN));
+        let result = world.update_state(&parse("look".to_string()));
+        assert!(
+            result.contains("picking through the undergrowth for herbs. (travelling with you)\n")
+        );
+
+        // The hermit strikes every round of a fight
+        world.objects[hermit].accuracy = 100;
+        world.objects[hermit].critical = -100;
+        world.objects[LOC_TROLL].location = Some(LOC_TAVERN);
+        world.objects[LOC_TROLL].initiative = -100;
+        world.objects[LOC_TROLL].health = Some(1);
+        world.objects[LOC_TROLL].specials.clear();
+        world.update_state(&parse("attack troll".to_string()));
+        let result = world.update_state(&parse("defend".to_string()));
+        assert!(result.contains("The Hermit attacks the Troll."));
+        assert!(world.flags.contains("defeated:troll"));
+        assert!(world.combat.is_none());
+
+        let result = world.update_state(&parse("dismiss hermit".to_string()));
+        assert!(result.starts_with("The Hermit heads back to the Forest.\n"));
+        assert_eq!(world.objects[hermit].location, Some(LOC_FOREST));
+        assert_eq!(world.party(), vec![LOC_PLAYER]);
+    }
+
+    #[test]
+    fn test_select_target() {
+        let mut world = World::new();
+        let hermit = index_of(&world, "Hermit");
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        world.objects[hermit].location = Some(LOC_FOREST);
+        world.objects[hermit].companion.as_mut().unwrap().joined = true;
+        world.objects[LOC_PLAYER].health = Some(100);
+        world.objects[hermit].health = Some(30);
+
+        // A random pick only ever lands on someone in the party
+        world.objects[LOC_TROLL].targeting = Targeting::Random;
+        for _ in 0..20 {
+            assert!(world.party().contains(&world.select_target(LOC_TROLL)));
+        }
+
+        // The weakest member of the party draws the blows
+        world.objects[LOC_TROLL].targeting = Targeting::LowestHealth;
+        assert_eq!(world.select_target(LOC_TROLL), hermit);
+        world.objects[hermit].health = Some(300);
+        assert_eq!(world.select_target(LOC_TROLL), LOC_PLAYER);
+
+        // Whoever has done the enemy the most harm draws the blows
+        world.objects[LOC_TROLL].targeting = Targeting::HighestThreat;
+        world.add_threat(LOC_TROLL, LOC_PLAYER, 5);
+        world.add_threat(LOC_TROLL, hermit, 1);
+        assert_eq!(world.select_target(LOC_TROLL), LOC_PLAYER);
+        world.add_threat(LOC_TROLL, hermit, 10);
+        assert_eq!(world.select_target(LOC_TROLL), hermit);
+
+        // Nobody standing is left but the player
+        world.objects[hermit].health = Some(0);
+        assert_eq!(world.select_target(LOC_TROLL), LOC_PLAYER);
+        world.objects[LOC_TROLL].targeting = Targeting::Random;
+        assert_eq!(world.select_target(LOC_TROLL), LOC_PLAYER);
+    }
+
+    #[test]
+    fn test_taunt_and_sneak() {
+        let mut world = World::new();
+        let hermit = index_of(&world, "Hermit");
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[hermit].location = Some(LOC_TAVERN);
+        world.objects[hermit].companion.as_mut().unwrap().joined = true;
+        world.objects[hermit].accuracy = -100;
+        world.objects[LOC_PLAYER].accuracy = -100;
+        world.objects[LOC_TROLL].location = Some(LOC_TAVERN);
+        world.objects[LOC_TROLL].attack = Some(0);
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_TROLL].targeting = Targeting::HighestThreat;
+        world.update_state(&parse("attack troll".to_string()));
+        world.objects[LOC_TROLL].threat.clear();
+        world.add_threat(LOC_TROLL, hermit, 30);
+        assert_eq!(world.select_target(LOC_TROLL), hermit);
+
+        // Taunting draws the troll's eye away from the hermit
+        let result = world.update_state(&parse("taunt".to_string()));
+        assert!(result.starts_with("You taunt the Troll. It only has eyes for you now."));
+        assert_eq!(world.threat(LOC_TROLL, LOC_PLAYER), 25);
+        world.update_state(&parse("taunt".to_string()));
+        assert_eq!(world.threat(LOC_TROLL, LOC_PLAYER), 50);
+        assert_eq!(world.select_target(LOC_TROLL), LOC_PLAYER);
+
+        // Slipping out of sight halves it, and the hermit is the target again
+        world.objects[LOC_PLAYER].agility = Some(40);
+        let result = world.update_state(&parse("sneak".to_string()));
+        assert!(result.starts_with("You slip out of the Troll's sight."));
+        assert_eq!(world.threat(LOC_TROLL, LOC_PLAYER), 25);
+        assert_eq!(world.select_target(LOC_TROLL), hermit);
+
+        // Clumsy, noisy players can't get away
+        world.update_state(&parse("taunt".to_string()));
+        world.objects[LOC_PLAYER].agility = Some(0);
+        for label in ["Sword", "Spear", "Bow"] {
+            let index = index_of(&world, label);
+            world.objects[index].location = Some(LOC_PLAYER);
+            world.objects[index].weight_class = WeightClass::Heavy;
+        }
+        let result = world.update_state(&parse("sneak".to_string()));
+        assert!(result.starts_with("Your gear rattles and the Troll keeps its eyes on you."));
+        assert_eq!(world.threat(LOC_TROLL, LOC_PLAYER), 50);
+        assert_eq!(world.select_target(LOC_TROLL), LOC_PLAYER);
+    }
+
+    #[test]
+    fn test_persuade_and_intimidate() {
+        let mut world = World::new();
+        world.events.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_STRONGHOLD);
+        world.objects[LOC_BANDITS].initiative = -100;
+        world.objects[LOC_BANDITS].specials.clear();
+
+        let result = world.update_state(&parse("talk bandits".to_string()));
+        assert!(result.ends_with("You could try to <persuade bandits> or <intimidate bandits>.\n"));
+        assert!(world.combat.is_none());
+
+        // A failed attempt starts the fight
+        if let Some(talk) = world.objects[LOC_BANDITS].talk.as_mut() {
+            talk.intimidate = Some(100);
+        }
+        let result = world.update_state(&parse("intimidate bandits".to_string()));
+        assert!(result.starts_with("Your threats don't scare the Bandits.\n"));
+        assert!(world.combat.is_some());
+        world.combat = None;
+
+        world.karma = 100;
+        let result = world.update_state(&parse("persuade bandits".to_string()));
+        assert_eq!(
+            result,
+            "You talk the Bandits round. They put away their weapons and leave in peace.\n"
+        );
+        assert_eq!(world.objects[LOC_BANDITS].location, None);
+        assert!(world.flags.contains("persuaded:bandits"));
+        assert!(world.flags.contains("defeated:bandits"));
+        assert!(world.objects[LOC_BANDITS].calmed);
+    }
+
+    #[test]
+    fn test_schedules() {
+        let mut world = World::new();
+        let merchant = index_of(&world, "Merchant");
+        world.events.clear();
+        world.reactions.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        assert!(!world.is_night());
+
+        // Nobody keeps a routine before they are in the world
+        world.tick();
+        assert_eq!(world.objects[merchant].location, None);
+
+        world.objects[merchant].location = Some(LOC_TAVERN);
+        let output = world.tick();
+        assert_eq!(world.objects[merchant].location, Some(LOC_VILLAGE));
+        assert!(output.contains("The Merchant arrives.\n"));
+
+        // The merchant heads for the tavern at nightfall
+        world.turn = 9;
+        assert_eq!(
+            world.do_time(),
+            "It is day, hour 17. Night falls next turn.\n"
+        );
+        let output = world.tick();
+        assert!(world.is_night());
+        assert!(output.contains("The Merchant leaves for the Tavern.\n"));
+        assert_eq!(world.objects[merchant].location, Some(LOC_TAVERN));
+        assert_eq!(
+            world.do_time(),
+            "It is night, hour 18. Dawn comes in 12 turns.\n"
+        );
+    }
+
+    #[test]
+    fn test_bestiary() {
+        let mut world = World::new();
+        world.events.clear();
+        assert_eq!(
+            world.update_state(&parse("bestiary".to_string())),
+            "Your bestiary is empty. Enemies you come across are written in it.\n"
+        );
+
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.update_state(&parse("look".to_string()));
+        let result = world.update_state(&parse("bestiary".to_string()));
+        assert_eq!(
+            result,
+            "Bestiary:\n- Troll: A troll (enemy)\n  Fight it to learn more.\n"
+        );
+
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_TROLL].initiative = -100;
+        world.objects[LOC_TROLL].dodge = 0;
+        world.objects[LOC_TROLL].specials.clear();
+        world.update_state(&parse("attack troll".to_string()));
+        let result = world.update_state(&parse("bestiary".to_string()));
+        assert!(result.contains("  Health: 100. Attack: 20"));
+        assert!(result.contains("Resists: pierce."));
+        assert!(result.ends_with("  Defeat it to learn more.\n"));
+
+        world.objects[LOC_TROLL].health = Some(1);
+        world.update_state(&parse("attack troll".to_string()));
+        assert!(world.combat.is_none());
+        let result = world.update_state(&parse("bestiary".to_string()));
+        assert!(result.contains("  Defeated: 1.\n  Trolls have hide like bark"));
+        assert_eq!(world.bestiary[&LOC_TROLL].defeated, 1);
+    }
+
+    #[test]
+    fn test_story_chapters() {
+        let mut world = World::new();
+        let key = index_of(&world, "Iron Key");
+        world.events.clear();
+        world.objects[LOC_BEAR].location = None;
+        assert!(world.do_journal().starts_with("Chapter 1: The Iron Key\n"));
+
+        // Holding the key completes the first chapter and begins the next
+        world.objects[key].location = Some(LOC_PLAYER);
+        let output = world.tick();
+        assert!(output
+            .contains("Chapter 1 complete: The Iron Key.\n\nChapter 2: Storm the Stronghold\n"));
+        assert!(world.flags.contains("chapter:1"));
+        assert_eq!(world.variable("renown"), 1);
+
+        // The Stronghold is described differently once the bandits are gone
+        world.set_flag("defeated:bandits".to_string());
+        let output = world.tick();
+        assert!(output.contains("Chapter 3: The Final Choice\n"));
+        assert!(world.objects[LOC_STRONGHOLD]
+            .description
+            .contains("stand empty"));
+
+        // The villagers offer the final choice
+        let villagers = index_of(&world, "Villagers");
+        world.objects[villagers].location = world.objects[LOC_PLAYER].location;
+        let result = world.update_state(&parse("talk villagers".to_string()));
+        assert!(result.contains("1. Give it to the village."));
+        let result = world.update_state(&parse("1".to_string()));
+        assert!(result.contains("Chapter 3 complete: The Final Choice.\n"));
+        assert!(result.contains("The story is over, but the world goes on.\n"));
+        assert_eq!(world.variable("renown"), 4);
+        assert!(world.objects[LOC_STRONGHOLD]
+            .description
+            .contains("Villagers have moved in"));
+        assert_eq!(world.describe_chapter(), "");
+    }
+
+    #[test]
+    fn test_tame_bear() {
+        let mut world = World::new();
+        let apple = index_of(&world, "Apple");
+        let bones = index_of(&world, "Bones");
+        world.events.clear();
+        world.chapters.clear();
+        world.objects[LOC_BEAR].location = Some(LOC_FOREST);
+        world.objects[LOC_BEAR].roams.clear();
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        world.objects[bones].location = Some(LOC_PLAYER);
+
+        let result = world.update_state(&parse("feed bear".to_string()));
+        assert_eq!(
+            result,
+            "You have nothing the Bear wants to eat. It is fond of the Apple.\n"
+        );
+        let result = world.update_state(&parse("feed bear with bones".to_string()));
+        assert_eq!(result, "The Bear sniffs the Bones and turns away.\n");
+
+        world.objects[apple].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("feed apple to bear".to_string()));
+        assert_eq!(
+            result,
+            "The Bear gulps down the Apple and eyes you for more. (1/3)\n"
+        );
+        assert!(!world.objects[LOC_BEAR].asleep);
+        world.update_state(&parse("feed bear".to_string()));
+        let result = world.update_state(&parse("feed bear".to_string()));
+        assert_eq!(
+            result,
+            "The Bear gulps down the Apple and nuzzles your hand. It follows you now.\n"
+        );
+        assert_eq!(world.count(apple), 2);
+        assert!(!world.objects[LOC_BEAR].enemy);
+        assert!(world.flags.contains("tamed:bear"));
+        assert_eq!(world.party(), vec![LOC_PLAYER, LOC_BEAR]);
+
+        // The bear follows the player and can't be attacked any more
+        world.update_state(&parse("go north".to_string()));
+        assert_eq!(world.objects[LOC_BEAR].location, Some(LOC_TAVERN));
+        let result = world.update_state(&parse("attack bear".to_string()));
+        assert_eq!(result, "The Bear has done nothing to deserve that.\n");
+    }
+
+    #[test]
+    fn test_attributes() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        world.objects[LOC_PLAYER].strength = Some(12);
+        world.objects[LOC_PLAYER].agility = Some(10);
+        world.objects[LOC_PLAYER].intellect = Some(9);
+        world.attribute_points = 1;
+
+        let result = world.update_state(&parse("attributes".to_string()));
+        assert_eq!(
+            result,
+            "strength: 12 (+1)\nagility: 10 (+0)\nintellect: 9 (+0)\n\
+             Level 1, 0/100 experience to the next.\n\
+             You have 1 attribute points to spend. Try <raise strength>.\n"
+        );
+        assert_eq!(world.attack_bonus(LOC_PLAYER), 1);
+        assert_eq!(world.dodge_bonus(LOC_PLAYER), 0);
+
+        let result = world.update_state(&parse("raise luck".to_string()));
+        assert_eq!(
+            result,
+            "Raise which attribute? Try strength, agility or intellect.\n"
+        );
+        let result = world.update_state(&parse("raise agility".to_string()));
+        assert_eq!(
+            result,
+            "Your agility rises to 11. You have 0 attribute points left.\n"
+        );
+        world.objects[LOC_PLAYER].agility = Some(14);
+        assert_eq!(world.dodge_bonus(LOC_PLAYER), 6);
+        let result = world.update_state(&parse("raise strength".to_string()));
+        assert_eq!(result, "You have no attribute points to spend.\n");
+
+        // Levelling up hands out more points
+        assert_eq!(world.gain_experience(40), "");
+        assert_eq!(
+            world.gain_experience(70),
+            "You reached level 2! You have 2 attribute points to spend. Try <raise strength>.\n\
+             You can pick a perk too. Enter <perks> to see them.\n"
+        );
+        assert_eq!(world.level(), 2);
+        assert_eq!(world.attribute_points, 2);
+    }
+
+    #[test]
+    fn test_character_classes() {
+        let mut world = World::new();
+        let bow = index_of(&world, "Hunting Bow");
+        world.events.clear();
+        world.chapters.clear();
+
+        let result = world.update_state(&parse("class".to_string()));
+        assert!(result.starts_with("Choose your class with <class name>:\n- Warrior:"));
+        assert!(result.contains("Starts with the Hunting Bow."));
+        let result = world.update_state(&parse("class ranger".to_string()));
+        assert_eq!(
+            result,
+            "You are a Ranger. Sure Shot: you never miss with a bow.\n\
+             You start with the Hunting Bow.\n"
+        );
+        assert_eq!(world.objects[bow].location, Some(LOC_PLAYER));
+        assert_eq!(world.attribute(Attribute::Agility), 14);
+        assert!(world.has_perk(Perk::SureShot));
+        let result = world.update_state(&parse("class warrior".to_string()));
+        assert_eq!(result, "You are a Ranger.\n");
+
+        // A ranger can't miss with a bow, however clumsy
+        world.objects[LOC_PLAYER].accuracy = -200;
+        for _ in 0..5 {
+            world.objects[LOC_TROLL].health = Some(100);
+            let result = world.resolve_damage(LOC_PLAYER, Some(bow), LOC_TROLL);
+            assert_ne!(result.outcome, Outcome::Miss);
+        }
+
+        // A warrior shrugs off some of every blow
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let defense = world.defense_bonus(LOC_PLAYER);
+        world.update_state(&parse("class warrior".to_string()));
+        assert_eq!(world.defense_bonus(LOC_PLAYER), defense + 2);
+
+        // Once the journey has begun it is too late to pick one
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        world.update_state(&parse("look".to_string()));
+        let result = world.update_state(&parse("class scholar".to_string()));
+        assert_eq!(
+            result,
+            "It's too late to choose a class now that your journey has begun.\n"
+        );
+        assert_eq!(world.describe_classes(), "");
+    }
+
+    #[test]
+    fn test_perks() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        world.update_state(&parse("class ranger".to_string()));
+
+        let result = world.update_state(&parse("perks".to_string()));
+        assert_eq!(
+            result,
+            "Your perks:\n- Sure Shot: you never miss with a bow\n\
+             Perks you can pick:\n- Thick Skin: +10 max health\n\
+             - Scavenger: +20% chance of finding loot on enemies\n\
+             - Light Step: +3 to sneaking\n\
+             You get to pick one every level.\n"
+        );
+        let result = world.update_state(&parse("perk thick skin".to_string()));
+        assert_eq!(
+            result,
+            "You have no perks to pick. You get to pick one every level.\n"
+        );
+
+        // Levelling up earns a pick
+        world.gain_experience(100);
+        assert_eq!(world.perk_points, 1);
+        let result = world.update_state(&parse("perk thick skin".to_string()));
+        assert_eq!(result, "You pick Thick Skin: +10 max health.\n");
+        assert!(world.has_perk(Perk::ThickSkin));
+        assert_eq!(world.max_health(), 110);
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(110));
+        let result = world.update_state(&parse("perk thick skin".to_string()));
+        assert_eq!(result, "You already have Thick Skin.\n");
+
+        // Healing tops out at the new maximum
+        let apple = index_of(&world, "Apple");
+        world.objects[apple].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].health = Some(105);
+        world.update_state(&parse("eat apple".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(110));
+    }
+
+    #[test]
+    fn test_character_creation() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+
+        // Choices that don't work are turned down without changing anything
+        let mut choices = CharacterChoices {
+            name: "  ".to_string(),
+            class: Some("Scholar".to_string()),
+            raises: vec![Attribute::Strength],
+        };
+        assert_eq!(
+            world.create_character(&choices),
+            Err("Your character needs a name.".to_string())
+        );
+        choices.name = "Aldric".to_string();
+        choices.class = Some("Bard".to_string());
+        assert_eq!(
+            world.create_character(&choices),
+            Err("There is no class called Bard.".to_string())
+        );
+        choices.class = Some("scholar".to_string());
+        choices.raises = vec![Attribute::Strength; 4];
+        assert_eq!(
+            world.create_character(&choices),
+            Err("You only have 3 attribute points to spend.".to_string())
+        );
+        assert_eq!(world.objects[LOC_PLAYER].name, None);
+        assert_eq!(world.objects[LOC_PLAYER].class, None);
+
+        choices.raises = vec![Attribute::Strength, Attribute::Intellect];
+        let result = world.create_character(&choices).unwrap();
+        assert!(result.starts_with("You are a Scholar."));
+        assert!(result.ends_with(
+            "Your intellect rises to 15. You have 1 attribute points left.\nWelcome, Aldric.\n"
+        ));
+        assert_eq!(world.objects[LOC_PLAYER].name, Some("Aldric".to_string()));
+        assert_eq!(world.attribute(Attribute::Strength), 9);
+        assert_eq!(world.attribute_points, 1);
+        assert_eq!(
+            world.create_character(&choices),
+            Err("The character has already been created.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_character_sheet() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        world
+            .create_character(&CharacterChoices {
+                name: "Mira".to_string(),
+                class: Some("Ranger".to_string()),
+                raises: vec![Attribute::Intellect],
+            })
+            .unwrap();
+        world.update_state(&parse("equip hunting bow".to_string()));
+        world.objects[LOC_PLAYER].health = Some(80);
+        world.karma = 2;
+
+        let result = world.update_state(&parse("stats".to_string()));
+        assert_eq!(
+            result,
+            "Mira, level 1 Ranger\nhealth: 80/100\nstamina: 50/50\n\
+             strength: 10 (+0)\nagility: 14 (+2)\nintellect: 10 (+0)\n\
+             weapon: Hunting Bow\narmor: nothing\ntrinket: nothing\nnoise: quiet\n\
+             perks: Sure Shot\neffects: none\ngold: 20, valuables: 0\n\
+             karma: 2, reputation: 0\n\
+             Level 1, 0/100 experience to the next.\n\
+             You have 2 attribute points to spend. Try <raise strength>.\n"
+        );
+    }
+
+    #[test]
+    fn test_skill_checks() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let passage = |world: &World, location: usize| {
+            world
+                .objects
+                .iter()
+                .position(|object| object.label[0] == "Up" && object.location == Some(location))
+                .unwrap()
+        };
+        let stairs = passage(&world, LOC_TAVERN);
+        let crack = passage(&world, LOC_CAVE);
+
+        let check = SkillCheck {
+            attribute: Attribute::Agility,
+            roll: 12,
+            bonus: 2,
+            difficulty: 15,
+        };
+        assert!(!check.passed());
+        assert_eq!(
+            check.to_string(),
+            "agility check: rolled 12 +2 = 14 against 15, failed"
+        );
+        world.objects[LOC_PLAYER].agility = Some(14);
+        let check = world.skill_check(Attribute::Agility, 1, 10);
+        assert_eq!(check.bonus, 3);
+        assert_eq!(check.passed(), check.roll + 3 >= 10);
+
+        // Picking a lock, with the rolls shown
+        let result = world.update_state(&parse("rolls on".to_string()));
+        assert_eq!(result, "The rolls behind checks will be shown.\n");
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[stairs].pick_difficulty = Some(100);
+        let result = world.update_state(&parse("pick lock up".to_string()));
+        assert!(result.starts_with(
+            "Your pick slips and the lock on the way up holds. (agility check: rolled "
+        ));
+        world.objects[stairs].pick_difficulty = Some(-100);
+        let result = world.update_state(&parse("lockpick up".to_string()));
+        assert!(result.starts_with("You pick the lock on the way up. (agility check: "));
+        assert_eq!(world.objects[stairs].locked_by, None);
+
+        // Climbing out of the cave
+        world.objects[LOC_PLAYER].location = Some(LOC_CAVE);
+        world.objects[crack].hidden = false;
+        world.objects[crack].climb = Some(100);
+        let result = world.update_state(&parse("go up".to_string()));
+        assert!(result.starts_with("You lose your grip and slide back down. (strength check: "));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_CAVE));
+        world.update_state(&parse("rolls off".to_string()));
+        world.objects[crack].climb = Some(-100);
+        let result = world.update_state(&parse("go up".to_string()));
+        assert!(result.starts_with("You make the climb.\nOK.\n"));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_FOREST));
+    }
+
+    #[test]
+    fn test_status_effects() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        world.objects[LOC_PLAYER].health = Some(50);
+
+        // Doses of the same effect stack up and hurt more
+        world.stack_effect(LOC_PLAYER, StatusEffect::poisoned(2, 3));
+        world.stack_effect(LOC_PLAYER, StatusEffect::poisoned(2, 3));
+        assert!(world
+            .do_stats()
+            .contains("Poisoned x2 (-4 health per turn, 3 turns)"));
+        let result = world.tick();
+        assert!(result.contains("You lose 4 health."));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(46));
+
+        // Regeneration heals every turn, but never past the player's most health
+        world.objects[LOC_PLAYER].effects.clear();
+        world.add_effect(LOC_PLAYER, StatusEffect::regenerating(5, 3));
+        let result = world.tick();
+        assert!(result.contains("You regain 5 health. Your health: 51"));
+        world.objects[LOC_PLAYER].health = Some(world.max_health() - 1);
+        world.tick();
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(world.max_health()));
+        world.objects[LOC_PLAYER].effects.clear();
+
+        // A blow from the Thug can stun, and a stunned player loses their turn
+        let thug = index_of(&world, "Thug");
+        world.objects[thug].location = world.objects[LOC_TROLL].location;
+        world.objects[thug].specials[0].chance = 100;
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_PLAYER].dodge = -100;
+        world.objects[LOC_PLAYER].initiative = 100;
+        world.objects[LOC_TROLL].attack = Some(0);
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.update_state(&parse("attack troll".to_string()));
+        let result = world.update_state(&parse("attack".to_string()));
+        assert!(result.contains("You are stunned."));
+        let health = world.objects[LOC_TROLL].health;
+        let result = world.update_state(&parse("attack".to_string()));
+        assert!(result.contains("You are stunned and can't act!"));
+        assert_eq!(world.objects[LOC_TROLL].health, health);
+
+        // Stunned enemies lose their turns too
+        world.objects[LOC_PLAYER].effects.clear();
+        world.add_effect(thug, StatusEffect::stunned(3));
+        let result = world.update_state(&parse("attack".to_string()));
+        assert!(result.contains("The Thug is stunned and can't act."));
+        assert!(!result.contains("You are stunned."));
+    }
+
+    #[test]
+    fn test_survival() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let apple = index_of(&world, "Apple");
+        world.objects[apple].location = Some(LOC_PLAYER);
+
+        // Without survival nobody gets hungry
+        world.tick();
+        assert_eq!((world.hunger, world.thirst), (0, 0));
+        assert!(!world.do_stats().contains("food:"));
+
+        let result = world.update_state(&parse("survival on".to_string()));
+        assert!(result.contains("You will need to eat and drink"));
+        assert_eq!(world.turn, 1);
+        world.hunger = 74;
+        world.thirst = 74;
+        let strength = world.attribute_bonus(Attribute::Strength);
+        let agility = world.attribute_bonus(Attribute::Agility);
+
+        // Running low warns the player and weakens them
+        world.turn = 9;
+        let result = world.tick();
+        assert!(result.contains("You are getting hungry."));
+        assert!(result.contains("You are getting thirsty."));
+        assert!(world.do_stats().contains("food: 25/100, water: 25/100"));
+        assert_eq!(world.attribute_bonus(Attribute::Strength), strength - 2);
+        assert_eq!(world.attribute_bonus(Attribute::Agility), agility - 2);
+        world.thirst = 100;
+        assert_eq!(world.attribute_bonus(Attribute::Agility), agility - 4);
+
+        // Food fills the player back up, even at full health
+        let result = world.update_state(&parse("eat apple".to_string()));
+        assert!(result.contains("You feel less hungry."));
+        assert!(result.contains("You feel less thirsty."));
+        assert_eq!(world.attribute_bonus(Attribute::Strength), strength);
+
+        world.update_state(&parse("survival off".to_string()));
+        assert_eq!(world.attribute_bonus(Attribute::Agility), agility);
+    }
+
+    #[test]
+    fn test_resting() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let wolf = index_of(&world, "Wolf");
+        assert!(world.encounters.contains(&wolf));
+
+        // Nobody disturbs a rest in the Tavern
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_PLAYER].health = Some(45);
+        let result = world.update_state(&parse("rest".to_string()));
+        assert!(result.starts_with("You settle down for a safe rest."));
+        assert!(result.contains("You get up rested. Your health is 100."));
+        assert_eq!(world.turn, 6);
+
+        // Not with an enemy about
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].health = Some(50);
+        let result = world.update_state(&parse("sleep".to_string()));
+        assert!(result.starts_with("You can't rest with the Troll about."));
+
+        // Resting out in the open may bring a wolf down on the player
+        let interrupted = (0..50).any(|seed| {
+            let mut world = World::new();
+            world.events.clear();
+            world.chapters.clear();
+            world.seed = seed;
+            world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+            world.objects[LOC_PLAYER].health = Some(20);
+            let result = world.update_state(&parse("rest".to_string()));
+            assert!(result.starts_with("You settle down to rest, keeping one eye open."));
+            result.contains("A Wolf comes upon you in your sleep!")
+                && world.fighting_enemies() == vec![wolf]
+        });
+        assert!(interrupted);
+    }
+
+    #[test]
+    fn test_stamina() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        assert_eq!(world.objects[LOC_PLAYER].stamina, Some(50));
+
+        // Walking costs stamina, and a tired player has to catch their breath
+        world.objects[LOC_PLAYER].stamina = Some(1);
+        let result = world.update_state(&parse("n".to_string()));
+        assert!(result.starts_with("You are too tired to go on."));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_FOREST));
+        let result = world.update_state(&parse("wait".to_string()));
+        assert!(result.contains("Your stamina: 16"));
+        world.update_state(&parse("n".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+        assert_eq!(world.objects[LOC_PLAYER].stamina, Some(14));
+
+        // Attacks cost stamina, and a winded player strikes at half strength
+        world.objects[LOC_PLAYER].location = world.objects[LOC_TROLL].location;
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].critical = -100;
+        world.objects[LOC_PLAYER].min_attack = Some(8);
+        world.objects[LOC_PLAYER].attack = Some(8);
+        world.objects[LOC_TROLL].attack = Some(0);
+        world.objects[LOC_TROLL].dodge = -100;
+        world.objects[LOC_TROLL].specials.clear();
+        world.objects[LOC_TROLL].resists.clear();
+        world.objects[LOC_BEAR].location = None;
+        world.update_state(&parse("attack troll".to_string()));
+        world.objects[LOC_PLAYER].stamina = Some(5);
+        let health = world.objects[LOC_TROLL].health.unwrap();
+        let result = world.update_state(&parse("use fists".to_string()));
+        assert!(result.contains("You are out of breath."));
+        let full = health - world.objects[LOC_TROLL].health.unwrap();
+        let health = world.objects[LOC_TROLL].health.unwrap();
+        world.update_state(&parse("use fists".to_string()));
+        assert_eq!(health - world.objects[LOC_TROLL].health.unwrap(), full / 2);
+
+        // Too winded to run, but bracing for a blow gives some breath back
+        let result = world.update_state(&parse("run".to_string()));
+        assert!(result.starts_with("You are too winded to run!"));
+        world.update_state(&parse("defend".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].stamina, Some(5));
+    }
+
+    #[test]
+    fn test_diseases() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let (bats, herbs) = (index_of(&world, "Bats"), index_of(&world, "Herbs"));
+
+        // Bats only come upon a player resting in the Cave, and their bites
+        // can pass on Cave Fever
+        let disease = world.objects[bats].disease.clone().unwrap();
+        assert_eq!(disease.name, "Cave Fever");
+        assert_eq!(world.objects[bats].roams, vec![LOC_CAVE]);
+        world.objects[bats].disease.as_mut().unwrap().chance = 100;
+        world.objects[bats].location = Some(LOC_FOREST);
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_PLAYER].dodge = -100;
+        world.update_state(&parse("attack bats".to_string()));
+        let caught = (0..20).any(|_| {
+            world.update_state(&parse("defend".to_string()));
+            !world.objects[LOC_PLAYER].diseases.is_empty()
+        });
+        assert!(caught);
+        world.combat = None;
+        world.objects[bats].location = None;
+        world.objects[LOC_PLAYER].effects.clear();
+
+        // It lies low for a while, then gets worse the longer it goes on
+        world.objects[LOC_PLAYER].diseases[0].turns = disease.incubation - 1;
+        world.objects[LOC_PLAYER].health = Some(100);
+        assert!(world.do_stats().contains("Cave Fever (not showing yet)"));
+        assert_eq!(world.tick(), "");
+        let result = world.tick();
+        assert!(result.contains("You come down with Cave Fever."));
+        assert!(result.contains("You lose 2 health."));
+        for _ in 0..disease.worsens {
+            world.tick();
+        }
+        assert!(world.do_stats().contains("Cave Fever (-3 health per turn)"));
+
+        // The right remedy cures it, even at full health
+        world.objects[herbs].location = Some(LOC_PLAYER);
+        world.objects[herbs].hidden = false;
+        world.objects[LOC_PLAYER].health = Some(world.max_health());
+        let result = world.update_state(&parse("eat herbs".to_string()));
+        assert!(result.contains("You are cured of Cave Fever."));
+        assert!(world.objects[LOC_PLAYER].diseases.is_empty());
+
+        // So does a good rest in the Tavern
+        world.objects[LOC_PLAYER].diseases.push(disease);
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        let result = world.update_state(&parse("rest".to_string()));
+        assert!(result.contains("You are cured of Cave Fever."));
+        assert!(world.objects[LOC_PLAYER].diseases.is_empty());
+    }
+
+    #[test]
+    fn test_timed_effects() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        for enemy in [
+            LOC_BANDITS,
+            index_of(&world, "Lookout"),
+            index_of(&world, "Brute"),
+        ] {
+            world.objects[enemy].location = None;
+        }
+        let key = index_of(&world, "Iron Key");
+        world.objects[key].location = Some(LOC_PLAYER);
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        let attack = world.attack_bonus(LOC_PLAYER);
+
+        // The cold over the Stronghold lasts while the player stays
+        let result = world.update_state(&parse("n".to_string()));
+        assert!(result.contains("You are Chilled (-5 attack, 10 turns)."));
+        assert_eq!(world.attack_bonus(LOC_PLAYER), attack - 5);
+        world.update_state(&parse("look".to_string()));
+        assert!(world
+            .do_stats()
+            .contains("effects: Chilled (-5 attack, 10 turns)"));
+
+        // and wears off some turns after they leave
+        world.update_state(&parse("s".to_string()));
+        assert!(!world.do_stats().contains("10 turns"));
+        let worn_off = (0..10).any(|_| world.tick().contains("Your Chilled wears off."));
+        assert!(worn_off);
+        assert_eq!(world.attack_bonus(LOC_PLAYER), attack);
+
+        // Enemies' special moves can weaken the player for a while too
+        let assassin = index_of(&world, "Assassin");
+        world.objects[assassin].location = Some(LOC_VILLAGE);
+        world.objects[assassin].specials[0].chance = 100;
+        world.objects[assassin].min_attack = Some(15);
+        world.objects[LOC_PLAYER].health = Some(10_000);
+        world.objects[LOC_PLAYER].dodge = -100;
+        world.objects[LOC_PLAYER].initiative = 100;
+        world.update_state(&parse("attack assassin".to_string()));
+        let result = world.update_state(&parse("defend".to_string()));
+        assert!(result.contains("You are hamstrung."));
+        assert!(world.do_stats().contains("Hamstrung (-15 dodge, 3 turns)"));
+    }
+
+    #[test]
+    fn test_weather() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let (torch, lantern) = (index_of(&world, "Torch"), index_of(&world, "Lantern"));
+        assert_eq!(world.weather, Weather::Clear);
+        assert!(!world.do_look("").contains("Rain"));
+
+        // Rain shows outdoors and puts out the torch, but not the lantern
+        world.weather = Weather::Rain;
+        assert!(world.do_look("").contains("Rain is falling steadily."));
+        world.objects[torch].location = Some(LOC_PLAYER);
+        world.objects[lantern].location = Some(LOC_PLAYER);
+        world.objects[torch].lit = true;
+        world.objects[lantern].lit = true;
+        let result = world.update_state(&parse("wait".to_string()));
+        assert!(result.contains("The rain puts out the Torch."));
+        assert!(!world.objects[torch].lit);
+        assert!(world.objects[lantern].lit);
+        let result = world.update_state(&parse("light torch".to_string()));
+        assert!(result.starts_with("The rain would put the Torch straight out."));
+
+        // Snow drifts in the Forest tire the player out
+        world.weather = Weather::Snow;
+        world.objects[LOC_PLAYER].stamina = Some(10);
+        world.update_state(&parse("wait".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].stamina, Some(24));
+
+        // Indoors the weather can't touch the player
+        world.update_state(&parse("n".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].location, Some(LOC_TAVERN));
+        assert!(!world.do_look("").contains("Snow"));
+        world.objects[LOC_PLAYER].stamina = Some(10);
+        world.update_state(&parse("wait".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].stamina, Some(25));
+        world.weather = Weather::Rain;
+        world.objects[torch].lit = true;
+        world.update_state(&parse("wait".to_string()));
+        assert!(world.objects[torch].lit);
+    }
+
+    #[test]
+    fn test_healing_over_time() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let (potion, bandage) = (
+            index_of(&world, "Regeneration Potion"),
+            index_of(&world, "Bandage"),
+        );
+        world.objects[potion].price = None;
+        world.objects[potion].location = Some(LOC_PLAYER);
+        world.objects[bandage].price = None;
+        world.objects[bandage].location = Some(LOC_PLAYER);
+
+        // Healing over time does nothing for a player at full health
+        let result = world.update_state(&parse("drink regeneration potion".to_string()));
+        assert!(result.starts_with("You are already at full health"));
+        assert_eq!(world.objects[potion].location, Some(LOC_PLAYER));
+
+        // The potion heals a few points every turn rather than all at once
+        world.objects[LOC_PLAYER].health = Some(50);
+        let result = world.update_state(&parse("drink regeneration potion".to_string()));
+        assert!(result.contains("You are under Regenerating (+4 health per turn, 8 turns)."));
+        assert!(result.contains("You regain 4 health. Your health: 54"));
+        world.update_state(&parse("wait".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(58));
+
+        // A bandage heals alongside it, and both wear off in time
+        world.update_state(&parse("use bandage".to_string()));
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(65));
+        let output: String = (0..8).map(|_| world.tick()).collect();
+        assert!(output.contains("Your Bandaged wears off."));
+        assert!(output.contains("Your Regenerating wears off."));
+        assert!(world.objects[LOC_PLAYER].effects.is_empty());
+        assert_eq!(world.objects[LOC_PLAYER].health, Some(97));
+
+        // Enemies heal back up to the health they started with and no further
+        let lookout = index_of(&world, "Lookout");
+        world.objects[lookout].health = Some(35);
+        world.add_effect(lookout, StatusEffect::regenerating(4, 3));
+        (0..3).for_each(|_| {
+            world.tick();
+        });
+        assert_eq!(world.max_health_of(lookout), 40);
+        assert_eq!(world.objects[lookout].health, Some(40));
+    }
+
+    #[test]
+    fn test_wealth() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let coins = index_of(&world, "Gold Coins");
+        world.objects[LOC_TROLL].location = None;
+        world.objects[LOC_PLAYER].location = Some(LOC_DUNGEONS);
+        assert_eq!(
+            world.do_inventory(),
+            "You currently do not have anything in your inventory.\nYou have 20 gold.\n"
+        );
+
+        // A pile of gold goes straight into the player's purse
+        let result = world.update_state(&parse("get coins".to_string()));
+        assert!(result.ends_with("You count 15 gold. You have 35 gold now.\n"));
+        assert_eq!(world.objects[coins].location, None);
+        assert_eq!(world.objects[LOC_PLAYER].gold, 35);
+
+        // Valuables count towards the player's wealth
+        world.update_state(&parse("get goblet".to_string()));
+        assert_eq!(world.valuables_worth(), 30);
+        assert!(world
+            .do_inventory()
+            .contains("You have 35 gold.\nYour valuables are worth 30 gold.\n"));
+        assert!(world.do_stats().contains("gold: 35, valuables: 30\n"));
+
+        // Enemies drop the gold in their purse when they are defeated
+        let wolf = index_of(&world, "Wolf");
+        world.objects[wolf].location = Some(LOC_DUNGEONS);
+        world.objects[wolf].health = Some(1);
+        world.objects[wolf].dodge = -100;
+        world.objects[wolf].loot.clear();
+        world.objects[LOC_PLAYER].accuracy = 100;
+        world.objects[LOC_PLAYER].initiative = 100;
+        let result = world.update_state(&parse("attack wolf".to_string()));
+        let result = match world.combat {
+            Some(_) => world.update_state(&parse("use fists".to_string())),
+            None => result,
+        };
+        assert!(result.contains("You find 2 gold on the Wolf."));
+        assert_eq!(world.objects[LOC_PLAYER].gold, 37);
+        assert_eq!(world.objects[wolf].gold, 0);
+    }
+
+    #[test]
+    fn test_shop() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let trader = index_of(&world, "Trader");
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        world.objects[LOC_PLAYER].gold = 100;
+
+        let result = world.update_state(&parse("shop".to_string()));
+        assert!(result.starts_with("The Trader sells:\n"));
+        assert!(result.contains("    Arrows (x10) (1 gold)\n"));
+        assert!(result.contains("    Healing Draught (x3) (8 gold)\n"));
+        assert!(!result.contains("off"));
+        assert_eq!(world.turn, 0);
+
+        // A good reputation gets the player better prices
+        world.reputation = 5;
+        let result = world.update_state(&parse("shop".to_string()));
+        assert!(result.contains("    Healing Draught (x3) (7 gold)\n"));
+        assert!(result.ends_with("The Trader gives you 10% off.\n"));
+        let result = world.update_state(&parse("buy healing draught".to_string()));
+        assert!(result.starts_with("You buy the Healing Draught from the Trader for 7 gold."));
+
+        // Haggling moves the prices one way or the other, once per restock
+        let result = world.update_state(&parse("haggle".to_string()));
+        let discount = match result.contains("knocks 15% off") {
+            true => 25,
+            false => 0,
+        };
+        assert_eq!(world.trade_discount(trader), discount);
+        let result = world.update_state(&parse("haggle".to_string()));
+        assert_eq!(
+            result,
+            "The Trader won't haggle with you again until it restocks.\n"
+        );
+
+        // What the player buys up comes back when the trader restocks
+        for _ in 0..3 {
+            world.update_state(&parse("buy throwing knife".to_string()));
+        }
+        let result = world.update_state(&parse("shop".to_string()));
+        assert!(!result.contains("Throwing Knife"));
+        world.turn = 29;
+        let result = world.tick();
+        assert!(result.contains("The Trader restocks its wares."));
+        assert_eq!(world.objects[trader].haggle, None);
+        let restocked = world
+            .objects
+            .iter()
+            .position(|object| {
+                object.label[0] == "Throwing Knife" && object.location == Some(trader)
+            })
+            .unwrap();
+        assert_eq!(world.count(restocked), 3);
+        assert_eq!(world.objects[restocked].price, Some(6));
+        let result = world.update_state(&parse("shop".to_string()));
+        assert!(result.contains("    Healing Draught (x3) (7 gold)\n"));
+
+        // Selling back never pays more than buying, however good the prices
+        world.reputation = 15;
+        world.objects[trader].haggle = Some(15);
+        world.objects[trader].gold = 100;
+        let gold = world.objects[LOC_PLAYER].gold;
+        world.update_state(&parse("buy healing draught".to_string()));
+        let result = world.update_state(&parse("sell 1 healing draught".to_string()));
+        assert!(result.starts_with("You sell"));
+        assert!(world.objects[LOC_PLAYER].gold <= gold);
+
+        world.objects[LOC_PLAYER].location = Some(LOC_FOREST);
+        let result = world.update_state(&parse("shop".to_string()));
+        assert_eq!(result, "There is nobody here to trade with.\n");
+    }
+
+    #[test]
+    fn test_combine() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let (bones, branch, spear) = (
+            index_of(&world, "Bones"),
+            index_of(&world, "Branch"),
+            index_of(&world, "Crude Spear"),
+        );
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        world.update_state(&parse("get branch".to_string()));
+        let result = world.update_state(&parse("combine bones with branch".to_string()));
+        assert_eq!(result, "You are not holding any bones.\n");
+
+        // Ingredients can be named in any order and are used up
+        world.objects[bones].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("combine branch with bones".to_string()));
+        assert_eq!(result, "You make the Crude Spear from the Branch, Bones.\n");
+        assert_eq!(world.objects[spear].location, Some(LOC_PLAYER));
+        assert_eq!(world.objects[bones].location, None);
+        assert_eq!(world.objects[branch].location, None);
+
+        // Herbs make a salve on their own, as often as the player finds them
+        let herbs = index_of(&world, "Herbs");
+        for made in 1..=2 {
+            world.objects[herbs].hidden = false;
+            world.objects[herbs].location = Some(LOC_PLAYER);
+            let result = world.update_state(&parse("mix herbs".to_string()));
+            assert_eq!(result, "You make the Salve from the Herbs.\n");
+            let salve = world
+                .objects
+                .iter()
+                .position(|object| {
+                    object.label[0] == "Salve" && object.location == Some(LOC_PLAYER)
+                })
+                .unwrap();
+            assert_eq!(world.count(salve), made);
+        }
+
+        // Crafting by name takes some split off a stack just as well
+        world.objects[herbs].location = Some(LOC_VILLAGE);
+        world.objects[herbs].quantity = Some(2);
+        world.update_state(&parse("get 1 herbs".to_string()));
+        assert_eq!(world.objects[herbs].location, Some(LOC_VILLAGE));
+        let result = world.update_state(&parse("craft salve".to_string()));
+        assert_eq!(result, "You make the Salve from the Herbs.\n");
+        assert_eq!(world.count(herbs), 1);
+
+        // Only what a recipe names makes anything
+        let shovel = index_of(&world, "Shovel");
+        world.objects[shovel].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("combine shovel".to_string()));
+        assert_eq!(result, "You can't make anything from the Shovel alone.\n");
+        let result = world.update_state(&parse("combine shovel and crude spear".to_string()));
+        assert_eq!(
+            result,
+            "The Shovel and the Crude Spear don't make anything together.\n"
+        );
+        let result = world.update_state(&parse("combine".to_string()));
+        assert!(result.starts_with("Combine what?"));
+    }
+
+    #[test]
+    fn test_brewing() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let (herbs, mushrooms) = (index_of(&world, "Herbs"), index_of(&world, "Mushrooms"));
+        let result = world.update_state(&parse("brew".to_string()));
+        assert!(result.starts_with("You know how to brew:\n"));
+        assert!(result.contains("Regeneration Potion (Herbs, Mushrooms)"));
+
+        // Brewing takes a fire and every ingredient
+        world.objects[mushrooms].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("brew healing draught".to_string()));
+        assert!(result.starts_with("There is no fire here"));
+        world.objects[LOC_PLAYER].location = Some(LOC_TAVERN);
+        let result = world.update_state(&parse("brew regeneration potion".to_string()));
+        assert_eq!(
+            result,
+            "To brew the Regeneration Potion you need: Herbs, Mushrooms.\n"
+        );
+
+        // A sharp mind brews potent potions that do more
+        world.objects[LOC_PLAYER].intellect = Some(60);
+        let result = world.update_state(&parse("brew healing draught".to_string()));
+        assert_eq!(
+            result,
+            "You brew a Potent Healing Draught from the Mushrooms. It came out potent.\n"
+        );
+        assert_eq!(world.count(mushrooms), 2);
+        let draught = index_of(&world, "Potent Healing Draught");
+        assert_eq!(world.objects[draught].location, Some(LOC_PLAYER));
+        assert_eq!(world.objects[draught].health, Some(30));
+
+        // A botched brew comes out weak
+        let regeneration = world
+            .brews
+            .iter()
+            .position(|brew| brew.needs.contains(&herbs))
+            .unwrap();
+        world.brews[regeneration].difficulty = 100;
+        world.objects[herbs].hidden = false;
+        world.objects[herbs].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("brew regeneration potion".to_string()));
+        assert!(result.ends_with(" It came out weak.\n"));
+        assert_eq!(world.objects[herbs].location, None);
+        let potion = index_of(&world, "Weak Regeneration Potion");
+        assert_eq!(world.objects[potion].buff.as_ref().unwrap().heal, 2);
+
+        let result = world.update_state(&parse("brew gold".to_string()));
+        assert_eq!(result, "You don't know how to brew a 'gold'.\n");
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let mut world = World::new();
+        world.events.clear();
+        world.chapters.clear();
+        let (sword, whetstone, ore) = (
+            index_of(&world, "Sword"),
+            index_of(&world, "Whetstone"),
+            index_of(&world, "Iron Ore"),
+        );
+        let attack = world.objects[sword].attack.unwrap();
+        world.objects[sword].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("upgrade sword".to_string()));
+        assert!(result.starts_with("There is no forge here"));
+
+        // Every tier renames the sword and asks for more materials
+        world.objects[LOC_PLAYER].location = Some(LOC_VILLAGE);
+        world.objects[whetstone].location = Some(LOC_PLAYER);
+        world.objects[whetstone].quantity = Some(4);
+        let result = world.update_state(&parse("upgrade sword".to_string()));
+        assert_eq!(
+            result,
+            format!(
+                "You forge the Sword into a Sharpened Sword at the forge, using 1 Whetstone. Its attack rises to {}.\n",
+                attack + 4
+            )
+        );
+        assert_eq!(world.objects[sword].label[0], "Sharpened Sword");
+        world.objects[ore].quantity = Some(1);
+        world.objects[ore].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("sharpen sword".to_string()));
+        assert_eq!(
+            result,
+            "To forge the Sharpened Sword into a Tempered Sword you need: 1 Whetstone, 2 Iron Ore.\n"
+        );
+
+        world.objects[ore].quantity = Some(6);
+        let result = world.update_state(&parse("forge sharpened sword".to_string()));
+        assert!(result.starts_with("You forge the Sharpened Sword into a Tempered Sword"));
+        assert_eq!(world.objects[sword].attack, Some(attack + 10));
+        assert_eq!(world.count(ore), 4);
+        let result = world.update_state(&parse("upgrade tempered sword".to_string()));
+        assert!(result.starts_with("You forge the Tempered Sword into a Masterwork Sword"));
+        assert_eq!(world.objects[sword].label[0], "Masterwork Sword");
+        assert_eq!(world.objects[sword].attack, Some(attack + 18));
+        assert_eq!(world.objects[ore].location, None);
+        assert_eq!(world.objects[whetstone].location, None);
+
+        let result = world.update_state(&parse("upgrade sword".to_string()));
+        assert_eq!(
+            result,
+            "The Masterwork Sword can't be improved any further.\n"
+        );
+        world.objects[whetstone].location = Some(LOC_PLAYER);
+        let result = world.update_state(&parse("upgrade whetstone".to_string()));
+        assert_eq!(result, "The Whetstone can't be upgraded.\n");
     }
 }